@@ -0,0 +1,6 @@
+--liquibase formatted sql
+
+--changeset doug:1
+ALTER TABLE gardens
+ADD COLUMN IF NOT EXISTS latitude DECIMAL(7, 4),
+ADD COLUMN IF NOT EXISTS longitude DECIMAL(7, 4);
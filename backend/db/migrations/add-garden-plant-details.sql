@@ -0,0 +1,10 @@
+--liquibase formatted sql
+
+--changeset doug:1
+-- How each plant is planted in a garden, so a garden can be a planting
+-- record.  Existing plants are one of each, still being planned.
+ALTER TABLE gardens_plants
+ADD COLUMN quantity INT NOT NULL DEFAULT 1,
+ADD COLUMN notes VARCHAR(1000) NULL,
+ADD COLUMN planted_date DATE NULL,
+ADD COLUMN status ENUM('Planned', 'Planted', 'Established', 'Lost') NOT NULL DEFAULT 'Planned';
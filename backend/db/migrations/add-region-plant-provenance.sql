@@ -0,0 +1,9 @@
+--liquibase formatted sql
+
+--changeset doug:1
+-- Why a plant is native to a region.  Existing links came from plant
+-- searches, and when they were added wasn't recorded.
+ALTER TABLE regions_plants
+ADD COLUMN source ENUM('Usda', 'StateList', 'Curator', 'Generated') NOT NULL DEFAULT 'Generated',
+ADD COLUMN confidence ENUM('Low', 'Medium', 'High') NOT NULL DEFAULT 'Low',
+ADD COLUMN added_at TIMESTAMP NULL;
@@ -0,0 +1,12 @@
+--liquibase formatted sql
+
+--changeset doug:1
+-- Parsed from the bloom, height and spread text, see plant_details.rs
+ALTER TABLE plants
+ADD COLUMN bloom_start_month TINYINT,
+ADD COLUMN bloom_end_month TINYINT,
+ADD COLUMN bloom_colors VARCHAR(100),
+ADD COLUMN height_min_inches INT,
+ADD COLUMN height_max_inches INT,
+ADD COLUMN spread_min_inches INT,
+ADD COLUMN spread_max_inches INT;
@@ -0,0 +1,6 @@
+--liquibase formatted sql
+
+--changeset doug:1
+-- The plant's symbol in the USDA PLANTS database, see usda.rs
+ALTER TABLE plants
+ADD COLUMN usda_symbol VARCHAR(20);
@@ -0,0 +1,22 @@
+--liquibase formatted sql
+
+--changeset doug:1
+-- Areas of a garden with their own conditions, ex: a shady north bed.
+-- Gardens without zones are one zone, using the garden's shade/moisture.
+CREATE TABLE IF NOT EXISTS garden_zones (
+  garden_id INT NOT NULL,
+  zone INT NOT NULL,
+  name VARCHAR(100) NOT NULL,
+  shade ENUM('None', 'Some', 'Lots') NOT NULL,
+  moisture ENUM('None', 'Some', 'Lots') NOT NULL,
+
+  PRIMARY KEY (garden_id, zone),
+  CONSTRAINT FK_GardenZonesGarden FOREIGN KEY (garden_id) REFERENCES gardens(id)
+);
+
+--changeset doug:2
+-- The zone each plant is in, the same plant may be in several.
+ALTER TABLE gardens_plants
+ADD COLUMN zone INT NOT NULL DEFAULT 0,
+DROP PRIMARY KEY,
+ADD PRIMARY KEY (garden_id, zone, plant_id);
@@ -0,0 +1,16 @@
+--liquibase formatted sql
+
+--changeset doug:1
+CREATE TABLE IF NOT EXISTS plant_changes (
+  id INT PRIMARY KEY AUTO_INCREMENT,
+  plant_id INT NOT NULL,
+  changed_by VARCHAR(100) NOT NULL,
+  action ENUM('create', 'update') NOT NULL,
+
+  -- json object of the changed fields, ex: {"birdRating": {"from": 4, "to": 5}}
+  changes TEXT NOT NULL,
+  changed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  INDEX (plant_id),
+  CONSTRAINT FK_PlantChangesPlant FOREIGN KEY (plant_id) REFERENCES plants(id)
+);
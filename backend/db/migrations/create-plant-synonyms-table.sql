@@ -0,0 +1,16 @@
+--liquibase formatted sql
+
+--changeset doug:1
+-- Other names a plant is known by.  Scientific synonyms are old or alternate
+-- names for the same species, ex: Aster novae-angliae for Symphyotrichum
+-- novae-angliae, and common names are any beyond plants.common_name.
+CREATE TABLE IF NOT EXISTS plant_synonyms (
+  plant_id INT NOT NULL,
+  name VARCHAR(100) NOT NULL,
+  kind ENUM('Scientific', 'Common') NOT NULL,
+
+  PRIMARY KEY (plant_id, kind, name),
+  INDEX (name),
+  FULLTEXT (name),
+  CONSTRAINT FK_PlantSynonymsPlant FOREIGN KEY (plant_id) REFERENCES plants(id)
+);
@@ -0,0 +1,10 @@
+--liquibase formatted sql
+
+--changeset doug:1
+CREATE TABLE IF NOT EXISTS request_counts (
+  uri VARCHAR(255),
+  date DATE,
+  count INT,
+
+  PRIMARY KEY (uri, date)
+);
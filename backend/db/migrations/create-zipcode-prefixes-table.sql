@@ -0,0 +1,16 @@
+--liquibase formatted sql
+
+--changeset doug:1
+-- The center of each three digit zip prefix, used to place zipcodes which
+-- aren't in the zipcodes table.
+CREATE TABLE IF NOT EXISTS zipcode_prefixes (
+  prefix INTEGER PRIMARY KEY,
+  latitude DECIMAL(7, 4),
+  longitude DECIMAL(7, 4)
+);
+
+INSERT INTO zipcode_prefixes (prefix, latitude, longitude)
+SELECT zipcode DIV 100, AVG(latitude), AVG(longitude)
+FROM zipcodes
+WHERE latitude IS NOT NULL AND longitude IS NOT NULL
+GROUP BY zipcode DIV 100;
@@ -0,0 +1,10 @@
+--liquibase formatted sql
+
+--changeset sqlite:1
+-- How each plant is planted in a garden, so a garden can be a planting
+-- record.  Existing plants are one of each, still being planned.
+ALTER TABLE gardens_plants ADD COLUMN quantity INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE gardens_plants ADD COLUMN notes VARCHAR(1000);
+ALTER TABLE gardens_plants ADD COLUMN planted_date DATE;
+ALTER TABLE gardens_plants ADD COLUMN status TEXT NOT NULL DEFAULT 'Planned'
+  CHECK (status IN ('Planned', 'Planted', 'Established', 'Lost'));
@@ -0,0 +1,10 @@
+--liquibase formatted sql
+
+--changeset sqlite:1
+-- Why a plant is native to a region.  Existing links came from plant
+-- searches, and when they were added wasn't recorded.
+ALTER TABLE regions_plants ADD COLUMN source TEXT NOT NULL DEFAULT 'Generated'
+  CHECK (source IN ('Usda', 'StateList', 'Curator', 'Generated'));
+ALTER TABLE regions_plants ADD COLUMN confidence TEXT NOT NULL DEFAULT 'Low'
+  CHECK (confidence IN ('Low', 'Medium', 'High'));
+ALTER TABLE regions_plants ADD COLUMN added_at TIMESTAMP;
@@ -0,0 +1,11 @@
+--liquibase formatted sql
+
+--changeset sqlite:1
+-- Parsed from the bloom, height and spread text, see plant_details.rs
+ALTER TABLE plants ADD COLUMN bloom_start_month INTEGER;
+ALTER TABLE plants ADD COLUMN bloom_end_month INTEGER;
+ALTER TABLE plants ADD COLUMN bloom_colors VARCHAR(100);
+ALTER TABLE plants ADD COLUMN height_min_inches INTEGER;
+ALTER TABLE plants ADD COLUMN height_max_inches INTEGER;
+ALTER TABLE plants ADD COLUMN spread_min_inches INTEGER;
+ALTER TABLE plants ADD COLUMN spread_max_inches INTEGER;
@@ -0,0 +1,39 @@
+--liquibase formatted sql
+
+--changeset sqlite:1
+-- Areas of a garden with their own conditions, ex: a shady north bed.
+-- Gardens without zones are one zone, using the garden's shade/moisture.
+CREATE TABLE IF NOT EXISTS garden_zones (
+  garden_id INTEGER NOT NULL REFERENCES gardens(id),
+  zone INTEGER NOT NULL,
+  name VARCHAR(100) NOT NULL,
+  shade TEXT NOT NULL CHECK (shade IN ('None', 'Some', 'Lots')),
+  moisture TEXT NOT NULL CHECK (moisture IN ('None', 'Some', 'Lots')),
+
+  PRIMARY KEY (garden_id, zone)
+);
+
+--changeset sqlite:2
+-- The zone each plant is in, the same plant may be in several.  SQLite
+-- can't change a primary key, so the table is rebuilt.
+CREATE TABLE gardens_plants_with_zones (
+  garden_id INTEGER REFERENCES gardens(id),
+  plant_id INTEGER REFERENCES plants(id),
+  zone INTEGER NOT NULL DEFAULT 0,
+  ordering INTEGER,
+  quantity INTEGER NOT NULL DEFAULT 1,
+  notes VARCHAR(1000),
+  planted_date DATE,
+  status TEXT NOT NULL DEFAULT 'Planned'
+    CHECK (status IN ('Planned', 'Planted', 'Established', 'Lost')),
+
+  PRIMARY KEY (garden_id, zone, plant_id)
+);
+
+INSERT INTO gardens_plants_with_zones
+  (garden_id, plant_id, ordering, quantity, notes, planted_date, status)
+SELECT garden_id, plant_id, ordering, quantity, notes, planted_date, status
+FROM gardens_plants;
+
+DROP TABLE gardens_plants;
+ALTER TABLE gardens_plants_with_zones RENAME TO gardens_plants;
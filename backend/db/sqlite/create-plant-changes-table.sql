@@ -0,0 +1,15 @@
+--liquibase formatted sql
+
+--changeset sqlite:1
+CREATE TABLE IF NOT EXISTS plant_changes (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  plant_id INTEGER NOT NULL REFERENCES plants(id),
+  changed_by VARCHAR(100) NOT NULL,
+  action TEXT NOT NULL CHECK (action IN ('create', 'update')),
+
+  -- json object of the changed fields, ex: {"birdRating": {"from": 4, "to": 5}}
+  changes TEXT NOT NULL,
+  changed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS plant_changes_plant_id ON plant_changes (plant_id);
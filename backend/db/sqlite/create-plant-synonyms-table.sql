@@ -0,0 +1,15 @@
+--liquibase formatted sql
+
+--changeset sqlite:1
+-- Other names a plant is known by.  Scientific synonyms are old or alternate
+-- names for the same species, ex: Aster novae-angliae for Symphyotrichum
+-- novae-angliae, and common names are any beyond plants.common_name.
+CREATE TABLE IF NOT EXISTS plant_synonyms (
+  plant_id INTEGER NOT NULL REFERENCES plants(id),
+  name VARCHAR(100) NOT NULL,
+  kind TEXT NOT NULL CHECK (kind IN ('Scientific', 'Common')),
+
+  PRIMARY KEY (plant_id, kind, name)
+);
+
+CREATE INDEX IF NOT EXISTS plant_synonyms_name ON plant_synonyms (name);
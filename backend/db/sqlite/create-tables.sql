@@ -0,0 +1,116 @@
+--liquibase formatted sql
+
+-- SQLite equivalent of the tables created by the MySQL migrations.
+-- MySQL ENUM/SET columns are stored as TEXT, using the same values.
+
+--changeset sqlite:1
+CREATE TABLE IF NOT EXISTS regions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name VARCHAR(100),
+  latitude DECIMAL(7, 4),
+  longitude DECIMAL(7, 4),
+  radius_miles INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS zipcodes (
+  zipcode INTEGER PRIMARY KEY,
+  latitude DECIMAL(7, 4),
+  longitude DECIMAL(7, 4),
+  region_id INTEGER REFERENCES regions(id)
+);
+
+CREATE TABLE IF NOT EXISTS nurseries (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name VARCHAR(255),
+  url VARCHAR(255),
+  address VARCHAR(255),
+  city VARCHAR(50),
+  state VARCHAR(2),
+  zipcode INTEGER,
+  latitude DECIMAL(7,4),
+  longitude DECIMAL(7,4)
+);
+
+CREATE TABLE IF NOT EXISTS zipcodes_nurseries (
+  zipcode INTEGER REFERENCES zipcodes(zipcode),
+  nursery_id INTEGER REFERENCES nurseries(id),
+  miles INTEGER,
+  PRIMARY KEY (zipcode, nursery_id)
+);
+
+CREATE TABLE IF NOT EXISTS images (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  title VARCHAR(200),
+  card_url VARCHAR(100),
+  original_url VARCHAR(100),
+  author VARCHAR(100),
+  license VARCHAR(50)
+);
+
+CREATE TABLE IF NOT EXISTS plants (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  scientific_name VARCHAR(100) UNIQUE,
+  common_name VARCHAR(100),
+  bloom VARCHAR(100),
+  height VARCHAR(50),
+  spread VARCHAR(50),
+
+  -- comma separated, ex: "Some,Lots"
+  moistures TEXT,
+  shades TEXT,
+
+  pollinator_rating INTEGER,
+  bird_rating INTEGER,
+  spread_rating INTEGER,
+  deer_resistance_rating INTEGER,
+
+  usda_source VARCHAR(100),
+  wiki_source VARCHAR(100),
+
+  image_id INTEGER REFERENCES images(id)
+);
+
+CREATE TABLE IF NOT EXISTS queries (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  region_id INTEGER REFERENCES regions(id),
+  shade TEXT CHECK (shade IN ('None', 'Some', 'Lots')),
+  moisture TEXT CHECK (moisture IN ('None', 'Some', 'Lots')),
+  count INTEGER,
+
+  UNIQUE (region_id, shade, moisture)
+);
+
+CREATE TABLE IF NOT EXISTS regions_plants (
+  region_id INTEGER REFERENCES regions(id),
+  plant_id INTEGER REFERENCES plants(id),
+
+  PRIMARY KEY (region_id, plant_id)
+);
+
+CREATE TABLE IF NOT EXISTS gardens (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  read_id VARCHAR(25) UNIQUE,
+  write_id VARCHAR(50) UNIQUE,
+  name VARCHAR(255),
+  shade TEXT CHECK (shade IN ('None', 'Some', 'Lots')),
+  moisture TEXT CHECK (moisture IN ('None', 'Some', 'Lots')),
+  zipcode INTEGER,
+  latitude DECIMAL(7, 4),
+  longitude DECIMAL(7, 4)
+);
+
+CREATE TABLE IF NOT EXISTS gardens_plants (
+  garden_id INTEGER REFERENCES gardens(id),
+  plant_id INTEGER REFERENCES plants(id),
+  ordering INTEGER,
+
+  PRIMARY KEY (garden_id, plant_id)
+);
+
+CREATE TABLE IF NOT EXISTS request_counts (
+  uri VARCHAR(255),
+  date DATE,
+  count INTEGER,
+
+  PRIMARY KEY (uri, date)
+);
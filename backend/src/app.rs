@@ -2,10 +2,15 @@
 use crate::database::Database;
 
 use crate::{
+    auth::ApiTokens,
     controllers::{
+        admin::{
+            create_plant_handler, patch_plant_handler, replace_plant_handler, AdminController,
+        },
+        export::{export_plants_handler, ExportController},
         gardens::{
-            create_garden_handler, list_garden_handler, read_garden_handler, update_garden_handler,
-            GardensController,
+            create_garden_handler, garden_calendar_handler, garden_summary_handler,
+            list_garden_handler, read_garden_handler, update_garden_handler, GardensController,
         },
         maps::{maps_api_key_handler, MapsController},
         nurseries::{fetch_nurseries_handler, NurseriesController},
@@ -13,34 +18,83 @@ use crate::{
             find_plant_handler, find_plants_handler, plants_stream_by_scientific_name_handler,
             plants_stream_handler, PlantController,
         },
+        regions::{
+            list_regions_handler, read_region_handler, region_plants_handler, RegionsController,
+        },
     },
     highlights::Highlights,
 };
 use actix_cors::Cors;
-use actix_web::{http, web, App, HttpServer};
+use actix_web::{http, rt, web, App, HttpServer};
 use mockall_double::double;
+use std::time::Duration;
+use tracing::log::{info, warn};
 
 pub struct PlantingLifeApp {
+    pub db: &'static Database,
     pub gardens_controller: GardensController,
     pub plant_controller: PlantController,
     pub nursery_controller: NurseriesController,
     pub maps_controller: MapsController,
+    pub export_controller: ExportController,
+    pub admin_controller: AdminController,
+    pub regions_controller: RegionsController,
 }
 
 impl PlantingLifeApp {
-    pub fn new(db_url: &str) -> Self {
+    pub fn new(db_url: &str, api_tokens: ApiTokens, highlights: Highlights) -> Self {
         tracing_subscriber::fmt::init();
 
         let db = live_forever(Database::new(db_url));
-        let highlights = live_forever(Highlights {});
+        let highlights = live_forever(highlights);
+        let api_tokens = live_forever(api_tokens);
         Self {
+            db,
             gardens_controller: GardensController { db, highlights },
             plant_controller: PlantController { db, highlights },
             nursery_controller: NurseriesController { db },
             maps_controller: MapsController { db },
+            export_controller: ExportController { db, api_tokens },
+            admin_controller: AdminController { db, api_tokens },
+            regions_controller: RegionsController { db, highlights },
+        }
+    }
+
+    /// Applies any pending schema changesets, logging each one.
+    pub async fn migrate(&self) -> anyhow::Result<()> {
+        let applied = self.db.migrate().await?;
+        info!("Applied {} schema changeset(s)", applied.len());
+
+        let backfilled = self.db.backfill_plant_details().await?;
+        info!("Filled in bloom, size and USDA details for {backfilled} plant(s)");
+
+        Ok(())
+    }
+
+    /// Loads every plant's names for searching by name.  If it fails, name
+    /// searches fall back to the database.
+    pub async fn load_search_index(&self) {
+        match self.db.load_search_index().await {
+            Ok(count) => info!("Indexed {count} plant(s) for name search"),
+            Err(e) => warn!("Failed to load name search index: {e}"),
         }
     }
 
+    /// Reloads the name search index every period, in the background.  Saves
+    /// in this process keep it current, but the import and migrate binaries
+    /// write to the database directly.
+    pub fn refresh_search_index(&'static self, period: Duration) {
+        rt::spawn(async move {
+            let mut interval = rt::time::interval(period);
+            // The first tick is immediate, and the index is loaded on startup
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                self.load_search_index().await;
+            }
+        });
+    }
+
     pub async fn start(&'static self) -> std::io::Result<()> {
         println!("Starting!");
         HttpServer::new(move || {
@@ -50,7 +104,8 @@ impl PlantingLifeApp {
                 .allowed_origin("https://maps.planting.life")
                 .allowed_header(http::header::CONTENT_TYPE)
                 .allowed_header(http::header::ACCEPT)
-                .allowed_methods(vec!["GET", "POST", "PUT"]);
+                .allowed_header(http::header::AUTHORIZATION)
+                .allowed_methods(vec!["GET", "POST", "PUT", "PATCH"]);
 
             // In local (debug build, not release), don't restrict origin
             // This allows localhost, but also networked locations (ex: access
@@ -68,10 +123,19 @@ impl PlantingLifeApp {
                 .service(find_plant_handler)
                 .service(fetch_nurseries_handler)
                 .service(read_garden_handler)
+                .service(garden_calendar_handler)
+                .service(garden_summary_handler)
                 .service(list_garden_handler)
                 .service(create_garden_handler)
                 .service(update_garden_handler)
                 .service(maps_api_key_handler)
+                .service(export_plants_handler)
+                .service(create_plant_handler)
+                .service(replace_plant_handler)
+                .service(patch_plant_handler)
+                .service(list_regions_handler)
+                .service(read_region_handler)
+                .service(region_plants_handler)
         })
         .bind("0.0.0.0:8080")?
         .run()
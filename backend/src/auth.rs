@@ -0,0 +1,66 @@
+use actix_web::{http::header, HttpRequest};
+use std::collections::HashMap;
+
+/// Bearer tokens which unlock the admin endpoints, each belonging to a named
+/// person so changes can be attributed to them.
+pub struct ApiTokens {
+    names_by_token: HashMap<String, String>,
+}
+
+impl ApiTokens {
+    /// Parses comma separated name:token pairs, ex: "doug:abc123,jane:def456".
+    /// Malformed pairs are ignored.
+    pub fn parse(config: &str) -> Self {
+        let names_by_token = config
+            .split(',')
+            .filter_map(|pair| pair.split_once(':'))
+            .map(|(name, token)| (name.trim(), token.trim()))
+            .filter(|(name, token)| !name.is_empty() && !token.is_empty())
+            .map(|(name, token)| (token.to_string(), name.to_string()))
+            .collect();
+
+        Self { names_by_token }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names_by_token.is_empty()
+    }
+
+    /// Returns the name of whoever sent the request, if it has an
+    /// "Authorization: Bearer <token>" header with a known token.
+    pub fn authenticate(&self, request: &HttpRequest) -> Option<&str> {
+        let authorization = request.headers().get(header::AUTHORIZATION)?;
+        let token = authorization.to_str().ok()?.strip_prefix("Bearer ")?;
+
+        self.names_by_token.get(token.trim()).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_authenticate() {
+        let tokens = ApiTokens::parse("doug:abc123, jane:def456,malformed,:nameless");
+
+        let request = TestRequest::default()
+            .insert_header((header::AUTHORIZATION, "Bearer def456"))
+            .to_http_request();
+        assert_eq!(tokens.authenticate(&request), Some("jane"));
+
+        let request = TestRequest::default()
+            .insert_header((header::AUTHORIZATION, "Bearer nameless"))
+            .to_http_request();
+        assert_eq!(tokens.authenticate(&request), None);
+
+        let request = TestRequest::default()
+            .insert_header((header::AUTHORIZATION, "abc123"))
+            .to_http_request();
+        assert_eq!(tokens.authenticate(&request), None);
+
+        let request = TestRequest::default().to_http_request();
+        assert_eq!(tokens.authenticate(&request), None);
+    }
+}
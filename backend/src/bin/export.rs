@@ -0,0 +1,68 @@
+use planting_life::catalog::{CatalogExporter, CatalogFormat};
+use planting_life::database::Database;
+use std::{
+    env,
+    fs::File,
+    io::{self, Write},
+};
+
+const USAGE: &str = "Usage: export [--format csv|jsonl] [file]";
+
+/// Exports every plant in the database in $PLANTING_LIFE_DB_URL as CSV or
+/// JSON lines, to the file or stdout.  The output can be read by import.
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let mut format = None;
+    let mut path = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                let value = args.next().unwrap_or_default();
+                format = Some(value.parse().unwrap_or_else(|e| exit_with_usage(&e)));
+            }
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => exit_with_usage(&format!("unexpected argument: {arg}")),
+        }
+    }
+
+    let format = format
+        .or_else(|| path.as_deref().and_then(CatalogFormat::from_path))
+        .unwrap_or(CatalogFormat::JsonLines);
+
+    let mut output: Box<dyn Write> = match &path {
+        Some(path) => Box::new(File::create(path).unwrap_or_else(|e| {
+            eprintln!("Can't create {path}: {e}");
+            std::process::exit(1);
+        })),
+        None => Box::new(io::stdout().lock()),
+    };
+
+    let db_url = env::var("PLANTING_LIFE_DB_URL").expect("Must define $PLANTING_LIFE_DB_URL");
+    let db = Database::new(&db_url);
+
+    let mut exporter = CatalogExporter::new(&db, format);
+    loop {
+        match exporter.next_chunk().await {
+            Ok(Some(chunk)) => output
+                .write_all(chunk.as_bytes())
+                .unwrap_or_else(|e| exit_with_error(&e)),
+            Ok(None) => break,
+            Err(e) => exit_with_error(&e),
+        }
+    }
+
+    output.flush().unwrap_or_else(|e| exit_with_error(&e));
+}
+
+fn exit_with_error(error: &dyn std::fmt::Display) -> ! {
+    eprintln!("Export failed: {error}");
+    std::process::exit(1);
+}
+
+fn exit_with_usage(message: &dyn std::fmt::Display) -> ! {
+    eprintln!("{message}");
+    eprintln!("{USAGE}");
+    std::process::exit(1);
+}
@@ -0,0 +1,58 @@
+use planting_life::catalog::{import_plants, read_plant_records, CatalogFormat};
+use planting_life::database::Database;
+use std::{env, fs};
+
+const USAGE: &str = "Usage: import [--dry-run] [--format csv|jsonl] <file>";
+
+/// Imports plants from a CSV or JSON lines file into the database in
+/// $PLANTING_LIFE_DB_URL, then reports what was created, updated and rejected.
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let mut dry_run = false;
+    let mut format = None;
+    let mut path = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--format" => {
+                let value = args.next().unwrap_or_default();
+                format = Some(value.parse().unwrap_or_else(|e| exit_with_usage(&e)));
+            }
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => exit_with_usage(&format!("unexpected argument: {arg}")),
+        }
+    }
+
+    let path = path.unwrap_or_else(|| exit_with_usage(&"a file is required"));
+    let format = format
+        .or_else(|| CatalogFormat::from_path(&path))
+        .unwrap_or_else(|| exit_with_usage(&"can't tell format from file name, use --format"));
+
+    let contents = fs::read_to_string(&path).unwrap_or_else(|e| {
+        eprintln!("Can't read {path}: {e}");
+        std::process::exit(1);
+    });
+
+    let db_url = env::var("PLANTING_LIFE_DB_URL").expect("Must define $PLANTING_LIFE_DB_URL");
+    let db = Database::new(&db_url);
+
+    let records = read_plant_records(&contents, format);
+    let report = import_plants(&db, records, dry_run).await;
+
+    if dry_run {
+        println!("Dry run, nothing was saved.");
+    }
+    println!("{report}");
+
+    if !report.rejected.is_empty() {
+        std::process::exit(2);
+    }
+}
+
+fn exit_with_usage(message: &dyn std::fmt::Display) -> ! {
+    eprintln!("{message}");
+    eprintln!("{USAGE}");
+    std::process::exit(1);
+}
@@ -0,0 +1,44 @@
+use planting_life::database::Database;
+use std::env;
+
+/// Applies pending schema changesets to the database in $PLANTING_LIFE_DB_URL.
+///
+/// Usage: migrate [status]
+///   (no args)  applies all pending changesets, then parses any plant bloom
+///              and size text which isn't structured yet
+///   status     lists pending changesets without applying them
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    tracing_subscriber::fmt::init();
+
+    let db_url = env::var("PLANTING_LIFE_DB_URL").expect("Must define $PLANTING_LIFE_DB_URL");
+    let db = Database::new(&db_url);
+
+    let args: Vec<String> = env::args().collect();
+    let result = match args.get(1).map(String::as_str) {
+        None => match db.migrate().await {
+            Ok(applied) => {
+                println!("Applied {} changeset(s)", applied.len());
+                db.backfill_plant_details().await.map(|backfilled| {
+                    println!("Filled in details for {backfilled} plant(s)");
+                })
+            }
+            Err(e) => Err(e),
+        },
+        Some("status") => db.get_pending_migrations().await.map(|pending| {
+            for changeset in &pending {
+                println!("pending: {changeset}");
+            }
+            println!("{} changeset(s) pending", pending.len());
+        }),
+        Some(other) => {
+            eprintln!("Unknown command: {other}, expected no arguments or 'status'");
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("Migration failed: {e}");
+        std::process::exit(1);
+    }
+}
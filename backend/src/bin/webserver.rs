@@ -1,7 +1,15 @@
-use std::env;
+use std::{env, time::Duration};
 
-use planting_life::app::PlantingLifeApp;
-use tracing::log::warn;
+use planting_life::{
+    app::PlantingLifeApp,
+    auth::ApiTokens,
+    highlights::{HighlightRules, Highlights},
+};
+use tracing::log::{error, warn};
+
+/// How often plants written outside the webserver, ex: by import, become
+/// searchable by name
+const SEARCH_INDEX_REFRESH: Duration = Duration::from_secs(5 * 60);
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -9,11 +17,40 @@ async fn main() -> std::io::Result<()> {
         warn!("Configure valid PLANTING_LIFE_DB_URL to use database");
         "".to_string()
     });
-    let app = PlantingLifeApp::new(&db_url);
+
+    // Comma separated name:token pairs, which unlock the admin endpoints
+    let api_tokens = ApiTokens::parse(&env::var("PLANTING_LIFE_API_TOKENS").unwrap_or_default());
+    if api_tokens.is_empty() {
+        warn!("Configure PLANTING_LIFE_API_TOKENS to use admin endpoints");
+    }
+
+    // Lets highlights be tuned without a release, otherwise the built in rules
+    // from config/highlights.toml are used.
+    let highlight_rules = match env::var("PLANTING_LIFE_HIGHLIGHTS_CONFIG") {
+        Ok(path) => HighlightRules::from_file(&path).map_err(|e| {
+            error!("Failed to load highlight rules: {e}");
+            std::io::Error::other(e.to_string())
+        })?,
+        Err(_) => HighlightRules::default(),
+    };
+
+    let app = PlantingLifeApp::new(&db_url, api_tokens, Highlights::new(highlight_rules));
 
     // Leak it to get a 'static lifetime, by definition it lives for
     // the entirety of the program
     let app = Box::leak(Box::new(app));
 
+    // Deployments can opt in to keeping the schema current on startup,
+    // otherwise run the migrate binary.
+    if env::var("PLANTING_LIFE_MIGRATE_ON_STARTUP").is_ok_and(|v| v == "true") {
+        if let Err(e) = app.migrate().await {
+            error!("Failed to migrate database: {e}");
+            return Err(std::io::Error::other(e.to_string()));
+        }
+    }
+
+    app.load_search_index().await;
+    app.refresh_search_index(SEARCH_INDEX_REFRESH);
+
     app.start().await
 }
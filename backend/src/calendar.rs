@@ -0,0 +1,212 @@
+//! Lays out when the plants in a garden bloom, month by month, so gaps in
+//! the forage a garden offers pollinators stand out.
+
+use serde::Serialize;
+
+use crate::{
+    domain::{Garden, GardenPlantStatus, Plant},
+    plant_details::parse_bloom,
+};
+
+/// The months pollinators are out foraging.  Nothing blooming in the winter
+/// isn't worth pointing out, but nothing blooming in April is.
+const FORAGE_MONTHS: [u8; 8] = [3, 4, 5, 6, 7, 8, 9, 10];
+
+/// Which plants in a garden bloom in each month.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BloomCalendar {
+    /// Every month, starting with January
+    pub months: Vec<BloomMonth>,
+
+    /// The forage months nothing blooms in, ex: [4] if nothing blooms in April
+    pub gaps: Vec<u8>,
+
+    /// The plants whose bloom isn't known, so aren't on the calendar
+    pub unknown: Vec<CalendarPlant>,
+}
+
+/// The plants blooming in one month, from 1 (January) to 12 (December).
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BloomMonth {
+    pub month: u8,
+    pub plants: Vec<CalendarPlant>,
+
+    /// Nothing blooms in this month, though pollinators are foraging
+    pub gap: bool,
+}
+
+/// Enough of a plant to show it on the calendar.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarPlant {
+    pub id: Option<usize>,
+    pub common: String,
+    pub scientific: String,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub bloom_colors: Vec<String>,
+}
+
+impl From<&Plant> for CalendarPlant {
+    fn from(plant: &Plant) -> Self {
+        Self {
+            id: plant.id,
+            common: plant.common.clone(),
+            scientific: plant.scientific.clone(),
+            bloom_colors: plant.bloom_colors.clone(),
+        }
+    }
+}
+
+/// Builds the calendar for a garden's plants.  Each plant is listed once,
+/// even when it is in several zones, and lost plants are left out.
+pub fn bloom_calendar(garden: &Garden) -> BloomCalendar {
+    let mut months: Vec<BloomMonth> = (1..=12)
+        .map(|month| BloomMonth {
+            month,
+            plants: vec![],
+            gap: false,
+        })
+        .collect();
+    let mut unknown: Vec<CalendarPlant> = vec![];
+
+    let mut seen_plants: Vec<&Plant> = vec![];
+    for garden_plant in &garden.plants {
+        let plant = &garden_plant.plant;
+        if garden_plant.details.status == GardenPlantStatus::Lost
+            || seen_plants.iter().any(|p| is_same_plant(p, plant))
+        {
+            continue;
+        }
+        seen_plants.push(plant);
+
+        // Plants saved before their bloom was parsed only have the text
+        let bloom = plant
+            .bloom_period
+            .or_else(|| plant.bloom.as_deref().and_then(parse_bloom));
+
+        match bloom {
+            Some(bloom) => {
+                // Saved bloom periods aren't validated, so skip months
+                // which don't exist rather than trusting them
+                for month in bloom.months() {
+                    if let Some(month) = months.get_mut((month as usize).wrapping_sub(1)) {
+                        month.plants.push(plant.into());
+                    }
+                }
+            }
+            None => unknown.push(plant.into()),
+        }
+    }
+
+    for month in &mut months {
+        month.gap = month.plants.is_empty() && FORAGE_MONTHS.contains(&month.month);
+    }
+    let gaps = months.iter().filter(|m| m.gap).map(|m| m.month).collect();
+
+    BloomCalendar {
+        months,
+        gaps,
+        unknown,
+    }
+}
+
+/// Whether two entries in a garden are the same plant, ex: in two zones
+pub(crate) fn is_same_plant(lhs: &Plant, rhs: &Plant) -> bool {
+    match (lhs.id, rhs.id) {
+        (Some(lhs), Some(rhs)) => lhs == rhs,
+        _ => lhs.scientific == rhs.scientific,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{BloomPeriod, GardenPlant, GardenPlantDetails, Moisture, Shade};
+
+    fn garden_plant(id: usize, bloom: Option<&str>, status: GardenPlantStatus) -> GardenPlant {
+        let mut plant = Plant::new(&format!("Plant {id}"), &format!("Plant {id}"));
+        plant.id = Some(id);
+        plant.bloom = bloom.map(str::to_string);
+
+        GardenPlant {
+            plant,
+            details: GardenPlantDetails {
+                status,
+                ..GardenPlantDetails::default()
+            },
+            warnings: vec![],
+        }
+    }
+
+    fn plant_ids(month: &BloomMonth) -> Vec<Option<usize>> {
+        month.plants.iter().map(|p| p.id).collect()
+    }
+
+    #[test]
+    fn test_bloom_calendar() {
+        let mut parsed = garden_plant(1, None, GardenPlantStatus::Planted);
+        parsed.plant.bloom_period = Some(BloomPeriod {
+            start_month: 5,
+            end_month: 6,
+        });
+
+        let mut in_another_zone = parsed.clone();
+        in_another_zone.details.zone = 1;
+
+        let mut garden = Garden::empty(
+            "name".to_string(),
+            "43081".to_string(),
+            Shade::None,
+            Moisture::Some,
+        );
+        garden.plants = vec![
+            parsed,
+            in_another_zone,
+            garden_plant(2, Some("June to August"), GardenPlantStatus::Planned),
+            garden_plant(3, Some("Fall"), GardenPlantStatus::Lost),
+            garden_plant(4, Some("Showy"), GardenPlantStatus::Established),
+        ];
+
+        let calendar = bloom_calendar(&garden);
+
+        assert_eq!(calendar.months.len(), 12);
+        assert_eq!(plant_ids(&calendar.months[4]), vec![Some(1)]);
+        assert_eq!(plant_ids(&calendar.months[5]), vec![Some(1), Some(2)]);
+        assert_eq!(plant_ids(&calendar.months[7]), vec![Some(2)]);
+        assert!(calendar.months[8].plants.is_empty());
+
+        assert_eq!(calendar.gaps, vec![3, 4, 9, 10]);
+        assert!(calendar.months[3].gap);
+        assert!(!calendar.months[0].gap);
+
+        assert_eq!(
+            calendar.unknown.iter().map(|p| p.id).collect::<Vec<_>>(),
+            vec![Some(4)]
+        );
+    }
+
+    #[test]
+    fn test_bloom_calendar_skips_invalid_months() {
+        let mut invalid = garden_plant(1, None, GardenPlantStatus::Planted);
+        invalid.plant.bloom_period = Some(BloomPeriod {
+            start_month: 0,
+            end_month: 13,
+        });
+
+        let mut garden = Garden::empty(
+            "name".to_string(),
+            "43081".to_string(),
+            Shade::None,
+            Moisture::Some,
+        );
+        garden.plants = vec![invalid];
+
+        let calendar = bloom_calendar(&garden);
+        assert_eq!(calendar.months.len(), 12);
+        assert!(calendar.months.iter().all(|m| m.plants.len() <= 1));
+        assert_eq!(plant_ids(&calendar.months[0]), vec![Some(1)]);
+    }
+}
@@ -0,0 +1,1081 @@
+use anyhow::anyhow;
+use mockall_double::double;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+#[double]
+use crate::database::Database;
+use crate::domain::{
+    Image, Moisture, NativeConfidence, NativeProvenance, NativeSource, Plant, PlantRegion,
+    PlantSynonym, Shade, SynonymKind,
+};
+
+/// Separates multiple values within one CSV field, ex: "Full Sun;Partial Shade"
+const CSV_LIST_SEPARATOR: char = ';';
+
+/// How many plants an export reads from the database at once
+const EXPORT_PAGE_SIZE: usize = 500;
+
+/// File formats for reading and writing the plant catalog.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CatalogFormat {
+    Csv,
+    JsonLines,
+}
+
+impl CatalogFormat {
+    /// Picks a format from a file's extension, ex: "plants.csv"
+    pub fn from_path(path: &str) -> Option<CatalogFormat> {
+        let extension = path.rsplit_once('.')?.1;
+        extension.parse().ok()
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            CatalogFormat::Csv => "text/csv",
+            CatalogFormat::JsonLines => "application/jsonl",
+        }
+    }
+}
+
+impl FromStr for CatalogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(CatalogFormat::Csv),
+            "jsonl" | "json" => Ok(CatalogFormat::JsonLines),
+            _ => Err(anyhow!(
+                "unknown catalog format: {s}, expected csv or jsonl"
+            )),
+        }
+    }
+}
+
+/// One plant in a catalog file.  Shaped like the Plant json, but includes the
+/// fields the API hides (ratings, conditions) and the zipcodes of the regions
+/// it is native to.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlantRecord {
+    /// Written by export, ignored by import which matches on scientific name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<usize>,
+
+    pub scientific: String,
+    pub common: String,
+
+    /// Other scientific names this plant is known by, ex: "Aster novae-angliae"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub synonyms: Vec<String>,
+
+    /// Common names besides common, ex: "Pleurisy Root"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub common_names: Vec<String>,
+
+    /// Ex: "Full Sun" or "None"
+    #[serde(default)]
+    pub shades: Vec<String>,
+
+    /// Ex: "Medium" or "Some"
+    #[serde(default)]
+    pub moistures: Vec<String>,
+
+    pub bloom: Option<String>,
+    pub height: Option<String>,
+    pub spread: Option<String>,
+
+    pub pollinator_rating: Option<u8>,
+    pub bird_rating: Option<u8>,
+    pub spread_rating: Option<u8>,
+    pub deer_resistance_rating: Option<u8>,
+
+    /// Looked up from the scientific name when missing, ex: "ASTU"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usda_symbol: Option<String>,
+    pub usda_source: Option<String>,
+    pub wiki_source: Option<String>,
+
+    pub image: Option<ImageRecord>,
+
+    /// One zipcode from each region this plant is native to
+    #[serde(default)]
+    pub zipcodes: Vec<String>,
+
+    /// Where the zipcodes came from, defaults to a curator
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub native_source: Option<NativeSource>,
+
+    /// How sure native_source is, defaults to how sure it usually is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub native_confidence: Option<NativeConfidence>,
+
+    /// Names of the regions this plant is native to.  Written by export for
+    /// readability, ignored by import which uses zipcodes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageRecord {
+    pub title: String,
+    pub card_url: String,
+    pub original_url: String,
+    pub author: String,
+    pub license: String,
+}
+
+/// The flat version of PlantRecord, one column per field.  Lists are
+/// separated by semicolons.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CsvPlantRecord {
+    pub id: Option<usize>,
+    pub scientific: String,
+    pub common: String,
+    #[serde(default)]
+    pub synonyms: String,
+    #[serde(default)]
+    pub common_names: String,
+    #[serde(default)]
+    pub shades: String,
+    #[serde(default)]
+    pub moistures: String,
+    pub bloom: Option<String>,
+    pub height: Option<String>,
+    pub spread: Option<String>,
+    pub pollinator_rating: Option<u8>,
+    pub bird_rating: Option<u8>,
+    pub spread_rating: Option<u8>,
+    pub deer_resistance_rating: Option<u8>,
+    pub usda_symbol: Option<String>,
+    pub usda_source: Option<String>,
+    pub wiki_source: Option<String>,
+    pub image_title: Option<String>,
+    pub image_card_url: Option<String>,
+    pub image_original_url: Option<String>,
+    pub image_author: Option<String>,
+    pub image_license: Option<String>,
+    #[serde(default)]
+    pub zipcodes: String,
+    pub native_source: Option<NativeSource>,
+    pub native_confidence: Option<NativeConfidence>,
+    #[serde(default)]
+    pub regions: String,
+}
+
+impl From<CsvPlantRecord> for PlantRecord {
+    fn from(csv: CsvPlantRecord) -> Self {
+        // Any image column makes an image, to_plant reports what is missing.
+        let has_image = [
+            &csv.image_title,
+            &csv.image_card_url,
+            &csv.image_original_url,
+            &csv.image_author,
+            &csv.image_license,
+        ]
+        .iter()
+        .any(|field| field.is_some());
+
+        let image = has_image.then(|| ImageRecord {
+            title: csv.image_title.unwrap_or_default(),
+            card_url: csv.image_card_url.unwrap_or_default(),
+            original_url: csv.image_original_url.unwrap_or_default(),
+            author: csv.image_author.unwrap_or_default(),
+            license: csv.image_license.unwrap_or_default(),
+        });
+
+        PlantRecord {
+            id: csv.id,
+            scientific: csv.scientific,
+            common: csv.common,
+            synonyms: split_csv_list(&csv.synonyms),
+            common_names: split_csv_list(&csv.common_names),
+            shades: split_csv_list(&csv.shades),
+            moistures: split_csv_list(&csv.moistures),
+            bloom: csv.bloom,
+            height: csv.height,
+            spread: csv.spread,
+            pollinator_rating: csv.pollinator_rating,
+            bird_rating: csv.bird_rating,
+            spread_rating: csv.spread_rating,
+            deer_resistance_rating: csv.deer_resistance_rating,
+            usda_symbol: csv.usda_symbol,
+            usda_source: csv.usda_source,
+            wiki_source: csv.wiki_source,
+            image,
+            zipcodes: split_csv_list(&csv.zipcodes),
+            native_source: csv.native_source,
+            native_confidence: csv.native_confidence,
+            regions: split_csv_list(&csv.regions),
+        }
+    }
+}
+
+impl From<PlantRecord> for CsvPlantRecord {
+    fn from(record: PlantRecord) -> Self {
+        let separator = CSV_LIST_SEPARATOR.to_string();
+        let image = record.image;
+
+        CsvPlantRecord {
+            id: record.id,
+            scientific: record.scientific,
+            common: record.common,
+            synonyms: record.synonyms.join(&separator),
+            common_names: record.common_names.join(&separator),
+            shades: record.shades.join(&separator),
+            moistures: record.moistures.join(&separator),
+            bloom: record.bloom,
+            height: record.height,
+            spread: record.spread,
+            pollinator_rating: record.pollinator_rating,
+            bird_rating: record.bird_rating,
+            spread_rating: record.spread_rating,
+            deer_resistance_rating: record.deer_resistance_rating,
+            usda_symbol: record.usda_symbol,
+            usda_source: record.usda_source,
+            wiki_source: record.wiki_source,
+            image_title: image.as_ref().map(|i| i.title.clone()),
+            image_card_url: image.as_ref().map(|i| i.card_url.clone()),
+            image_original_url: image.as_ref().map(|i| i.original_url.clone()),
+            image_author: image.as_ref().map(|i| i.author.clone()),
+            image_license: image.map(|i| i.license),
+            zipcodes: record.zipcodes.join(&separator),
+            native_source: record.native_source,
+            native_confidence: record.native_confidence,
+            regions: record.regions.join(&separator),
+        }
+    }
+}
+
+impl PlantRecord {
+    /// Converts a saved plant, its regions and synonyms into the record import
+    /// reads.  Provenance is only kept when every region shares it.
+    pub fn from_plant(plant: &Plant, regions: &[PlantRegion], synonyms: &[PlantSynonym]) -> Self {
+        let names_of = |kind: SynonymKind| {
+            synonyms
+                .iter()
+                .filter(|s| s.kind == kind)
+                .map(|s| s.name.clone())
+                .collect()
+        };
+
+        let first = regions.first().map(|r| &r.provenance);
+        let native_source = first
+            .map(|p| p.source)
+            .filter(|source| regions.iter().all(|r| r.provenance.source == *source));
+        let native_confidence = first.map(|p| p.confidence).filter(|confidence| {
+            regions
+                .iter()
+                .all(|r| r.provenance.confidence == *confidence)
+        });
+
+        PlantRecord {
+            id: plant.id,
+            scientific: plant.scientific.clone(),
+            common: plant.common.clone(),
+            synonyms: names_of(SynonymKind::Scientific),
+            common_names: names_of(SynonymKind::Common),
+            shades: plant.shades.iter().map(condition_name).collect(),
+            moistures: plant.moistures.iter().map(condition_name).collect(),
+            bloom: plant.bloom.clone(),
+            height: plant.height.clone(),
+            spread: plant.spread.clone(),
+            pollinator_rating: plant.pollinator_rating,
+            bird_rating: plant.bird_rating,
+            spread_rating: plant.spread_rating,
+            deer_resistance_rating: plant.deer_resistance_rating,
+            usda_symbol: plant.usda_symbol.clone(),
+            usda_source: plant.usda_source.clone(),
+            wiki_source: plant.wiki_source.clone(),
+            image: plant.image.as_ref().map(|image| ImageRecord {
+                title: image.title.clone(),
+                card_url: image.card_url.clone(),
+                original_url: image.original_url.clone(),
+                author: image.author.clone(),
+                license: image.license.clone(),
+            }),
+            zipcodes: regions.iter().map(|r| r.zipcode.clone()).collect(),
+            native_source,
+            native_confidence,
+            regions: regions.iter().map(|r| r.name.clone()).collect(),
+        }
+    }
+
+    /// This record's other names, scientific then common.
+    pub fn plant_synonyms(&self) -> Vec<PlantSynonym> {
+        let scientific = self
+            .synonyms
+            .iter()
+            .map(|name| PlantSynonym::new(name.trim(), SynonymKind::Scientific));
+        let common = self
+            .common_names
+            .iter()
+            .map(|name| PlantSynonym::new(name.trim(), SynonymKind::Common));
+
+        scientific
+            .chain(common)
+            .filter(|synonym| !synonym.name.is_empty())
+            .collect()
+    }
+
+    /// Why this record's zipcodes are native regions.
+    pub fn provenance(&self) -> NativeProvenance {
+        let mut provenance =
+            NativeProvenance::new(self.native_source.unwrap_or(NativeSource::Curator));
+        if let Some(confidence) = self.native_confidence {
+            provenance.confidence = confidence;
+        }
+        provenance
+    }
+
+    /// Validates this record and converts it to a Plant without an id.
+    /// Returns every problem found if it is invalid.
+    pub fn to_plant(&self) -> Result<Plant, Vec<String>> {
+        let mut errors = vec![];
+
+        let scientific = self.scientific.trim();
+        let common = self.common.trim();
+        if scientific.is_empty() {
+            errors.push("scientific name is required".to_string());
+        }
+        if common.is_empty() {
+            errors.push("common name is required".to_string());
+        }
+        check_length(&mut errors, "scientific", Some(scientific), 100);
+        check_length(&mut errors, "common", Some(common), 100);
+        check_length(&mut errors, "bloom", self.bloom.as_deref(), 100);
+        check_length(&mut errors, "height", self.height.as_deref(), 50);
+        check_length(&mut errors, "spread", self.spread.as_deref(), 50);
+        check_length(&mut errors, "usdaSymbol", self.usda_symbol.as_deref(), 20);
+        check_length(&mut errors, "usdaSource", self.usda_source.as_deref(), 100);
+        check_length(&mut errors, "wikiSource", self.wiki_source.as_deref(), 100);
+        for synonym in self.synonyms.iter().chain(&self.common_names) {
+            check_length(&mut errors, "synonym", Some(synonym), 100);
+        }
+
+        for (name, rating) in [
+            ("pollinatorRating", self.pollinator_rating),
+            ("birdRating", self.bird_rating),
+            ("spreadRating", self.spread_rating),
+            ("deerResistanceRating", self.deer_resistance_rating),
+        ] {
+            if let Some(rating) = rating {
+                if !(1..=10).contains(&rating) {
+                    errors.push(format!("{name} must be between 1 and 10, was {rating}"));
+                }
+            }
+        }
+
+        let shades: Vec<Shade> = parse_conditions(&mut errors, "shade", &self.shades);
+        let moistures: Vec<Moisture> = parse_conditions(&mut errors, "moisture", &self.moistures);
+
+        for zip in &self.zipcodes {
+            if zip.len() != 5 || !zip.chars().all(|c| c.is_ascii_digit()) {
+                errors.push(format!("invalid zipcode: {zip}"));
+            }
+        }
+
+        let image = self.image.as_ref().and_then(|image| {
+            for (name, value, max_length) in [
+                ("image title", &image.title, 200),
+                ("image cardUrl", &image.card_url, 100),
+                ("image originalUrl", &image.original_url, 100),
+                ("image author", &image.author, 100),
+            ] {
+                if value.trim().is_empty() {
+                    errors.push(format!("{name} is required"));
+                }
+                check_length(&mut errors, name, Some(value), max_length);
+            }
+
+            // Unknown licenses can't be attributed, so they can't be shown
+            match Image::get_license_url(&image.license) {
+                Some(license_url) => Some(Image {
+                    id: None,
+                    title: image.title.clone(),
+                    card_url: image.card_url.clone(),
+                    original_url: image.original_url.clone(),
+                    author: image.author.clone(),
+                    license: image.license.clone(),
+                    license_url,
+                }),
+                None => {
+                    errors.push(format!("unknown image license: {}", image.license));
+                    None
+                }
+            }
+        });
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(Plant {
+            shades,
+            moistures,
+            bloom: self.bloom.clone(),
+            height: self.height.clone(),
+            spread: self.spread.clone(),
+            pollinator_rating: self.pollinator_rating,
+            bird_rating: self.bird_rating,
+            spread_rating: self.spread_rating,
+            deer_resistance_rating: self.deer_resistance_rating,
+            usda_symbol: self.usda_symbol.clone(),
+            usda_source: self.usda_source.clone(),
+            wiki_source: self.wiki_source.clone(),
+            image,
+            ..Plant::new(scientific, common)
+        })
+    }
+}
+
+/// Reads every record in a catalog file.  Each record is paired with its
+/// line number (CSV counts the header as line 1), or the reason it could
+/// not be read.
+pub fn read_plant_records(
+    contents: &str,
+    format: CatalogFormat,
+) -> Vec<(usize, Result<PlantRecord, String>)> {
+    match format {
+        CatalogFormat::JsonLines => contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                let record = serde_json::from_str(line).map_err(|e| format!("invalid json: {e}"));
+                (index + 1, record)
+            })
+            .collect(),
+        CatalogFormat::Csv => csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(contents.as_bytes())
+            .deserialize()
+            .enumerate()
+            .map(|(index, record)| {
+                let record = record
+                    .map(|csv: CsvPlantRecord| PlantRecord::from(csv))
+                    .map_err(|e| format!("invalid csv: {e}"));
+                (index + 2, record)
+            })
+            .collect(),
+    }
+}
+
+/// What happened to each record during an import.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub rejected: Vec<Rejection>,
+}
+
+#[derive(Debug)]
+pub struct Rejection {
+    pub line: usize,
+    pub reasons: Vec<String>,
+}
+
+impl Display for ImportReport {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        for rejection in &self.rejected {
+            writeln!(
+                f,
+                "rejected line {}: {}",
+                rejection.line,
+                rejection.reasons.join(", ")
+            )?;
+        }
+
+        write!(
+            f,
+            "created: {}, updated: {}, rejected: {}",
+            self.created.len(),
+            self.updated.len(),
+            self.rejected.len()
+        )
+    }
+}
+
+/// Validates and upserts every record, linking each plant to the regions of
+/// its zipcodes and saving its synonyms.  Plants are matched to existing ones
+/// by scientific name, which may be a synonym of the existing one's.
+/// When dry_run is true, nothing is saved but the report is the same.
+pub async fn import_plants(
+    db: &Database,
+    records: Vec<(usize, Result<PlantRecord, String>)>,
+    dry_run: bool,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+
+    // Catches repeated plants within the file, which a dry run never saves.
+    let mut seen_names = HashSet::new();
+
+    for (line, record) in records {
+        let record = match record {
+            Ok(record) => record,
+            Err(reason) => {
+                report.rejected.push(Rejection {
+                    line,
+                    reasons: vec![reason],
+                });
+                continue;
+            }
+        };
+
+        let mut plant = match record.to_plant() {
+            Ok(plant) => plant,
+            Err(reasons) => {
+                report.rejected.push(Rejection { line, reasons });
+                continue;
+            }
+        };
+
+        let reasons = check_zipcodes(db, &record.zipcodes).await;
+        if !reasons.is_empty() {
+            report.rejected.push(Rejection { line, reasons });
+            continue;
+        }
+
+        let existing = db.get_plant_by_scientific_name(&plant.scientific).await;
+        let is_update = !seen_names.insert(plant.scientific.clone()) || existing.is_some();
+        if let Some(existing) = existing {
+            plant = merge_imported_plant(&existing, &plant);
+        }
+
+        if !dry_run {
+            match db.save_plant(&plant).await {
+                Ok(saved) => {
+                    for zip in &record.zipcodes {
+                        db.save_plant_region(&saved, zip, &record.provenance())
+                            .await;
+                    }
+
+                    let synonyms = record.plant_synonyms();
+                    if let (Some(id), false) = (saved.id, synonyms.is_empty()) {
+                        if let Err(e) = db.save_plant_synonyms(id, &synonyms).await {
+                            report.rejected.push(Rejection {
+                                line,
+                                reasons: vec![format!("failed to save synonyms: {e}")],
+                            });
+                            continue;
+                        }
+                    }
+                }
+                Err(e) => {
+                    report.rejected.push(Rejection {
+                        line,
+                        reasons: vec![format!("failed to save: {e}")],
+                    });
+                    continue;
+                }
+            }
+        }
+
+        if is_update {
+            report.updated.push(plant.scientific);
+        } else {
+            report.created.push(plant.scientific);
+        }
+    }
+
+    report
+}
+
+/// Checks that each zipcode is a known one (not just close to one), returning
+/// a reason for each which isn't.
+pub async fn check_zipcodes(db: &Database, zipcodes: &[String]) -> Vec<String> {
+    let mut reasons = vec![];
+    for zip in zipcodes {
+        match db.lookup_closest_valid_zip(zip).await {
+            Ok(valid_zip) if &valid_zip == zip => {}
+            Ok(_) => reasons.push(format!("unknown zipcode: {zip}")),
+            Err(e) => reasons.push(format!("can't verify zipcode {zip}: {e}")),
+        }
+    }
+
+    reasons
+}
+
+/// Writes the whole catalog a page at a time, so it can be streamed without
+/// holding every plant in memory.  The output can be imported again.
+pub struct CatalogExporter<'a> {
+    db: &'a Database,
+    format: CatalogFormat,
+    after_id: usize,
+    wrote_header: bool,
+    finished: bool,
+}
+
+impl<'a> CatalogExporter<'a> {
+    pub fn new(db: &'a Database, format: CatalogFormat) -> Self {
+        Self {
+            db,
+            format,
+            after_id: 0,
+            wrote_header: false,
+            finished: false,
+        }
+    }
+
+    /// Formats the next page of plants, or returns None after the last one.
+    /// CSV output starts with a header row.
+    pub async fn next_chunk(&mut self) -> anyhow::Result<Option<String>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let plants = self
+            .db
+            .get_plants_after_id(self.after_id, EXPORT_PAGE_SIZE)
+            .await?;
+        if plants.len() < EXPORT_PAGE_SIZE {
+            self.finished = true;
+        }
+        if plants.is_empty() {
+            return Ok(None);
+        }
+
+        let plant_ids: Vec<usize> = plants.iter().filter_map(|p| p.id).collect();
+        self.after_id = plant_ids.iter().copied().max().unwrap_or(self.after_id);
+        let regions = self.db.get_plant_regions(plant_ids.clone()).await?;
+        let synonyms = self.db.get_plant_synonyms(plant_ids).await?;
+
+        let records = plants.iter().map(|plant| {
+            let plant_regions = plant
+                .id
+                .and_then(|id| regions.get(&id))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let plant_synonyms = plant
+                .id
+                .and_then(|id| synonyms.get(&id))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            PlantRecord::from_plant(plant, plant_regions, plant_synonyms)
+        });
+
+        let chunk = match self.format {
+            CatalogFormat::JsonLines => {
+                let mut chunk = String::new();
+                for record in records {
+                    chunk.push_str(&serde_json::to_string(&record)?);
+                    chunk.push('\n');
+                }
+                chunk
+            }
+            CatalogFormat::Csv => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(!self.wrote_header)
+                    .from_writer(vec![]);
+                for record in records {
+                    writer.serialize(CsvPlantRecord::from(record))?;
+                }
+                self.wrote_header = true;
+
+                String::from_utf8(writer.into_inner()?)?
+            }
+        };
+
+        Ok(Some(chunk))
+    }
+}
+
+/// Merges an imported plant into the existing one.  Like Plant::merge, but
+/// the import may also correct the common name, and empty conditions or a
+/// re-imported image keep what is already saved.
+pub fn merge_imported_plant(existing: &Plant, imported: &Plant) -> Plant {
+    let mut merged = existing.merge(imported);
+    merged.common = imported.common.clone();
+
+    if imported.shades.is_empty() {
+        merged.shades = existing.shades.clone();
+    }
+    if imported.moistures.is_empty() {
+        merged.moistures = existing.moistures.clone();
+    }
+
+    if let (Some(existing_image), Some(imported_image)) = (&existing.image, &imported.image) {
+        if existing_image.card_url == imported_image.card_url {
+            merged.image = Some(existing_image.clone());
+        }
+    }
+
+    merged
+}
+
+/// The json name of a shade/moisture, ex: "Full Sun"
+fn condition_name<T: Serialize>(condition: &T) -> String {
+    match serde_json::to_value(condition) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+fn split_csv_list(value: &str) -> Vec<String> {
+    value
+        .split(CSV_LIST_SEPARATOR)
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn check_length(errors: &mut Vec<String>, name: &str, value: Option<&str>, max_length: usize) {
+    if let Some(value) = value {
+        if value.chars().count() > max_length {
+            errors.push(format!("{name} is longer than {max_length} characters"));
+        }
+    }
+}
+
+/// Parses shades/moistures by either their json name ("Full Sun") or
+/// their database name ("None").
+fn parse_conditions<T>(errors: &mut Vec<String>, name: &str, values: &[String]) -> Vec<T>
+where
+    T: FromStr + DeserializeOwned + PartialEq,
+{
+    let mut conditions = vec![];
+
+    for value in values {
+        let condition = value
+            .parse()
+            .ok()
+            .or_else(|| serde_json::from_value(serde_json::Value::String(value.clone())).ok());
+
+        match condition {
+            Some(condition) if !conditions.contains(&condition) => conditions.push(condition),
+            Some(_) => {}
+            None => errors.push(format!("unknown {name}: {value}")),
+        }
+    }
+
+    conditions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::MockDatabase;
+    use std::collections::HashMap;
+
+    fn valid_record() -> PlantRecord {
+        PlantRecord {
+            scientific: "Asclepias incarnata".to_string(),
+            common: "Swamp Milkweed".to_string(),
+            shades: vec!["Full Sun".to_string(), "Some".to_string()],
+            moistures: vec!["High".to_string()],
+            pollinator_rating: Some(9),
+            zipcodes: vec!["43081".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_to_plant_valid() {
+        let plant = valid_record().to_plant().unwrap();
+
+        assert_eq!(plant.scientific, "Asclepias incarnata");
+        assert_eq!(plant.shades, vec![Shade::None, Shade::Some]);
+        assert_eq!(plant.moistures, vec![Moisture::Lots]);
+        assert_eq!(plant.pollinator_rating, Some(9));
+        assert_eq!(plant.id, None);
+    }
+
+    #[test]
+    fn test_to_plant_invalid() {
+        let record = PlantRecord {
+            scientific: " ".to_string(),
+            shades: vec!["Dappled".to_string()],
+            bird_rating: Some(11),
+            zipcodes: vec!["4308".to_string()],
+            image: Some(ImageRecord {
+                title: "title".to_string(),
+                card_url: "card".to_string(),
+                original_url: "original".to_string(),
+                author: "author".to_string(),
+                license: "All rights reserved".to_string(),
+            }),
+            ..valid_record()
+        };
+
+        let errors = record.to_plant().unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                "scientific name is required",
+                "birdRating must be between 1 and 10, was 11",
+                "unknown shade: Dappled",
+                "invalid zipcode: 4308",
+                "unknown image license: All rights reserved",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_csv_records() {
+        let contents = "\
+scientific,common,shades,moistures,pollinator_rating,image_title,zipcodes
+Asclepias incarnata,Swamp Milkweed,Full Sun;Partial Shade,High,9,,43081;43085
+Aster,Aster,,,not a number,,
+";
+
+        let records = read_plant_records(contents, CatalogFormat::Csv);
+
+        assert_eq!(records.len(), 2);
+        let (line, record) = &records[0];
+        let record = record.as_ref().unwrap();
+        assert_eq!(*line, 2);
+        assert_eq!(record.shades, vec!["Full Sun", "Partial Shade"]);
+        assert_eq!(record.zipcodes, vec!["43081", "43085"]);
+        assert_eq!(record.pollinator_rating, Some(9));
+        assert_eq!(record.image, None);
+
+        let (line, record) = &records[1];
+        assert_eq!(*line, 3);
+        assert!(record.is_err());
+    }
+
+    #[test]
+    fn test_read_json_lines_records() {
+        let contents = r#"{"scientific": "Asclepias incarnata", "common": "Swamp Milkweed", "moistures": ["High"]}
+
+{"scientific": "missing common"}
+"#;
+
+        let records = read_plant_records(contents, CatalogFormat::JsonLines);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].1.as_ref().unwrap().moistures, vec!["High"]);
+        assert_eq!(records[1].0, 3);
+        assert!(records[1].1.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_import_plants() {
+        let mut db = MockDatabase::default();
+        db.expect_lookup_closest_valid_zip()
+            .returning(|zip| Ok(zip.to_string()));
+        db.expect_get_plant_by_scientific_name()
+            .returning(|name| match name {
+                "Existing plant" => Some(Plant {
+                    id: Some(7),
+                    bird_rating: Some(5),
+                    moistures: vec![Moisture::Some],
+                    ..Plant::new("Existing plant", "Old name")
+                }),
+                _ => None,
+            });
+        db.expect_save_plant()
+            .withf(|p| p.scientific == "Existing plant")
+            .times(1)
+            .returning(|p| {
+                // Keeps saved values the import didn't provide
+                assert_eq!(p.id, Some(7));
+                assert_eq!(p.common, "New name");
+                assert_eq!(p.bird_rating, Some(5));
+                assert_eq!(p.moistures, vec![Moisture::Some]);
+                Ok(p.clone())
+            });
+        db.expect_save_plant()
+            .withf(|p| p.scientific == "Asclepias incarnata")
+            .times(1)
+            .returning(|p| {
+                Ok(Plant {
+                    id: Some(8),
+                    ..p.clone()
+                })
+            });
+        db.expect_save_plant_region()
+            .withf(|p, zip, provenance| {
+                p.id == Some(8)
+                    && zip == "43081"
+                    && *provenance == NativeProvenance::new(NativeSource::Curator)
+            })
+            .times(1)
+            .returning(|_, _, _| ());
+        db.expect_save_plant_synonyms()
+            .withf(|id, synonyms| {
+                *id == 8
+                    && synonyms
+                        == [
+                            PlantSynonym::new("Asclepias pulchra", SynonymKind::Scientific),
+                            PlantSynonym::new("Rose Milkweed", SynonymKind::Common),
+                        ]
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let records = vec![
+            (
+                1,
+                Ok(PlantRecord {
+                    synonyms: vec!["Asclepias pulchra".to_string()],
+                    common_names: vec!["Rose Milkweed".to_string()],
+                    ..valid_record()
+                }),
+            ),
+            (
+                2,
+                Ok(PlantRecord {
+                    scientific: "Existing plant".to_string(),
+                    common: "New name".to_string(),
+                    ..Default::default()
+                }),
+            ),
+            (
+                3,
+                Ok(PlantRecord {
+                    pollinator_rating: Some(0),
+                    ..valid_record()
+                }),
+            ),
+            (4, Err("invalid json".to_string())),
+        ];
+
+        let report = import_plants(&db, records, false).await;
+
+        assert_eq!(report.created, vec!["Asclepias incarnata"]);
+        assert_eq!(report.updated, vec!["Existing plant"]);
+        let rejected_lines: Vec<usize> = report.rejected.iter().map(|r| r.line).collect();
+        assert_eq!(rejected_lines, vec![3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_import_plants_dry_run() {
+        let mut db = MockDatabase::default();
+        db.expect_lookup_closest_valid_zip()
+            .returning(|_| Ok("43085".to_string()));
+        db.expect_get_plant_by_scientific_name().returning(|_| None);
+        db.expect_save_plant().never();
+
+        let records = vec![
+            (1, Ok(PlantRecord::default())),
+            (
+                2,
+                Ok(PlantRecord {
+                    zipcodes: vec![],
+                    ..valid_record()
+                }),
+            ),
+            (
+                3,
+                Ok(PlantRecord {
+                    zipcodes: vec![],
+                    ..valid_record()
+                }),
+            ),
+            // 43081 is adjusted to 43085, so it isn't a known zipcode
+            (4, Ok(valid_record())),
+        ];
+
+        let report = import_plants(&db, records, true).await;
+
+        assert_eq!(report.created, vec!["Asclepias incarnata"]);
+        assert_eq!(report.updated, vec!["Asclepias incarnata"]);
+        assert_eq!(report.rejected.len(), 2);
+        assert_eq!(report.rejected[1].reasons, vec!["unknown zipcode: 43081"]);
+    }
+
+    fn exported_plant() -> Plant {
+        Plant {
+            id: Some(3),
+            shades: vec![Shade::None, Shade::Some],
+            moistures: vec![Moisture::Lots],
+            bird_rating: Some(4),
+            image: Some(Image {
+                id: Some(1),
+                title: "Milkweed, with a comma".to_string(),
+                card_url: "https://example.com/card.jpg".to_string(),
+                original_url: "https://example.com/original".to_string(),
+                author: "Someone".to_string(),
+                license: "CC BY-SA 2.0".to_string(),
+                license_url: "https://creativecommons.org/licenses/by-sa/2.0/".to_string(),
+            }),
+            ..Plant::new("Asclepias incarnata", "Swamp Milkweed")
+        }
+    }
+
+    fn export_db() -> MockDatabase {
+        let mut db = MockDatabase::default();
+        db.expect_get_plants_after_id()
+            .withf(|after_id, _| *after_id == 0)
+            .times(1)
+            .returning(|_, _| Ok(vec![exported_plant(), Plant::new("Aster", "Aster")]));
+        db.expect_get_plant_regions()
+            .withf(|ids| ids == &vec![3])
+            .times(1)
+            .returning(|_| {
+                let regions = vec![PlantRegion {
+                    region_id: 1,
+                    name: "Columbus, OH".to_string(),
+                    zipcode: "43081".to_string(),
+                    provenance: NativeProvenance::new(NativeSource::Usda),
+                }];
+                Ok(HashMap::from([(3, regions)]))
+            });
+        db.expect_get_plant_synonyms()
+            .withf(|ids| ids == &vec![3])
+            .times(1)
+            .returning(|_| {
+                let synonyms = vec![
+                    PlantSynonym::new("Asclepias pulchra", SynonymKind::Scientific),
+                    PlantSynonym::new("Rose Milkweed", SynonymKind::Common),
+                    PlantSynonym::new("White Indian Hemp", SynonymKind::Common),
+                ];
+                Ok(HashMap::from([(3, synonyms)]))
+            });
+        db
+    }
+
+    async fn export_all(db: &MockDatabase, format: CatalogFormat) -> String {
+        let mut exporter = CatalogExporter::new(db, format);
+        let mut contents = String::new();
+        while let Some(chunk) = exporter.next_chunk().await.unwrap() {
+            contents.push_str(&chunk);
+        }
+        contents
+    }
+
+    #[tokio::test]
+    async fn test_export_round_trips_through_import() {
+        for format in [CatalogFormat::JsonLines, CatalogFormat::Csv] {
+            let db = export_db();
+            let contents = export_all(&db, format).await;
+
+            let records = read_plant_records(&contents, format);
+            assert_eq!(records.len(), 2);
+
+            let record = records[0].1.as_ref().unwrap();
+            assert_eq!(record.id, Some(3));
+            assert_eq!(record.shades, vec!["Full Sun", "Partial Shade"]);
+            assert_eq!(record.zipcodes, vec!["43081"]);
+            assert_eq!(record.regions, vec!["Columbus, OH"]);
+            assert_eq!(record.synonyms, vec!["Asclepias pulchra"]);
+            assert_eq!(
+                record.common_names,
+                vec!["Rose Milkweed", "White Indian Hemp"]
+            );
+            assert_eq!(
+                record.provenance(),
+                NativeProvenance::new(NativeSource::Usda)
+            );
+
+            let plant = record.to_plant().unwrap();
+            assert_eq!(plant.moistures, vec![Moisture::Lots]);
+            assert_eq!(plant.bird_rating, Some(4));
+            assert_eq!(
+                plant.image.map(|i| i.title),
+                Some("Milkweed, with a comma".to_string())
+            );
+
+            let record = records[1].1.as_ref().unwrap();
+            assert_eq!(record.scientific, "Aster");
+            assert_eq!(record.image, None);
+        }
+    }
+}
@@ -1,4 +1,7 @@
+pub mod admin;
+pub mod export;
 pub mod gardens;
 pub mod maps;
 pub mod nurseries;
 pub mod plants;
+pub mod regions;
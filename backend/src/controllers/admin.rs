@@ -0,0 +1,603 @@
+use actix_web::{http::header, patch, post, put, web, HttpRequest, HttpResponse, Responder};
+use mockall_double::double;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tracing::log::{info, warn};
+
+#[double]
+use crate::database::Database;
+use crate::{
+    app::PlantingLifeApp,
+    auth::ApiTokens,
+    catalog::{check_zipcodes, merge_imported_plant, ImageRecord, PlantRecord},
+    domain::{NativeConfidence, NativeSource, Plant, PlantChange, PlantChangeAction},
+};
+
+/// Fields to change on a plant, anything missing is left as it is.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct PlantPatch {
+    common: Option<String>,
+    shades: Option<Vec<String>>,
+    moistures: Option<Vec<String>>,
+    bloom: Option<String>,
+    height: Option<String>,
+    spread: Option<String>,
+    pollinator_rating: Option<u8>,
+    bird_rating: Option<u8>,
+    spread_rating: Option<u8>,
+    deer_resistance_rating: Option<u8>,
+    usda_symbol: Option<String>,
+    usda_source: Option<String>,
+    wiki_source: Option<String>,
+    image: Option<ImageRecord>,
+
+    /// More scientific names this plant is known by
+    #[serde(default)]
+    synonyms: Vec<String>,
+
+    /// More common names this plant is known by
+    #[serde(default)]
+    common_names: Vec<String>,
+
+    /// Zipcodes of more regions this plant is native to
+    #[serde(default)]
+    zipcodes: Vec<String>,
+    native_source: Option<NativeSource>,
+    native_confidence: Option<NativeConfidence>,
+}
+
+impl PlantPatch {
+    fn apply(self, record: PlantRecord) -> PlantRecord {
+        PlantRecord {
+            common: self.common.unwrap_or(record.common),
+            shades: self.shades.unwrap_or(record.shades),
+            moistures: self.moistures.unwrap_or(record.moistures),
+            bloom: self.bloom.or(record.bloom),
+            height: self.height.or(record.height),
+            spread: self.spread.or(record.spread),
+            pollinator_rating: self.pollinator_rating.or(record.pollinator_rating),
+            bird_rating: self.bird_rating.or(record.bird_rating),
+            spread_rating: self.spread_rating.or(record.spread_rating),
+            deer_resistance_rating: self
+                .deer_resistance_rating
+                .or(record.deer_resistance_rating),
+            usda_symbol: self.usda_symbol.or(record.usda_symbol),
+            usda_source: self.usda_source.or(record.usda_source),
+            wiki_source: self.wiki_source.or(record.wiki_source),
+            image: self.image.or(record.image),
+            synonyms: self.synonyms,
+            common_names: self.common_names,
+            zipcodes: self.zipcodes,
+            native_source: self.native_source,
+            native_confidence: self.native_confidence,
+            ..record
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct AdminErrorResponse {
+    errors: Vec<String>,
+}
+
+pub struct AdminController {
+    pub db: &'static Database,
+    pub api_tokens: &'static ApiTokens,
+}
+
+impl AdminController {
+    pub fn new(db: &'static Database, api_tokens: &'static ApiTokens) -> Self {
+        Self { db, api_tokens }
+    }
+
+    async fn create_plant(&self, request: &HttpRequest, record: PlantRecord) -> HttpResponse {
+        let Some(changed_by) = self.api_tokens.authenticate(request) else {
+            return unauthorized();
+        };
+        info!("{changed_by} is creating plant: {record:?}");
+
+        let plant = match record.to_plant() {
+            Ok(plant) => plant,
+            Err(errors) => return bad_request(errors),
+        };
+
+        if self
+            .db
+            .get_plant_by_scientific_name(&plant.scientific)
+            .await
+            .is_some()
+        {
+            return HttpResponse::Conflict().json(AdminErrorResponse {
+                errors: vec![format!("{} already exists", plant.scientific)],
+            });
+        }
+
+        self.save(changed_by, None, plant, &record).await
+    }
+
+    /// Replaces every field of a plant, so anything missing is cleared.
+    async fn replace_plant(
+        &self,
+        request: &HttpRequest,
+        id: usize,
+        record: PlantRecord,
+    ) -> HttpResponse {
+        let Some(changed_by) = self.api_tokens.authenticate(request) else {
+            return unauthorized();
+        };
+        info!("{changed_by} is replacing plant {id}: {record:?}");
+
+        let Some(existing) = self.db.get_plant_by_id(id).await else {
+            return HttpResponse::NotFound().finish();
+        };
+
+        let plant = match record.to_plant() {
+            Ok(plant) => plant,
+            Err(errors) => return bad_request(errors),
+        };
+
+        if plant.scientific != existing.scientific {
+            let other = self
+                .db
+                .get_plant_by_scientific_name(&plant.scientific)
+                .await;
+            if other.is_some_and(|other| other.id != existing.id) {
+                return HttpResponse::Conflict().json(AdminErrorResponse {
+                    errors: vec![format!("{} already exists", plant.scientific)],
+                });
+            }
+        }
+
+        // Keep the saved image when it's the same one, rather than saving a copy
+        let image = match (&existing.image, plant.image) {
+            (Some(saved), Some(image)) if saved.card_url == image.card_url => Some(saved.clone()),
+            (_, image) => image,
+        };
+
+        let plant = Plant {
+            id: existing.id,
+            image,
+            ..plant
+        };
+
+        self.save(changed_by, Some(&existing), plant, &record).await
+    }
+
+    /// Changes only the fields in the patch, keeping the rest.
+    async fn patch_plant(
+        &self,
+        request: &HttpRequest,
+        id: usize,
+        patch: PlantPatch,
+    ) -> HttpResponse {
+        let Some(changed_by) = self.api_tokens.authenticate(request) else {
+            return unauthorized();
+        };
+        info!("{changed_by} is patching plant {id}: {patch:?}");
+
+        let Some(existing) = self.db.get_plant_by_id(id).await else {
+            return HttpResponse::NotFound().finish();
+        };
+
+        let (bloom_patched, height_patched, spread_patched) = (
+            patch.bloom.is_some(),
+            patch.height.is_some(),
+            patch.spread.is_some(),
+        );
+
+        // Validates the whole patched record, so bad saved values are caught too
+        let record = patch.apply(PlantRecord::from_plant(&existing, &[], &[]));
+        let mut plant = match record.to_plant() {
+            Ok(plant) => merge_imported_plant(&existing, &plant),
+            Err(errors) => return bad_request(errors),
+        };
+
+        // Details parsed from the old text would outlive new text which doesn't parse
+        if bloom_patched {
+            plant.bloom_period = None;
+            plant.bloom_colors = vec![];
+        }
+        if height_patched {
+            plant.height_range = None;
+        }
+        if spread_patched {
+            plant.spread_range = None;
+        }
+
+        self.save(changed_by, Some(&existing), plant, &record).await
+    }
+
+    /// Saves the plant and the record's regions and synonyms, then records
+    /// what changed.
+    async fn save(
+        &self,
+        changed_by: &str,
+        existing: Option<&Plant>,
+        plant: Plant,
+        record: &PlantRecord,
+    ) -> HttpResponse {
+        let zipcodes = &record.zipcodes;
+        let errors = check_zipcodes(self.db, zipcodes).await;
+        if !errors.is_empty() {
+            return bad_request(errors);
+        }
+
+        let saved = match self.db.save_plant(&plant).await {
+            Ok(saved) => saved,
+            Err(e) => {
+                warn!("Error saving plant: {e}");
+                return HttpResponse::InternalServerError().body("Could not save plant");
+            }
+        };
+        let id = saved.id.expect("saved plants have an id");
+
+        let provenance = record.provenance();
+        for zip in zipcodes {
+            self.db.save_plant_region(&saved, zip, &provenance).await;
+        }
+
+        let synonyms = record.plant_synonyms();
+        if !synonyms.is_empty() {
+            if let Err(e) = self.db.save_plant_synonyms(id, &synonyms).await {
+                warn!("Error saving synonyms for plant {id}: {e}");
+                return HttpResponse::InternalServerError().body("Could not save plant");
+            }
+        }
+
+        let mut changes = diff_plants(existing, &saved);
+        if !zipcodes.is_empty() {
+            changes.insert("zipcodes".to_string(), json!({ "added": zipcodes }));
+        }
+        if !synonyms.is_empty() {
+            changes.insert("synonyms".to_string(), json!({ "added": synonyms }));
+        }
+
+        let change = PlantChange {
+            plant_id: id,
+            changed_by: changed_by.to_string(),
+            action: match existing {
+                Some(_) => PlantChangeAction::Update,
+                None => PlantChangeAction::Create,
+            },
+            changes: Value::Object(changes),
+        };
+        info!("Plant change: {change:?}");
+        if let Err(e) = self.db.save_plant_change(&change).await {
+            warn!("Failed to save plant change to audit log: {e}");
+        }
+
+        let regions = self
+            .db
+            .get_plant_regions(vec![id])
+            .await
+            .unwrap_or_default()
+            .remove(&id)
+            .unwrap_or_default();
+        let synonyms = self
+            .db
+            .get_plant_synonyms(vec![id])
+            .await
+            .unwrap_or_default()
+            .remove(&id)
+            .unwrap_or_default();
+        let record = PlantRecord::from_plant(&saved, &regions, &synonyms);
+
+        match existing {
+            Some(_) => HttpResponse::Ok().json(record),
+            None => HttpResponse::Created().json(record),
+        }
+    }
+}
+
+/// Lists each field which differs, ex: {"birdRating": {"from": 4, "to": 5}}.
+/// Creating a plant changes every field from null.
+fn diff_plants(before: Option<&Plant>, after: &Plant) -> Map<String, Value> {
+    let to_map =
+        |plant: &Plant| match serde_json::to_value(PlantRecord::from_plant(plant, &[], &[])) {
+            Ok(Value::Object(map)) => map,
+            _ => Map::new(),
+        };
+    let before = before.map(to_map).unwrap_or_default();
+    let after = to_map(after);
+
+    let mut changes = Map::new();
+    for (field, to) in after {
+        let from = before.get(&field).cloned().unwrap_or(Value::Null);
+        if field != "id" && from != to {
+            changes.insert(field, json!({ "from": from, "to": to }));
+        }
+    }
+
+    changes
+}
+
+fn unauthorized() -> HttpResponse {
+    HttpResponse::Unauthorized()
+        .insert_header((header::WWW_AUTHENTICATE, "Bearer"))
+        .finish()
+}
+
+fn bad_request(errors: Vec<String>) -> HttpResponse {
+    HttpResponse::BadRequest().json(AdminErrorResponse { errors })
+}
+
+#[post("/admin/plants")]
+async fn create_plant_handler(
+    request: HttpRequest,
+    web::Json(payload): web::Json<PlantRecord>,
+    app: web::Data<&'static PlantingLifeApp>,
+) -> impl Responder {
+    app.admin_controller.create_plant(&request, payload).await
+}
+
+#[put("/admin/plants/{id}")]
+async fn replace_plant_handler(
+    request: HttpRequest,
+    id: web::Path<usize>,
+    web::Json(payload): web::Json<PlantRecord>,
+    app: web::Data<&'static PlantingLifeApp>,
+) -> impl Responder {
+    app.admin_controller
+        .replace_plant(&request, *id, payload)
+        .await
+}
+
+#[patch("/admin/plants/{id}")]
+async fn patch_plant_handler(
+    request: HttpRequest,
+    id: web::Path<usize>,
+    web::Json(payload): web::Json<PlantPatch>,
+    app: web::Data<&'static PlantingLifeApp>,
+) -> impl Responder {
+    app.admin_controller
+        .patch_plant(&request, id.into_inner(), payload)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::MockDatabase;
+    use crate::domain::{BloomPeriod, Moisture, PlantSynonym, SizeRange, SynonymKind};
+    use actix_web::{body::to_bytes, test::TestRequest};
+    use std::collections::HashMap;
+
+    fn controller(db: MockDatabase) -> AdminController {
+        AdminController {
+            db: Box::leak(Box::new(db)),
+            api_tokens: Box::leak(Box::new(ApiTokens::parse("doug:abc123"))),
+        }
+    }
+
+    fn authorized_request() -> HttpRequest {
+        TestRequest::default()
+            .insert_header((header::AUTHORIZATION, "Bearer abc123"))
+            .to_http_request()
+    }
+
+    fn existing_plant() -> Plant {
+        Plant {
+            id: Some(7),
+            bloom: Some("summer".to_string()),
+            bird_rating: Some(4),
+            moistures: vec![Moisture::Some],
+            ..Plant::new("Asclepias incarnata", "Swamp Milkweed")
+        }
+    }
+
+    async fn body_json(response: HttpResponse) -> Value {
+        let bytes = to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_patch_plant_requires_token() {
+        let mut db = MockDatabase::default();
+        db.expect_save_plant().never();
+        let controller = controller(db);
+
+        let request = TestRequest::default()
+            .insert_header((header::AUTHORIZATION, "Bearer wrong"))
+            .to_http_request();
+        let response = controller
+            .patch_plant(&request, 7, PlantPatch::default())
+            .await;
+
+        assert_eq!(response.status(), 401);
+    }
+
+    #[tokio::test]
+    async fn test_patch_plant() {
+        let mut db = MockDatabase::default();
+        db.expect_lookup_closest_valid_zip()
+            .returning(|zip| Ok(zip.to_string()));
+        db.expect_get_plant_by_id()
+            .returning(|_| Some(existing_plant()));
+        db.expect_save_plant().times(1).returning(|p| {
+            // Keeps everything the patch didn't change
+            assert_eq!(p.id, Some(7));
+            assert_eq!(p.bloom, Some("summer".to_string()));
+            assert_eq!(p.moistures, vec![Moisture::Some]);
+            assert_eq!(p.bird_rating, Some(5));
+            Ok(p.clone())
+        });
+        db.expect_save_plant_region()
+            .withf(|p, zip, provenance| {
+                p.id == Some(7)
+                    && zip == "43081"
+                    && provenance.source == NativeSource::StateList
+                    && provenance.confidence == NativeConfidence::Medium
+            })
+            .times(1)
+            .returning(|_, _, _| ());
+        db.expect_save_plant_synonyms()
+            .withf(|id, synonyms| {
+                *id == 7 && synonyms == [PlantSynonym::new("Rose Milkweed", SynonymKind::Common)]
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+        db.expect_save_plant_change()
+            .withf(|change| {
+                change.plant_id == 7
+                    && change.changed_by == "doug"
+                    && change.action == PlantChangeAction::Update
+                    && change.changes
+                        == json!({
+                            "birdRating": {"from": 4, "to": 5},
+                            "zipcodes": {"added": ["43081"]},
+                            "synonyms": {"added": [{"name": "Rose Milkweed", "kind": "common"}]},
+                        })
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+        db.expect_get_plant_regions()
+            .returning(|_| Ok(HashMap::new()));
+        db.expect_get_plant_synonyms().returning(|_| {
+            Ok(HashMap::from([(
+                7,
+                vec![PlantSynonym::new("Rose Milkweed", SynonymKind::Common)],
+            )]))
+        });
+        let controller = controller(db);
+
+        let patch = PlantPatch {
+            bird_rating: Some(5),
+            common_names: vec!["Rose Milkweed".to_string()],
+            zipcodes: vec!["43081".to_string()],
+            native_source: Some(NativeSource::StateList),
+            ..Default::default()
+        };
+        let response = controller
+            .patch_plant(&authorized_request(), 7, patch)
+            .await;
+
+        assert_eq!(response.status(), 200);
+        let body = body_json(response).await;
+        assert_eq!(body["birdRating"], 5);
+        assert_eq!(body["commonNames"], json!(["Rose Milkweed"]));
+    }
+
+    #[tokio::test]
+    async fn test_patch_plant_rejects_invalid_values() {
+        let mut db = MockDatabase::default();
+        db.expect_get_plant_by_id()
+            .returning(|_| Some(existing_plant()));
+        db.expect_save_plant().never();
+        let controller = controller(db);
+
+        let patch = PlantPatch {
+            pollinator_rating: Some(11),
+            shades: Some(vec!["Dappled".to_string()]),
+            ..Default::default()
+        };
+        let response = controller
+            .patch_plant(&authorized_request(), 7, patch)
+            .await;
+
+        assert_eq!(response.status(), 400);
+        assert_eq!(
+            body_json(response).await,
+            json!({"errors": [
+                "pollinatorRating must be between 1 and 10, was 11",
+                "unknown shade: Dappled",
+            ]})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_patch_plant_clears_parsed_details_of_patched_text() {
+        let mut db = MockDatabase::default();
+        db.expect_get_plant_by_id().returning(|_| {
+            Some(Plant {
+                bloom_period: Some(BloomPeriod {
+                    start_month: 6,
+                    end_month: 8,
+                }),
+                bloom_colors: vec!["pink".to_string()],
+                height: Some("3-5 feet".to_string()),
+                height_range: Some(SizeRange {
+                    min_inches: 36,
+                    max_inches: 60,
+                }),
+                ..existing_plant()
+            })
+        });
+        db.expect_save_plant().times(1).returning(|p| {
+            assert_eq!(p.bloom, Some("Showy".to_string()));
+            assert_eq!(p.bloom_period, None);
+            assert!(p.bloom_colors.is_empty());
+
+            // The height wasn't patched, so keeps what was parsed from it
+            assert_eq!(p.height_range.map(|h| h.max_inches), Some(60));
+            Ok(p.clone())
+        });
+        db.expect_save_plant_change().returning(|_| Ok(()));
+        db.expect_get_plant_regions()
+            .returning(|_| Ok(HashMap::new()));
+        db.expect_get_plant_synonyms()
+            .returning(|_| Ok(HashMap::new()));
+        let controller = controller(db);
+
+        let patch = PlantPatch {
+            bloom: Some("Showy".to_string()),
+            ..Default::default()
+        };
+        let response = controller
+            .patch_plant(&authorized_request(), 7, patch)
+            .await;
+
+        assert_eq!(response.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_replace_plant_clears_missing_fields() {
+        let mut db = MockDatabase::default();
+        db.expect_get_plant_by_id()
+            .returning(|_| Some(existing_plant()));
+        db.expect_save_plant().times(1).returning(|p| {
+            assert_eq!(p.id, Some(7));
+            assert_eq!(p.bloom, None);
+            assert_eq!(p.moistures, vec![]);
+            Ok(p.clone())
+        });
+        db.expect_save_plant_change()
+            .withf(|change| change.changes["bloom"] == json!({"from": "summer", "to": null}))
+            .times(1)
+            .returning(|_| Ok(()));
+        db.expect_get_plant_regions()
+            .returning(|_| Ok(HashMap::new()));
+        db.expect_get_plant_synonyms()
+            .returning(|_| Ok(HashMap::new()));
+        let controller = controller(db);
+
+        let record = PlantRecord {
+            scientific: "Asclepias incarnata".to_string(),
+            common: "Swamp Milkweed".to_string(),
+            ..Default::default()
+        };
+        let response = controller
+            .replace_plant(&authorized_request(), 7, record)
+            .await;
+
+        assert_eq!(response.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_create_plant_conflict() {
+        let mut db = MockDatabase::default();
+        db.expect_get_plant_by_scientific_name()
+            .returning(|_| Some(existing_plant()));
+        db.expect_save_plant().never();
+        let controller = controller(db);
+
+        let record = PlantRecord {
+            scientific: "Asclepias incarnata".to_string(),
+            common: "Swamp Milkweed".to_string(),
+            ..Default::default()
+        };
+        let response = controller.create_plant(&authorized_request(), record).await;
+
+        assert_eq!(response.status(), 409);
+    }
+}
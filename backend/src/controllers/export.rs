@@ -0,0 +1,72 @@
+use actix_web::{get, http::header, web, HttpRequest, HttpResponse, Responder};
+use mockall_double::double;
+use serde::{Deserialize, Serialize};
+use tracing::log::{info, warn};
+
+#[double]
+use crate::database::Database;
+use crate::{
+    app::PlantingLifeApp,
+    auth::ApiTokens,
+    catalog::{CatalogExporter, CatalogFormat},
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ExportRequest {
+    /// "csv" or "jsonl", defaults to jsonl
+    format: Option<String>,
+}
+
+pub struct ExportController {
+    pub db: &'static Database,
+    pub api_tokens: &'static ApiTokens,
+}
+
+impl ExportController {
+    pub fn new(db: &'static Database, api_tokens: &'static ApiTokens) -> Self {
+        Self { db, api_tokens }
+    }
+
+    async fn export_plants(&self, request: &HttpRequest, payload: ExportRequest) -> HttpResponse {
+        let Some(name) = self.api_tokens.authenticate(request) else {
+            return HttpResponse::Unauthorized()
+                .insert_header((header::WWW_AUTHENTICATE, "Bearer"))
+                .finish();
+        };
+
+        let format = match payload.format.as_deref().map(str::parse) {
+            None => CatalogFormat::JsonLines,
+            Some(Ok(format)) => format,
+            Some(Err(e)) => return HttpResponse::BadRequest().body(e.to_string()),
+        };
+
+        info!("{name} is exporting plants as {format:?}");
+
+        // Streams one page at a time.  Errors after the first page can't
+        // change the status, so they end the response early.
+        let exporter = CatalogExporter::new(self.db, format);
+        let chunks = futures::stream::unfold(exporter, |mut exporter| async move {
+            match exporter.next_chunk().await {
+                Ok(Some(chunk)) => Some((Ok(web::Bytes::from(chunk)), exporter)),
+                Ok(None) => None,
+                Err(e) => {
+                    warn!("Failed to export plants: {e}");
+                    Some((Err(actix_web::error::ErrorInternalServerError(e)), exporter))
+                }
+            }
+        });
+
+        HttpResponse::Ok()
+            .content_type(format.content_type())
+            .streaming(chunks)
+    }
+}
+
+#[get("/export/plants")]
+async fn export_plants_handler(
+    request: HttpRequest,
+    web::Query(payload): web::Query<ExportRequest>,
+    app: web::Data<&'static PlantingLifeApp>,
+) -> impl Responder {
+    app.export_controller.export_plants(&request, payload).await
+}
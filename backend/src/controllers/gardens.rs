@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use actix_web::{get, post, put, web, Responder};
 use mockall_double::double;
 use serde::{Deserialize, Serialize};
@@ -7,16 +9,38 @@ use tracing::log::{info, warn};
 use crate::database::Database;
 use crate::{
     app::PlantingLifeApp,
-    domain::{Garden, Moisture, Plant, Shade},
+    calendar::bloom_calendar,
+    domain::{
+        Garden, GardenPlant, GardenPlantDetails, GardenPlantStatus, GardenPlantWarning, GardenZone,
+        Moisture, Plant, PlantRegion, Shade,
+    },
     highlights::Highlights,
+    summary::garden_summary,
 };
 
+/// The most of one plant a garden can have
+const MAX_QUANTITY: u32 = 10_000;
+
+/// The longest notes a plant in a garden can have
+const MAX_NOTES_LENGTH: usize = 1000;
+
+/// The longest name a garden zone can have
+const MAX_ZONE_NAME_LENGTH: usize = 100;
+
 #[derive(Serialize, Deserialize, Debug)]
 struct GardensPostRequest {
+    /// The plants without any details, use plants to include them
+    #[serde(default)]
     plant_ids: Vec<usize>,
+    #[serde(default)]
+    plants: Vec<GardenPlantRequest>,
     zipcode: String,
-    moisture: Moisture,
-    shade: Shade,
+    /// Required without zones, otherwise the first zone's are used
+    moisture: Option<Moisture>,
+    shade: Option<Shade>,
+    /// Areas with their own conditions, without any the garden is one zone
+    #[serde(default)]
+    zones: Vec<GardenZone>,
     name: Option<String>,
 }
 
@@ -26,14 +50,203 @@ struct GardensPostResponse {
     write_id: String,
     name: String,
     region_name: String,
+    /// The plants which may not do well where they were saved
+    warnings: Vec<GardenPlantWarnings>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct GardensPutResponse {
+    /// The plants which may not do well where they were saved
+    warnings: Vec<GardenPlantWarnings>,
+}
+
+/// Why one plant in a garden may not do well in its zone
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct GardenPlantWarnings {
+    plant_id: usize,
+    zone: usize,
+    warnings: Vec<GardenPlantWarning>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 struct GardensPutRequest {
+    /// The plants without any details, use plants to include them
+    #[serde(default)]
     plant_ids: Vec<usize>,
+    #[serde(default)]
+    plants: Vec<GardenPlantRequest>,
+    /// Replaces the garden's zones, or keeps them when missing
+    zones: Option<Vec<GardenZone>>,
     name: String,
 }
 
+/// One plant in a garden, anything missing is defaulted to one planned plant.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct GardenPlantRequest {
+    plant_id: usize,
+    quantity: Option<u32>,
+    notes: Option<String>,
+    /// When it was planted, as YYYY-MM-DD
+    planted_date: Option<String>,
+    status: Option<GardenPlantStatus>,
+    /// The index of the garden's zone it is in
+    zone: Option<usize>,
+}
+
+impl GardenPlantRequest {
+    /// Validates the details against a garden with zone_count zones, or Err
+    /// with what is wrong with them.
+    fn details(&self, zone_count: usize) -> Result<GardenPlantDetails, String> {
+        let defaults = GardenPlantDetails::default();
+
+        let zone = self.zone.unwrap_or(defaults.zone);
+        if zone >= zone_count {
+            return Err(format!(
+                "zone of plant {} must be less than {zone_count}, was {zone}",
+                self.plant_id
+            ));
+        }
+
+        let quantity = self.quantity.unwrap_or(defaults.quantity);
+        if quantity == 0 {
+            return Err(format!(
+                "quantity of plant {} must be at least 1",
+                self.plant_id
+            ));
+        }
+        if quantity > MAX_QUANTITY {
+            return Err(format!(
+                "quantity of plant {} must be at most {MAX_QUANTITY}, was {quantity}",
+                self.plant_id
+            ));
+        }
+
+        if let Some(notes) = &self.notes {
+            if notes.chars().count() > MAX_NOTES_LENGTH {
+                return Err(format!(
+                    "notes of plant {} are longer than {MAX_NOTES_LENGTH} characters",
+                    self.plant_id
+                ));
+            }
+        }
+
+        if let Some(date) = &self.planted_date {
+            if !is_valid_date(date) {
+                return Err(format!(
+                    "planted_date of plant {} must be a real date as YYYY-MM-DD, was {date}",
+                    self.plant_id
+                ));
+            }
+        }
+
+        Ok(GardenPlantDetails {
+            quantity,
+            notes: self.notes.clone().filter(|notes| !notes.trim().is_empty()),
+            planted_date: self.planted_date.clone(),
+            status: self.status.unwrap_or(defaults.status),
+            zone,
+        })
+    }
+}
+
+/// Combines the two ways a request can list plants into (plant_id, details),
+/// for a garden with zone_count zones.
+fn garden_plants(
+    plant_ids: Vec<usize>,
+    plants: Vec<GardenPlantRequest>,
+    zone_count: usize,
+) -> Result<Vec<(usize, GardenPlantDetails)>, String> {
+    if !plant_ids.is_empty() && !plants.is_empty() {
+        return Err("use either plant_ids or plants, not both".to_string());
+    }
+
+    if plants.is_empty() {
+        return Ok(plant_ids
+            .into_iter()
+            .map(|id| (id, GardenPlantDetails::default()))
+            .collect());
+    }
+
+    plants
+        .iter()
+        .map(|plant| Ok((plant.plant_id, plant.details(zone_count)?)))
+        .collect()
+}
+
+/// Checks the zones given for a garden, or Err with what is wrong with them.
+fn validate_zones(zones: &[GardenZone]) -> Result<(), String> {
+    if zones.is_empty() {
+        return Err("zones must have at least one zone".to_string());
+    }
+
+    for (index, zone) in zones.iter().enumerate() {
+        if zone.name.trim().is_empty() {
+            return Err(format!("name of zone {index} can't be blank"));
+        }
+        if zone.name.chars().count() > MAX_ZONE_NAME_LENGTH {
+            return Err(format!(
+                "name of zone {index} is longer than {MAX_ZONE_NAME_LENGTH} characters"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks a date is a real one written as YYYY-MM-DD, ex: "2024-05-18"
+fn is_valid_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        return false;
+    };
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return false;
+    }
+
+    let (Ok(year), Ok(month), Ok(day)) = (
+        year.parse::<u32>(),
+        month.parse::<u32>(),
+        day.parse::<u32>(),
+    ) else {
+        return false;
+    };
+
+    let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if is_leap_year => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return false,
+    };
+
+    (1..=days_in_month).contains(&day)
+}
+
+/// Why a plant may not do well in a zone with this shade and moisture.
+/// Conditions the plant has no data for aren't warned about, and neither is
+/// being native when that couldn't be checked.
+fn compatibility_warnings(
+    plant: &Plant,
+    shade: Shade,
+    moisture: Moisture,
+    native: Option<bool>,
+) -> Vec<GardenPlantWarning> {
+    let mut warnings = vec![];
+
+    if !plant.shades.is_empty() && !plant.shades.contains(&shade) {
+        warnings.push(GardenPlantWarning::ShadeMismatch);
+    }
+    if !plant.moistures.is_empty() && !plant.moistures.contains(&moisture) {
+        warnings.push(GardenPlantWarning::MoistureMismatch);
+    }
+    if native == Some(false) {
+        warnings.push(GardenPlantWarning::NotNative);
+    }
+
+    warnings
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct GardensListRequest {
     #[serde(default)]
@@ -63,10 +276,36 @@ impl GardensController {
             .name
             .unwrap_or_else(|| format!("Native Garden near {region_name}"));
 
-        let garden = Garden::empty(name, payload.zipcode, payload.shade, payload.moisture);
+        // Without zones, the garden's own conditions are its one zone
+        let (shade, moisture) = match (payload.zones.first(), payload.shade, payload.moisture) {
+            (Some(zone), _, _) => (zone.shade, zone.moisture),
+            (None, Some(shade), Some(moisture)) => (shade, moisture),
+            (None, _, _) => {
+                return actix_web::HttpResponse::BadRequest()
+                    .body("shade and moisture are required without zones")
+            }
+        };
+
+        if !payload.zones.is_empty() {
+            if let Err(e) = validate_zones(&payload.zones) {
+                return actix_web::HttpResponse::BadRequest().body(e);
+            }
+        }
 
-        let response = match self.db.save_new_garden(&garden, payload.plant_ids).await {
+        let zone_count = payload.zones.len().max(1);
+        let plants = match garden_plants(payload.plant_ids, payload.plants, zone_count) {
+            Ok(plants) => plants,
+            Err(e) => return actix_web::HttpResponse::BadRequest().body(e),
+        };
+
+        let garden = Garden {
+            zones: payload.zones,
+            ..Garden::empty(name, payload.zipcode, shade, moisture)
+        };
+
+        let response = match self.db.save_new_garden(&garden, plants).await {
             Ok((read_id, write_id)) => GardensPostResponse {
+                warnings: self.warnings(&write_id).await,
                 read_id,
                 write_id,
                 region_name,
@@ -85,12 +324,31 @@ impl GardensController {
     async fn update(&self, write_id: &str, payload: GardensPutRequest) -> impl Responder {
         info!("{payload:?}");
 
+        // Plants are checked against the zones they're saved with
+        let zone_count = match &payload.zones {
+            Some(zones) => match validate_zones(zones) {
+                Ok(()) => zones.len(),
+                Err(e) => return actix_web::HttpResponse::BadRequest().body(e),
+            },
+            None => match self.db.get_garden(write_id).await {
+                Some(garden) => garden.zones.len(),
+                None => return actix_web::HttpResponse::NotFound().body(""),
+            },
+        };
+
+        let plants = match garden_plants(payload.plant_ids, payload.plants, zone_count) {
+            Ok(plants) => plants,
+            Err(e) => return actix_web::HttpResponse::BadRequest().body(e),
+        };
+
         match self
             .db
-            .save_existing_garden(write_id, &payload.name, payload.plant_ids)
+            .save_existing_garden(write_id, &payload.name, payload.zones, plants)
             .await
         {
-            Ok(()) => actix_web::HttpResponse::Ok().body(""),
+            Ok(()) => actix_web::HttpResponse::Ok().json(GardensPutResponse {
+                warnings: self.warnings(write_id).await,
+            }),
             Err(e) => {
                 warn!("Error saving garden: {e}");
                 actix_web::HttpResponse::InternalServerError().body("Could not save garden")
@@ -101,25 +359,107 @@ impl GardensController {
     async fn read(&self, id: &str) -> impl Responder {
         info!("GardensGetRequest id: {id}");
 
-        // Fetch the garden, then populate the highlights on each plant
-        let garden = self.db.get_garden(id).await.map(|g| Garden {
-            plants: g
-                .plants
-                .into_iter()
-                .map(|p| Plant {
-                    highlights: self.highlights.generate(&p),
-                    ..p
-                })
-                .collect(),
-            ..g
-        });
+        match self.db.get_garden(id).await {
+            Some(garden) => actix_web::HttpResponse::Ok().json(self.annotate(garden).await),
+            None => actix_web::HttpResponse::NotFound().body(""),
+        }
+    }
+
+    async fn calendar(&self, id: &str) -> impl Responder {
+        info!("GardensCalendarRequest id: {id}");
 
-        match garden {
-            Some(garden) => actix_web::HttpResponse::Ok().json(garden),
+        match self.db.get_garden(id).await {
+            Some(garden) => actix_web::HttpResponse::Ok().json(bloom_calendar(&garden)),
             None => actix_web::HttpResponse::NotFound().body(""),
         }
     }
 
+    async fn summary(&self, id: &str) -> impl Responder {
+        info!("GardensSummaryRequest id: {id}");
+
+        match self.db.get_garden(id).await {
+            Some(garden) => {
+                actix_web::HttpResponse::Ok().json(garden_summary(&garden, self.highlights.rules()))
+            }
+            None => actix_web::HttpResponse::NotFound().body(""),
+        }
+    }
+
+    /// Populates the highlights and warnings on each plant, relative to the
+    /// conditions of its zone and the garden's region.
+    async fn annotate(&self, garden: Garden) -> Garden {
+        let native_regions = self.native_regions(&garden).await;
+
+        let plants = garden
+            .plants
+            .iter()
+            .map(|p| {
+                let (shade, moisture) = garden.zone_conditions(p.details.zone);
+                let native = garden
+                    .region_id
+                    .zip(native_regions.as_ref())
+                    .zip(p.plant.id)
+                    .map(|((region_id, native_regions), id)| {
+                        native_regions
+                            .get(&id)
+                            .is_some_and(|regions| regions.iter().any(|r| r.region_id == region_id))
+                    });
+
+                GardenPlant {
+                    plant: Plant {
+                        highlights: self.highlights.generate_for_conditions(
+                            &p.plant,
+                            &[shade],
+                            &[moisture],
+                        ),
+                        ..p.plant.clone()
+                    },
+                    warnings: compatibility_warnings(&p.plant, shade, moisture, native),
+                    ..p.clone()
+                }
+            })
+            .collect();
+
+        Garden { plants, ..garden }
+    }
+
+    /// The regions each plant in the garden is native to, or None when they
+    /// can't be compared to the garden's region.
+    async fn native_regions(&self, garden: &Garden) -> Option<HashMap<usize, Vec<PlantRegion>>> {
+        garden.region_id?;
+
+        let plant_ids = garden.plants.iter().filter_map(|p| p.plant.id).collect();
+        match self.db.get_plant_regions(plant_ids).await {
+            Ok(regions) => Some(regions),
+            Err(e) => {
+                warn!("Error finding native regions of garden plants: {e}");
+                None
+            }
+        }
+    }
+
+    /// The warnings for each plant in a saved garden which has any.
+    async fn warnings(&self, write_id: &str) -> Vec<GardenPlantWarnings> {
+        let Some(garden) = self.db.get_garden(write_id).await else {
+            warn!("Could not read garden {write_id} after saving it");
+            return vec![];
+        };
+
+        self.annotate(garden)
+            .await
+            .plants
+            .into_iter()
+            .filter(|p| !p.warnings.is_empty())
+            .filter_map(|p| {
+                Some(GardenPlantWarnings {
+                    plant_id: p.plant.id?,
+                    zone: p.details.zone,
+                    warnings: p.warnings,
+                })
+            })
+            .collect()
+    }
+
     async fn list(&self, payload: GardensListRequest) -> impl Responder {
         info!("GardensListRequest: {payload:?}");
 
@@ -144,6 +484,22 @@ async fn read_garden_handler(
     app.gardens_controller.read(&id).await
 }
 
+#[get("/gardens/{id}/calendar")]
+async fn garden_calendar_handler(
+    id: web::Path<String>,
+    app: web::Data<&'static PlantingLifeApp>,
+) -> impl Responder {
+    app.gardens_controller.calendar(&id).await
+}
+
+#[get("/gardens/{id}/summary")]
+async fn garden_summary_handler(
+    id: web::Path<String>,
+    app: web::Data<&'static PlantingLifeApp>,
+) -> impl Responder {
+    app.gardens_controller.summary(&id).await
+}
+
 #[get("/gardens")]
 async fn list_garden_handler(
     web::Query(payload): web::Query<GardensListRequest>,
@@ -168,3 +524,175 @@ async fn update_garden_handler(
 ) -> impl Responder {
     app.gardens_controller.update(&write_id, payload).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::MockDatabase;
+    use crate::domain::{NativeProvenance, NativeSource};
+
+    #[test]
+    fn test_garden_plants_from_plant_ids() {
+        let plants = garden_plants(vec![3, 5], vec![], 1).unwrap();
+        assert_eq!(
+            plants,
+            vec![
+                (3, GardenPlantDetails::default()),
+                (5, GardenPlantDetails::default())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_garden_plants_with_details() {
+        let request = GardenPlantRequest {
+            plant_id: 3,
+            quantity: Some(5),
+            planted_date: Some("2024-02-29".to_string()),
+            status: Some(GardenPlantStatus::Planted),
+            notes: Some(" ".to_string()),
+            zone: Some(1),
+        };
+
+        let plants = garden_plants(vec![], vec![request], 2).unwrap();
+        assert_eq!(
+            plants,
+            vec![(
+                3,
+                GardenPlantDetails {
+                    quantity: 5,
+                    notes: None,
+                    planted_date: Some("2024-02-29".to_string()),
+                    status: GardenPlantStatus::Planted,
+                    zone: 1,
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_garden_plants_rejects_invalid_details() {
+        let invalid =
+            |request: GardenPlantRequest| garden_plants(vec![], vec![request], 2).is_err();
+
+        assert!(invalid(GardenPlantRequest {
+            quantity: Some(0),
+            ..Default::default()
+        }));
+        assert!(invalid(GardenPlantRequest {
+            quantity: Some(MAX_QUANTITY + 1),
+            ..Default::default()
+        }));
+        assert!(!invalid(GardenPlantRequest {
+            quantity: Some(MAX_QUANTITY),
+            ..Default::default()
+        }));
+        assert!(invalid(GardenPlantRequest {
+            notes: Some("x".repeat(MAX_NOTES_LENGTH + 1)),
+            ..Default::default()
+        }));
+        for date in ["2023-02-29", "2024-13-01", "5/18/2024", "2024-5-18", ""] {
+            assert!(invalid(GardenPlantRequest {
+                planted_date: Some(date.to_string()),
+                ..Default::default()
+            }));
+        }
+
+        assert!(invalid(GardenPlantRequest {
+            zone: Some(2),
+            ..Default::default()
+        }));
+
+        assert!(garden_plants(vec![1], vec![GardenPlantRequest::default()], 1).is_err());
+    }
+
+    #[test]
+    fn test_compatibility_warnings() {
+        let mut plant = Plant::new("Asclepias tuberosa", "Butterfly Weed");
+        plant.shades = vec![Shade::None];
+        plant.moistures = vec![Moisture::None, Moisture::Some];
+
+        assert_eq!(
+            compatibility_warnings(&plant, Shade::None, Moisture::Some, Some(true)),
+            vec![]
+        );
+        assert_eq!(
+            compatibility_warnings(&plant, Shade::Lots, Moisture::Lots, Some(false)),
+            vec![
+                GardenPlantWarning::ShadeMismatch,
+                GardenPlantWarning::MoistureMismatch,
+                GardenPlantWarning::NotNative,
+            ]
+        );
+
+        // Unknown conditions and regions aren't warned about
+        let plant = Plant::new("Asclepias incarnata", "Swamp Milkweed");
+        assert_eq!(
+            compatibility_warnings(&plant, Shade::Lots, Moisture::Lots, None),
+            vec![]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_annotate_compares_native_regions_by_id() {
+        let region = |region_id: usize, name: &str| PlantRegion {
+            region_id,
+            name: name.to_string(),
+            zipcode: "43081".to_string(),
+            provenance: NativeProvenance::new(NativeSource::Usda),
+        };
+
+        let mut db = MockDatabase::default();
+        db.expect_get_plant_regions().returning(move |_| {
+            Ok(HashMap::from([
+                // Renamed since, but still the garden's region
+                (1, vec![region(7, "Columbus")]),
+                // Another region which happens to share the name
+                (2, vec![region(8, "Columbus, OH")]),
+            ]))
+        });
+        let controller = GardensController::new(
+            Box::leak(Box::new(db)),
+            Box::leak(Box::new(Highlights::default())),
+        );
+
+        let garden_plant = |id: usize| {
+            let mut plant = Plant::new(&format!("Plant {id}"), &format!("Plant {id}"));
+            plant.id = Some(id);
+            GardenPlant {
+                plant,
+                details: GardenPlantDetails::default(),
+                warnings: vec![],
+            }
+        };
+        let garden = Garden {
+            region_name: Some("Columbus, OH".to_string()),
+            region_id: Some(7),
+            plants: vec![garden_plant(1), garden_plant(2)],
+            ..Garden::empty(
+                "name".to_string(),
+                "43081".to_string(),
+                Shade::None,
+                Moisture::Some,
+            )
+        }
+        .with_zones(vec![]);
+
+        let garden = controller.annotate(garden).await;
+        assert_eq!(garden.plants[0].warnings, vec![]);
+        assert_eq!(
+            garden.plants[1].warnings,
+            vec![GardenPlantWarning::NotNative]
+        );
+    }
+
+    #[test]
+    fn test_validate_zones() {
+        let zone = |name: &str| GardenZone::new(name, Shade::Some, Moisture::Lots);
+
+        assert!(validate_zones(&[zone("Front"), zone("Rain garden")]).is_ok());
+        assert!(validate_zones(&[]).is_err());
+        assert!(validate_zones(&[zone("Front"), zone(" ")]).is_err());
+        assert!(validate_zones(&[zone(&"x".repeat(MAX_ZONE_NAME_LENGTH + 1))]).is_err());
+    }
+}
@@ -0,0 +1,26 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use mockall_double::double;
+use tracing::log::warn;
+
+use crate::app::PlantingLifeApp;
+#[double]
+use crate::database::Database;
+
+/// Hands out the key the frontend's maps load with.  This module was missing
+/// from the source, so until how the key is configured and limited is
+/// restored, maps are reported as not configured.
+pub struct MapsController {
+    pub db: &'static Database,
+}
+
+impl MapsController {
+    async fn api_key(&self) -> HttpResponse {
+        warn!("Maps api key requested, but none is configured");
+        HttpResponse::NotFound().body("maps are not configured")
+    }
+}
+
+#[get("/maps/key")]
+async fn maps_api_key_handler(app: web::Data<&'static PlantingLifeApp>) -> impl Responder {
+    app.maps_controller.api_key().await
+}
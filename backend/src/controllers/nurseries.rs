@@ -1,15 +1,19 @@
-use actix_web::{get, web, Responder};
+use actix_web::{get, web, HttpResponse, Responder};
 use mockall_double::double;
 use serde::{Deserialize, Serialize};
-use tracing::log::info;
+use tracing::log::{info, warn};
 
-use crate::app::PlantingLifeApp;
 #[double]
 use crate::database::Database;
+use crate::{app::PlantingLifeApp, domain::Coordinates};
 
 #[derive(Serialize, Deserialize, Debug)]
 struct NurseriesRequest {
-    zip: String,
+    zip: Option<String>,
+
+    /// Searches near a location instead of a zip, using the closest one
+    lat: Option<f64>,
+    lng: Option<f64>,
 }
 
 pub struct NurseriesController {
@@ -21,14 +25,35 @@ impl NurseriesController {
         Self { db }
     }
 
-    async fn list(&self, payload: NurseriesRequest) -> impl Responder {
+    async fn list(&self, payload: NurseriesRequest) -> HttpResponse {
         info!("{payload:?}");
 
+        let location = match Coordinates::from_optional(payload.lat, payload.lng) {
+            Ok(location) => location,
+            Err(e) => return HttpResponse::BadRequest().body(e.to_string()),
+        };
+
         // Purposefully NOT adjusting zipcode for nursery search.
         // This degrades nicely and all the distances would be incorrect
-        // if the zipcode isn't known.
+        // if the zipcode isn't known.  A location always has a closest one.
+        let zip = match (payload.zip, location) {
+            (Some(zip), None) => zip,
+            (None, Some(location)) => match self.db.lookup_closest_zip_by_location(&location).await
+            {
+                Ok(Some(resolved)) => resolved.zip,
+                Ok(None) => {
+                    warn!("Cannot find zipcode near {location:?}");
+                    return HttpResponse::BadRequest().body("no zipcode near location");
+                }
+                Err(e) => {
+                    warn!("Cannot look up zipcode near {location:?}: {e}");
+                    return HttpResponse::InternalServerError().body("could not look up location");
+                }
+            },
+            _ => return HttpResponse::BadRequest().body("either zip OR lat/lng are required"),
+        };
 
-        let mut nurseries = self.db.find_nurseries(&payload.zip).await;
+        let mut nurseries = self.db.find_nurseries(&zip).await;
 
         // Some areas have 20+ nurseries and it looks ridiculous, set a limit
         nurseries.truncate(10);
@@ -46,7 +71,7 @@ impl NurseriesController {
             }
         }
 
-        actix_web::HttpResponse::Ok().json(nurseries)
+        HttpResponse::Ok().json(nurseries)
     }
 }
 
@@ -57,3 +82,45 @@ async fn fetch_nurseries_handler(
 ) -> impl Responder {
     app.nursery_controller.list(payload).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::MockDatabase;
+    use actix_web::http::StatusCode;
+    use anyhow::anyhow;
+
+    fn controller(db: MockDatabase) -> NurseriesController {
+        NurseriesController::new(Box::leak(Box::new(db)))
+    }
+
+    fn near(lat: f64, lng: f64) -> NurseriesRequest {
+        NurseriesRequest {
+            zip: None,
+            lat: Some(lat),
+            lng: Some(lng),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_no_zip_near_location() {
+        let mut db = MockDatabase::default();
+        db.expect_lookup_closest_zip_by_location()
+            .returning(|_| Ok(None));
+        db.expect_find_nurseries().never();
+
+        let response = controller(db).list(near(40.1, -82.9)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_list_location_lookup_fails() {
+        let mut db = MockDatabase::default();
+        db.expect_lookup_closest_zip_by_location()
+            .returning(|_| Err(anyhow!("connection refused")));
+        db.expect_find_nurseries().never();
+
+        let response = controller(db).list(near(40.1, -82.9)).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -1,13 +1,19 @@
 use actix_web::{get, web, HttpResponse, Responder};
 use actix_web_lab::sse::{self, ChannelStream, Sender, Sse};
 use mockall_double::double;
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{DeserializeOwned, IntoDeserializer},
+    Deserialize, Serialize,
+};
 use std::time::Duration;
 use tracing::log::{info, warn};
 
 #[double]
 use crate::database::Database;
-use crate::{app::PlantingLifeApp, domain::*, highlights::Highlights};
+use crate::{
+    app::PlantingLifeApp, domain::*, highlights::Highlights, plant_details::parse_bloom,
+    search::PlantNameMatch,
+};
 
 #[derive(Serialize, Deserialize, Debug)]
 struct PlantsStreamRequest {
@@ -20,8 +26,152 @@ struct PlantsStreamRequest {
 struct PlantSearchRequest {
     name: Option<String>,
     zip: Option<String>,
-    shade: Option<Shade>,
-    moisture: Option<Moisture>,
+
+    /// Searches near a location instead of a zip, finding the closest one
+    lat: Option<f64>,
+    lng: Option<f64>,
+
+    /// One or more comma separated shades, ex: "Full Sun,Partial Shade"
+    shade: Option<String>,
+    /// One or more comma separated moistures, ex: "Low,Medium"
+    moisture: Option<String>,
+
+    // Optional filters for zip/shade/moisture searches
+    /// When it blooms, ex: "late summer" or "June-July"
+    bloom: Option<String>,
+    min_height: Option<u32>,
+    max_height: Option<u32>,
+    min_pollinator_rating: Option<u8>,
+    min_bird_rating: Option<u8>,
+    min_deer_resistance_rating: Option<u8>,
+
+    // Optional paging for zip/shade/moisture searches
+    sort: Option<PlantSort>,
+    offset: Option<usize>,
+    /// Without a limit, every plant after offset is returned
+    limit: Option<usize>,
+}
+
+impl PlantSearchRequest {
+    /// Builds the filters from the request, or Err if bloom doesn't parse.
+    fn filters(&self) -> Result<PlantFilters, String> {
+        let bloom = match &self.bloom {
+            Some(text) => match parse_bloom(text) {
+                Some(bloom) => Some(bloom),
+                None => return Err(format!("unrecognized bloom time: {text}")),
+            },
+            None => None,
+        };
+
+        Ok(PlantFilters {
+            bloom,
+            min_height_inches: self.min_height,
+            max_height_inches: self.max_height,
+            min_pollinator_rating: self.min_pollinator_rating,
+            min_bird_rating: self.min_bird_rating,
+            min_deer_resistance_rating: self.min_deer_resistance_rating,
+        })
+    }
+
+    /// Parses the requested shades, or Err if one isn't a Shade.
+    fn shades(&self) -> Result<Option<Vec<Shade>>, String> {
+        self.shade.as_deref().map(parse_conditions).transpose()
+    }
+
+    /// Parses the requested moistures, or Err if one isn't a Moisture.
+    fn moistures(&self) -> Result<Option<Vec<Moisture>>, String> {
+        self.moisture.as_deref().map(parse_conditions).transpose()
+    }
+
+    fn page(&self) -> PlantPage {
+        PlantPage::new(self.sort, self.offset, self.limit)
+    }
+}
+
+/// Parses comma separated conditions by their API names, ex: "Full Sun,Partial Shade"
+fn parse_conditions<T: DeserializeOwned + PartialEq>(text: &str) -> Result<Vec<T>, String> {
+    let mut conditions = vec![];
+    for value in text.split(',') {
+        let condition = T::deserialize(value.trim().into_deserializer())
+            .map_err(|e: serde::de::value::Error| e.to_string())?;
+        if !conditions.contains(&condition) {
+            conditions.push(condition);
+        }
+    }
+
+    Ok(conditions)
+}
+
+/// One page of the plants found by zip/shade/moisture, or the plants found
+/// by name
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PlantSearchResults<T> {
+    plants: Vec<T>,
+
+    /// The zip which was searched, and how far it is from the one requested.
+    /// None when searching by name.
+    #[serde(flatten)]
+    zip: Option<ResolvedZip>,
+
+    /// The name of the region the zip is in, if it has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region_name: Option<String>,
+
+    /// True when the requested zip wasn't found, so a nearby one was
+    /// searched.  Only set when searching by zip, a location is always
+    /// searched in the closest zip.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    adjusted: Option<bool>,
+
+    /// How many plants were found, on every page
+    total: usize,
+    offset: usize,
+
+    /// The offset of the next page, if there is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_offset: Option<usize>,
+}
+
+/// A plant found by zip/shade/moisture, with which of the searched conditions
+/// it grows in.  Plants with unknown conditions match all of them.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PlantSearchResult {
+    #[serde(flatten)]
+    plant: Plant,
+    matching_shades: Vec<Shade>,
+    matching_moistures: Vec<Moisture>,
+}
+
+impl PlantSearchResult {
+    fn new(plant: Plant, shades: &[Shade], moistures: &[Moisture]) -> Self {
+        let matching_shades = shades
+            .iter()
+            .filter(|s| plant.shades.is_empty() || plant.shades.contains(s))
+            .copied()
+            .collect();
+        let matching_moistures = moistures
+            .iter()
+            .filter(|m| plant.moistures.is_empty() || plant.moistures.contains(m))
+            .copied()
+            .collect();
+
+        Self {
+            plant,
+            matching_shades,
+            matching_moistures,
+        }
+    }
+}
+
+/// One plant, with the regions it is native to and why
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PlantResponse {
+    #[serde(flatten)]
+    plant: Plant,
+    regions: Vec<PlantRegion>,
 }
 
 pub struct PlantController {
@@ -34,49 +184,102 @@ impl PlantController {
         Self { db, highlights }
     }
 
-    async fn find_plants(&'static self, payload: PlantSearchRequest) -> impl Responder {
+    async fn find_plants(&'static self, payload: PlantSearchRequest) -> HttpResponse {
         // find_plants can be used in two ways:
-        // 1. searching by zip/shade/moisture
+        // 1. searching by zip/shade/moisture, one page at a time
         // 2. search by partial name match
         info!("{payload:?}");
 
-        let plants = match payload {
-            PlantSearchRequest {
-                name: None,
-                zip: Some(zip),
-                moisture: Some(moisture),
-                shade: Some(shade),
-            } => {
-                // adjust zip codes which aren't in the database to the closest
-                // one that is, because not every zip is in the db
-                let zip = self.get_closest_valid_zip(&zip).await.unwrap_or(zip);
-
-                self.db.lookup_query_results(&zip, &moisture, &shade).await
-            }
-            PlantSearchRequest {
-                name: Some(name),
-                zip: None,
-                moisture: None,
-                shade: None,
-            } => self.db.find_plants_by_word_prefix(&name).await,
-            _ => {
-                return HttpResponse::BadRequest()
-                    .body("either name OR zip/shade/moisture are required")
-            }
+        let filters = match payload.filters() {
+            Ok(filters) => filters,
+            Err(e) => return HttpResponse::BadRequest().body(e),
+        };
+        let (shades, moistures) = match (payload.shades(), payload.moistures()) {
+            (Ok(shades), Ok(moistures)) => (shades, moistures),
+            (Err(e), _) | (_, Err(e)) => return HttpResponse::BadRequest().body(e),
         };
+        let page = payload.page();
 
-        // The plants which come back from the database don't have highlights,
-        // as those are derived from ratings.  Populate those, and mark it as
-        // done loading (at least until that field goes away).
-        let plants: Vec<Plant> = plants
-            .into_iter()
-            .map(|p| Plant {
-                highlights: self.highlights.generate(&p),
-                ..p
-            })
-            .collect();
+        let location = match Coordinates::from_optional(payload.lat, payload.lng) {
+            Ok(location) => location,
+            Err(e) => return HttpResponse::BadRequest().body(e.to_string()),
+        };
+        let requested_zip = payload.zip.clone();
+        let zip = match self.search_zip(payload.zip, location).await {
+            Ok(zip) => zip,
+            Err(e) => return e.error_response(),
+        };
 
-        actix_web::HttpResponse::Ok().json(plants)
+        match (payload.name, zip, shades, moistures) {
+            (None, Some(zip), Some(shades), Some(moistures)) => {
+                let plants = self
+                    .db
+                    .lookup_query_results(&zip.zip, &moistures, &shades, &filters, &page)
+                    .await;
+                let total = self
+                    .db
+                    .count_query_results(&zip.zip, &moistures, &shades, &filters)
+                    .await;
+
+                // Every plant found by conditions grows in some of them, so
+                // highlight how they differ.
+                let plants: Vec<PlantSearchResult> = plants
+                    .into_iter()
+                    .map(|p| Plant {
+                        highlights: self.highlights.generate_for_conditions(&p, &shades, &moistures),
+                        ..p
+                    })
+                    .map(|p| PlantSearchResult::new(p, &shades, &moistures))
+                    .collect();
+
+                let region_name = self.db.get_region_name_by_zip(&zip.zip).await;
+                let adjusted = requested_zip.map(|requested| requested != zip.zip);
+
+                let end = page.offset + plants.len();
+                HttpResponse::Ok().json(PlantSearchResults {
+                    plants,
+                    zip: Some(zip),
+                    region_name,
+                    adjusted,
+                    total,
+                    offset: page.offset,
+                    next_offset: (end < total).then_some(end),
+                })
+            }
+            (Some(name), None, None, None)
+                if filters == PlantFilters::default() && page == PlantPage::default() =>
+            {
+                // The plants which come back from the database don't have
+                // highlights, as those are derived from ratings.
+                let plants: Vec<PlantNameMatch> = self
+                    .db
+                    .search_plants_by_name(&name)
+                    .await
+                    .into_iter()
+                    .map(|found| PlantNameMatch {
+                        plant: Plant {
+                            highlights: self.highlights.generate(&found.plant),
+                            ..found.plant
+                        },
+                        ..found
+                    })
+                    .collect();
+
+                // Every match is returned, as one page
+                HttpResponse::Ok().json(PlantSearchResults {
+                    total: plants.len(),
+                    plants,
+                    zip: None,
+                    region_name: None,
+                    adjusted: None,
+                    offset: 0,
+                    next_offset: None,
+                })
+            }
+            _ => HttpResponse::BadRequest().body(
+                "either name OR zip (or lat/lng)/shade/moisture are required, filters and pages need zip/shade/moisture",
+            ),
+        }
     }
 
     async fn find_plant(&'static self, id: usize) -> impl Responder {
@@ -84,23 +287,83 @@ impl PlantController {
 
         if let Some(mut plant) = self.db.get_plant_by_id(id).await {
             plant.highlights = self.highlights.generate(&plant);
-            actix_web::HttpResponse::Ok().json(plant)
+
+            // Regions are extra detail, so the plant is still useful without them
+            let regions = match self.db.get_plant_regions(vec![id]).await {
+                Ok(mut regions) => regions.remove(&id).unwrap_or_default(),
+                Err(e) => {
+                    warn!("Cannot find regions for plant {id}: {e}");
+                    vec![]
+                }
+            };
+
+            actix_web::HttpResponse::Ok().json(PlantResponse { plant, regions })
         } else {
             actix_web::HttpResponse::NotFound().body("plant not found")
         }
     }
 
-    async fn get_closest_valid_zip(&self, zip: &str) -> Result<String, actix_web::Error> {
-        let valid_zip = self.db.lookup_closest_valid_zip(zip).await.map_err(|e| {
-            warn!("Cannot find valid zipcode: {e}");
-            actix_web::error::ErrorBadRequest("invalid zipcode")
-        })?;
+    /// Finds the zip to search in, which is either the given zip adjusted to
+    /// one in the database, or the one closest to the location.  Returns
+    /// Ok(None) if neither is given.
+    async fn search_zip(
+        &self,
+        zip: Option<String>,
+        location: Option<Coordinates>,
+    ) -> Result<Option<ResolvedZip>, actix_web::Error> {
+        match (zip, location) {
+            // adjust zip codes which aren't in the database to the closest
+            // one that is, because not every zip is in the db
+            (Some(zip), None) => self.resolve_zip(&zip).await.map(Some),
+            (None, Some(location)) => {
+                match self.db.lookup_closest_zip_by_location(&location).await {
+                    Ok(Some(resolved)) => Ok(Some(resolved)),
+                    Ok(None) => {
+                        warn!("Cannot find zipcode near {location:?}");
+                        Err(actix_web::error::ErrorBadRequest(
+                            "no zipcode near location",
+                        ))
+                    }
+                    Err(e) => {
+                        warn!("Cannot look up zipcode near {location:?}: {e}");
+                        Err(actix_web::error::ErrorInternalServerError(
+                            "could not look up location",
+                        ))
+                    }
+                }
+            }
+            (None, None) => Ok(None),
+            (Some(_), Some(_)) => Err(actix_web::error::ErrorBadRequest(
+                "either zip OR lat/lng are allowed",
+            )),
+        }
+    }
+
+    /// Adjusts the zip to the closest one in the database.  Errs with a 400
+    /// if nothing is near it, or a 500 if the database fails.
+    async fn resolve_zip(&self, zip: &str) -> Result<ResolvedZip, actix_web::Error> {
+        let resolved = match self.db.resolve_zip(zip).await {
+            Ok(Some(resolved)) => resolved,
+            Ok(None) => {
+                warn!("Cannot find zipcode near {zip}");
+                return Err(actix_web::error::ErrorBadRequest("unknown zip"));
+            }
+            Err(e) => {
+                warn!("Cannot resolve zipcode {zip}: {e}");
+                return Err(actix_web::error::ErrorInternalServerError(
+                    "could not look up zip",
+                ));
+            }
+        };
 
-        if valid_zip != zip {
-            info!("Adjusted unknown zip {} to {valid_zip}", zip);
+        if resolved.zip != zip {
+            info!(
+                "Adjusted unknown zip {zip} to {}, {:.1} miles away",
+                resolved.zip, resolved.distance_miles
+            );
         }
 
-        Ok(valid_zip)
+        Ok(resolved)
     }
 
     //TODO: Remove once browser caches are likely clear, this is legacy support
@@ -110,16 +373,26 @@ impl PlantController {
     ) -> Result<impl Responder, actix_web::Error> {
         info!("{payload:?}");
 
-        let valid_zip = self.get_closest_valid_zip(&payload.zip).await?;
+        let valid_zip = self.resolve_zip(&payload.zip).await?.zip;
         let plants = self
             .db
-            .lookup_query_results(&valid_zip, &payload.moisture, &payload.shade)
+            .lookup_query_results(
+                &valid_zip,
+                &[payload.moisture],
+                &[payload.shade],
+                &PlantFilters::default(),
+                &PlantPage::default(),
+            )
             .await;
 
         let plants: Vec<Plant> = plants
             .into_iter()
             .map(|p| Plant {
-                highlights: self.highlights.generate(&p),
+                highlights: self.highlights.generate_for_conditions(
+                    &p,
+                    &[payload.shade],
+                    &[payload.moisture],
+                ),
                 ..p
             })
             .collect();
@@ -223,3 +496,265 @@ async fn plants_stream_by_scientific_name_handler(
         .stream_by_scientific_name(id.to_string())
         .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::MockDatabase;
+    use crate::search::{NameField, NameMatch};
+    use actix_web::{body::to_bytes, http::StatusCode};
+    use anyhow::anyhow;
+
+    fn controller(db: MockDatabase) -> &'static PlantController {
+        Box::leak(Box::new(PlantController::new(
+            Box::leak(Box::new(db)),
+            Box::leak(Box::new(Highlights::default())),
+        )))
+    }
+
+    fn search(zip: Option<&str>, location: Option<(f64, f64)>) -> PlantSearchRequest {
+        PlantSearchRequest {
+            name: None,
+            zip: zip.map(str::to_string),
+            lat: location.map(|(lat, _)| lat),
+            lng: location.map(|(_, lng)| lng),
+            shade: Some("Full Sun".to_string()),
+            moisture: Some("Medium".to_string()),
+            bloom: None,
+            min_height: None,
+            max_height: None,
+            min_pollinator_rating: None,
+            min_bird_rating: None,
+            min_deer_resistance_rating: None,
+            sort: None,
+            offset: None,
+            limit: None,
+        }
+    }
+
+    async fn find_plants(
+        controller: &'static PlantController,
+        payload: PlantSearchRequest,
+    ) -> (StatusCode, String) {
+        let response = controller.find_plants(payload).await;
+        let status = response.status();
+        let body = to_bytes(response.into_body()).await.unwrap();
+
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    fn resolved(zip: &str, distance_miles: f64) -> ResolvedZip {
+        ResolvedZip {
+            zip: zip.to_string(),
+            distance_miles,
+        }
+    }
+
+    /// Expects a search in the zip, which finds one plant in Columbus
+    fn expect_search_in(db: &mut MockDatabase, zip: &'static str) {
+        db.expect_lookup_query_results()
+            .withf(move |searched, _, _, _, _| searched == zip)
+            .returning(|_, _, _, _, _| vec![Plant::new("Asclepias tuberosa", "Butterfly Weed")]);
+        db.expect_count_query_results().returning(|_, _, _, _| 1);
+        db.expect_get_region_name_by_zip()
+            .withf(move |searched| searched == zip)
+            .returning(|_| Some("Columbus, OH".to_string()));
+    }
+
+    fn json(body: &str) -> serde_json::Value {
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_find_plants_exact_zip() {
+        let mut db = MockDatabase::default();
+        db.expect_resolve_zip()
+            .returning(|zip| Ok(Some(resolved(zip, 0.0))));
+        expect_search_in(&mut db, "43081");
+
+        let (status, body) = find_plants(controller(db), search(Some("43081"), None)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let body = json(&body);
+        assert_eq!(body["zip"], "43081");
+        assert_eq!(body["distanceMiles"], 0.0);
+        assert_eq!(body["regionName"], "Columbus, OH");
+        assert_eq!(body["adjusted"], false);
+        assert_eq!(body["total"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_find_plants_adjusted_zip() {
+        let mut db = MockDatabase::default();
+        db.expect_resolve_zip()
+            .returning(|_| Ok(Some(resolved("43081", 1.4))));
+        expect_search_in(&mut db, "43081");
+
+        let (status, body) = find_plants(controller(db), search(Some("43083"), None)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let body = json(&body);
+        assert_eq!(body["zip"], "43081");
+        assert_eq!(body["distanceMiles"], 1.4);
+        assert_eq!(body["regionName"], "Columbus, OH");
+        assert_eq!(body["adjusted"], true);
+    }
+
+    #[tokio::test]
+    async fn test_find_plants_by_location() {
+        let mut db = MockDatabase::default();
+        db.expect_resolve_zip().never();
+        db.expect_lookup_closest_zip_by_location()
+            .withf(|location| location.latitude == 40.1)
+            .returning(|_| Ok(Some(resolved("43081", 0.8))));
+        expect_search_in(&mut db, "43081");
+
+        let (status, body) = find_plants(controller(db), search(None, Some((40.1, -82.9)))).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let body = json(&body);
+        assert_eq!(body["zip"], "43081");
+        assert_eq!(body["distanceMiles"], 0.8);
+        assert_eq!(body["regionName"], "Columbus, OH");
+
+        // There was no zip to adjust
+        assert!(body.get("adjusted").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_find_plants_unknown_zip() {
+        let mut db = MockDatabase::default();
+        db.expect_resolve_zip().returning(|_| Ok(None));
+        db.expect_lookup_query_results().never();
+
+        let (status, body) = find_plants(controller(db), search(Some("00001"), None)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body, "unknown zip");
+    }
+
+    #[tokio::test]
+    async fn test_find_plants_zip_lookup_fails() {
+        let mut db = MockDatabase::default();
+        db.expect_resolve_zip()
+            .returning(|_| Err(anyhow!("connection refused")));
+        db.expect_lookup_query_results().never();
+
+        let (status, _) = find_plants(controller(db), search(Some("43081"), None)).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_find_plants_no_zip_near_location() {
+        let mut db = MockDatabase::default();
+        db.expect_lookup_closest_zip_by_location()
+            .returning(|_| Ok(None));
+        db.expect_lookup_query_results().never();
+
+        let (status, body) = find_plants(controller(db), search(None, Some((40.1, -82.9)))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body, "no zipcode near location");
+    }
+
+    #[tokio::test]
+    async fn test_find_plants_location_lookup_fails() {
+        let mut db = MockDatabase::default();
+        db.expect_lookup_closest_zip_by_location()
+            .returning(|_| Err(anyhow!("connection refused")));
+        db.expect_lookup_query_results().never();
+
+        let (status, _) = find_plants(controller(db), search(None, Some((40.1, -82.9)))).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_find_plants_by_name() {
+        let mut db = MockDatabase::default();
+        db.expect_search_plants_by_name()
+            .withf(|name| name == "butterfly")
+            .returning(|_| {
+                vec![PlantNameMatch {
+                    plant: Plant::new("Asclepias tuberosa", "Butterfly Weed"),
+                    matched: NameMatch {
+                        field: NameField::Common,
+                        name: "Butterfly Weed".to_string(),
+                        ranges: vec![[0, 9]],
+                        typos: 0,
+                    },
+                }]
+            });
+        db.expect_lookup_query_results().never();
+
+        let payload = PlantSearchRequest {
+            name: Some("butterfly".to_string()),
+            shade: None,
+            moisture: None,
+            ..search(None, None)
+        };
+        let (status, body) = find_plants(controller(db), payload).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Same envelope as a zip search, without the zip
+        let body = json(&body);
+        assert_eq!(body["plants"][0]["common"], "Butterfly Weed");
+        assert_eq!(body["plants"][0]["matched"]["field"], "common");
+        assert_eq!(body["total"], 1);
+        assert_eq!(body["offset"], 0);
+        assert!(body.get("zip").is_none());
+        assert!(body.get("nextOffset").is_none());
+    }
+
+    #[test]
+    fn test_parse_conditions() {
+        assert_eq!(
+            parse_conditions::<Shade>("Full Sun, Partial Shade,Full Sun"),
+            Ok(vec![Shade::None, Shade::Some])
+        );
+        assert_eq!(
+            parse_conditions::<Moisture>("High"),
+            Ok(vec![Moisture::Lots])
+        );
+
+        // A moisture mixed in with the shades, or anything else, is rejected
+        let error = parse_conditions::<Shade>("Full Sun,Low").unwrap_err();
+        assert!(error.contains("Low"), "{error}");
+
+        let error = parse_conditions::<Moisture>("Medium,Soggy").unwrap_err();
+        assert!(error.contains("Soggy"), "{error}");
+
+        assert!(parse_conditions::<Moisture>("Medium,").is_err());
+        assert!(parse_conditions::<Shade>("None").is_err());
+    }
+
+    #[test]
+    fn test_search_result_matching_conditions() {
+        let plant = Plant {
+            shades: vec![Shade::Some, Shade::Lots],
+            moistures: vec![Moisture::Some],
+            ..Plant::new("Scientific", "Common")
+        };
+
+        let result = PlantSearchResult::new(
+            plant,
+            &[Shade::None, Shade::Some],
+            &[Moisture::Some, Moisture::Lots],
+        );
+        assert_eq!(result.matching_shades, vec![Shade::Some]);
+        assert_eq!(result.matching_moistures, vec![Moisture::Some]);
+
+        // Unknown conditions match everything searched for
+        let result = PlantSearchResult::new(
+            Plant::new("Scientific", "Common"),
+            &[Shade::None, Shade::Lots],
+            &[Moisture::None],
+        );
+        assert_eq!(result.matching_shades, vec![Shade::None, Shade::Lots]);
+        assert_eq!(result.matching_moistures, vec![Moisture::None]);
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(
+            json["matchingShades"],
+            serde_json::json!(["Full Sun", "Full Shade"])
+        );
+        assert_eq!(json["matchingMoistures"], serde_json::json!(["Low"]));
+    }
+}
@@ -0,0 +1,160 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use mockall_double::double;
+use serde::{Deserialize, Serialize};
+use tracing::log::{info, warn};
+
+#[double]
+use crate::database::Database;
+use crate::{
+    app::PlantingLifeApp,
+    domain::{Plant, PlantPage, PlantSort, Region, RegionPlantCounts},
+    highlights::Highlights,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+struct RegionPlantsRequest {
+    sort: Option<PlantSort>,
+    offset: Option<usize>,
+    /// Without a limit, every plant after offset is returned
+    limit: Option<usize>,
+}
+
+/// A region with what's in it
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RegionResponse {
+    #[serde(flatten)]
+    region: Region,
+    plant_counts: RegionPlantCounts,
+    zipcodes: Vec<String>,
+}
+
+/// One page of the plants native to a region
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RegionPlantsResponse {
+    plants: Vec<Plant>,
+
+    /// How many plants are in the region, on every page
+    total: usize,
+    offset: usize,
+
+    /// The offset of the next page, if there is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_offset: Option<usize>,
+}
+
+pub struct RegionsController {
+    pub db: &'static Database,
+    pub highlights: &'static Highlights,
+}
+
+impl RegionsController {
+    pub fn new(db: &'static Database, highlights: &'static Highlights) -> Self {
+        Self { db, highlights }
+    }
+
+    async fn list(&self) -> HttpResponse {
+        match self.db.get_regions().await {
+            Ok(regions) => HttpResponse::Ok().json(regions),
+            Err(e) => {
+                warn!("Error listing regions: {e}");
+                HttpResponse::InternalServerError().body("Could not list regions")
+            }
+        }
+    }
+
+    async fn read(&self, id: usize) -> HttpResponse {
+        info!("read region {id}");
+
+        let region = match self.db.get_region(id).await {
+            Ok(Some(region)) => region,
+            Ok(None) => return HttpResponse::NotFound().body("region not found"),
+            Err(e) => {
+                warn!("Error reading region {id}: {e}");
+                return HttpResponse::InternalServerError().body("Could not read region");
+            }
+        };
+
+        let plant_counts = self.db.count_region_plants(id).await;
+        let zipcodes = self.db.get_region_zipcodes(id).await;
+
+        match plant_counts.and_then(|counts| Ok((counts, zipcodes?))) {
+            Ok((plant_counts, zipcodes)) => HttpResponse::Ok().json(RegionResponse {
+                region,
+                plant_counts,
+                zipcodes,
+            }),
+            Err(e) => {
+                warn!("Error reading region {id}: {e}");
+                HttpResponse::InternalServerError().body("Could not read region")
+            }
+        }
+    }
+
+    async fn plants(&self, id: usize, payload: RegionPlantsRequest) -> HttpResponse {
+        info!("region {id} plants {payload:?}");
+
+        match self.db.get_region(id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => return HttpResponse::NotFound().body("region not found"),
+            Err(e) => {
+                warn!("Error reading region {id}: {e}");
+                return HttpResponse::InternalServerError().body("Could not find plants");
+            }
+        }
+
+        let page = PlantPage::new(payload.sort, payload.offset, payload.limit);
+        let plant_counts = self.db.count_region_plants(id).await;
+        let plants = self.db.get_region_plants(id, &page).await;
+
+        let (plant_counts, plants) = match plant_counts.and_then(|counts| Ok((counts, plants?))) {
+            Ok(found) => found,
+            Err(e) => {
+                warn!("Error finding plants in region {id}: {e}");
+                return HttpResponse::InternalServerError().body("Could not find plants");
+            }
+        };
+
+        // The plants which come back from the database don't have
+        // highlights, as those are derived from ratings.
+        let plants: Vec<Plant> = plants
+            .into_iter()
+            .map(|p| Plant {
+                highlights: self.highlights.generate(&p),
+                ..p
+            })
+            .collect();
+
+        let total = plant_counts.total;
+        let end = page.offset + plants.len();
+        HttpResponse::Ok().json(RegionPlantsResponse {
+            plants,
+            total,
+            offset: page.offset,
+            next_offset: (end < total).then_some(end),
+        })
+    }
+}
+
+#[get("/regions")]
+async fn list_regions_handler(app: web::Data<&'static PlantingLifeApp>) -> impl Responder {
+    app.regions_controller.list().await
+}
+
+#[get("/regions/{id}")]
+async fn read_region_handler(
+    id: web::Path<usize>,
+    app: web::Data<&'static PlantingLifeApp>,
+) -> impl Responder {
+    app.regions_controller.read(*id).await
+}
+
+#[get("/regions/{id}/plants")]
+async fn region_plants_handler(
+    id: web::Path<usize>,
+    web::Query(payload): web::Query<RegionPlantsRequest>,
+    app: web::Data<&'static PlantingLifeApp>,
+) -> impl Responder {
+    app.regions_controller.plants(*id, payload).await
+}
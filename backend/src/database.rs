@@ -1,28 +1,50 @@
-use crate::domain::*;
+use crate::{
+    domain::*,
+    plant_details,
+    search::{PlantNameMatch, PlantSearchIndex},
+    usda::UsdaSymbols,
+};
 use anyhow::anyhow;
 use mockall::automock;
-use mockall_double::double;
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
-use std::collections::HashSet;
-use tracing::log::warn;
+use std::collections::{HashMap, HashSet};
+use tracing::log::{info, warn};
 
-#[double]
-use self::sql::SqlRunner;
+use self::{migrations::Changeset, sql::SqlRunner, sqlite::SqliteRunner, storage::Storage};
 
 mod conversions;
+pub mod migrations;
 pub mod sql;
+pub mod sqlite;
+pub mod storage;
+
+/// How many plants a name search returns
+const NAME_SEARCH_LIMIT: usize = 10;
 
 pub struct Database {
-    sql_runner: SqlRunner,
+    sql_runner: Box<dyn Storage>,
+
+    /// Every plant's names, for searching by name.  Kept current by
+    /// save_plant and save_plant_synonyms once it is loaded.
+    search_index: PlantSearchIndex,
 }
 
 #[automock]
 impl Database {
-    /// Creates a Database.  If the url is None, it creates one without a pool.
-    /// When the pool is None, it degrades gracefully.
+    /// Creates a Database, choosing the storage backend by the url's scheme.
+    /// Urls starting with "sqlite:" use SQLite, anything else uses MySQL.
+    /// If the url is invalid, it creates one without a connection.
+    /// Without a connection, it degrades gracefully.
     pub fn new(db_url: &str) -> Self {
+        let sql_runner: Box<dyn Storage> = if SqliteRunner::is_sqlite_url(db_url) {
+            Box::new(SqliteRunner::new(db_url))
+        } else {
+            Box::new(SqlRunner::new(db_url))
+        };
+
         Self {
-            sql_runner: SqlRunner::new(db_url),
+            sql_runner,
+            search_index: PlantSearchIndex::default(),
         }
     }
 
@@ -39,26 +61,93 @@ impl Database {
 
     /// Finds the closest valid zipcode, returns Err if it can't.
     pub async fn lookup_closest_valid_zip(&self, zip: &str) -> anyhow::Result<String> {
-        if zip.len() != 5 || !zip.chars().all(char::is_numeric) {
+        if !is_zipcode(zip) {
             return Err(anyhow!("invalid zipcode format: {zip}"));
         }
 
+        match self.resolve_zip(zip).await? {
+            Some(resolved) => Ok(resolved.zip),
+            None => Err(anyhow!("no zipcodes near {zip}")),
+        }
+    }
+
+    /// Finds the closest valid zipcode and how far away it is.  Zipcodes
+    /// which aren't in the database are placed at the center of the ones
+    /// sharing their first three digits.  Returns Ok(None) if the zip isn't
+    /// five digits or no zipcodes share its first three, Err if the database
+    /// fails.
+    pub async fn resolve_zip(&self, zip: &str) -> anyhow::Result<Option<ResolvedZip>> {
+        if !is_zipcode(zip) {
+            return Ok(None);
+        }
+
         if self.sql_runner.check_zip_exists(zip).await? {
-            Ok(zip.to_string())
-        } else {
-            Ok(self.sql_runner.select_closest_zip(zip).await?)
+            return Ok(Some(ResolvedZip {
+                zip: zip.to_string(),
+                distance_miles: 0.0,
+            }));
+        }
+
+        match self
+            .sql_runner
+            .select_zip_prefix_centroid(&zip[..3])
+            .await?
+        {
+            Some(centroid) => self.lookup_closest_zip_by_location(&centroid).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Finds the zipcode whose centroid is closest to the location, by
+    /// distance over the earth's surface.  Returns Ok(None) if there are no
+    /// zipcodes at all.
+    pub async fn lookup_closest_zip_by_location(
+        &self,
+        location: &Coordinates,
+    ) -> anyhow::Result<Option<ResolvedZip>> {
+        const MILES_PER_DEGREE: f64 = 69.0;
+
+        // Searches a small box around the location, growing it until the
+        // closest zipcode inside is closer than anything outside could be.
+        let mut degrees = 0.25;
+        loop {
+            let zipcodes = self
+                .sql_runner
+                .select_zipcodes_near(*location, degrees)
+                .await?;
+
+            let closest = zipcodes
+                .into_iter()
+                .map(|(zip, centroid)| (location.distance_miles(&centroid), zip))
+                .min_by(|(lhs, _), (rhs, _)| lhs.total_cmp(rhs));
+
+            // Degrees of longitude shrink towards the poles
+            let box_miles = degrees * MILES_PER_DEGREE * location.latitude.to_radians().cos();
+            match closest {
+                Some((miles, zip)) if miles <= box_miles || degrees >= 180.0 => {
+                    return Ok(Some(ResolvedZip {
+                        zip,
+                        distance_miles: miles,
+                    }));
+                }
+                None if degrees >= 180.0 => return Ok(None),
+                _ => degrees *= 4.0,
+            }
         }
     }
 
-    /// Finds all Plants which match the given parameters.
+    /// Finds one page of Plants in the zip's region which grow in any of the
+    /// moistures and any of the shades, narrowed by filters.
     pub async fn lookup_query_results(
         &self,
         zip: &str,
-        moisture: &Moisture,
-        shade: &Shade,
+        moistures: &[Moisture],
+        shades: &[Shade],
+        filters: &PlantFilters,
+        page: &PlantPage,
     ) -> Vec<Plant> {
         self.sql_runner
-            .select_plants_by_zip_moisture_shade(zip, moisture, shade)
+            .select_plants_by_zip_moisture_shade(zip, moistures, shades, filters, page)
             .await
             .unwrap_or_else(|e| {
                 warn!("lookup_query_results query failed: {}", e);
@@ -66,6 +155,24 @@ impl Database {
             })
     }
 
+    /// Counts the Plants which match the given parameters and filters, on
+    /// every page.  Failures are logged and counted as zero.
+    pub async fn count_query_results(
+        &self,
+        zip: &str,
+        moistures: &[Moisture],
+        shades: &[Shade],
+        filters: &PlantFilters,
+    ) -> usize {
+        self.sql_runner
+            .count_plants_by_zip_moisture_shade(zip, moistures, shades, filters)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("count_query_results query failed: {}", e);
+                0
+            })
+    }
+
     ///Saves a new Query and maps it to the plants referenced by plant_ids.
     ///
     ///Failures are logged, but are otherwise ignored.
@@ -90,12 +197,20 @@ impl Database {
             .filter_map(|p| p.id)
             .collect();
 
-        if let Err(e) = self.sql_runner.insert_region_plants(zip, plant_ids).await {
+        let provenance = NativeProvenance::new(NativeSource::Generated);
+        if let Err(e) = self
+            .sql_runner
+            .insert_region_plants(zip, plant_ids, &provenance)
+            .await
+        {
             warn!("save_query_results failed to insert region plants: {}", e);
         }
     }
 
-    pub async fn save_plant_region(&self, plant: &Plant, zip: &str) {
+    /// Marks the plant as native to the zip's region, and why.
+    ///
+    /// Failures are logged, but are otherwise ignored.
+    pub async fn save_plant_region(&self, plant: &Plant, zip: &str, provenance: &NativeProvenance) {
         if plant.id.is_none() {
             warn!("save_plant_region requires plant.id");
             return;
@@ -105,8 +220,12 @@ impl Database {
         let mut plant_ids = HashSet::new();
         plant_ids.insert(plant.id.unwrap());
 
-        if let Err(e) = self.sql_runner.insert_region_plants(zip, plant_ids).await {
-            warn!("save_query_results failed to insert region plants: {}", e);
+        if let Err(e) = self
+            .sql_runner
+            .insert_region_plants(zip, plant_ids, provenance)
+            .await
+        {
+            warn!("save_plant_region failed to insert region plants: {}", e);
         }
     }
 
@@ -130,6 +249,8 @@ impl Database {
     /// Inserts or updates a single Plant, returning a new Plant with its
     /// id populated. Returns Err if it fails to save.
     pub async fn save_plant(&self, plant: &Plant) -> anyhow::Result<Plant> {
+        let plant = &UsdaSymbols::builtin().fill_in(&plant_details::parse_details(plant));
+
         let mut img_id = None;
         if let Some(image) = &plant.image {
             img_id = image.id;
@@ -147,10 +268,49 @@ impl Database {
             self.sql_runner.insert_plant(plant, img_id).await?
         };
 
-        Ok(Plant {
+        let saved = Plant {
             id: Some(id),
             ..plant.clone()
-        })
+        };
+        self.search_index.upsert(&saved);
+        self.save_usda_synonyms(id, plant).await;
+
+        Ok(saved)
+    }
+
+    /// Saves other names for a plant, keeping any it already has.
+    /// Returns Err if it fails to save.
+    pub async fn save_plant_synonyms(
+        &self,
+        plant_id: usize,
+        synonyms: &[PlantSynonym],
+    ) -> anyhow::Result<()> {
+        if synonyms.is_empty() {
+            return Ok(());
+        }
+
+        self.sql_runner
+            .insert_plant_synonyms(plant_id, synonyms)
+            .await?;
+        self.search_index.add_synonyms(plant_id, synonyms);
+
+        Ok(())
+    }
+
+    /// Saves the USDA's synonyms for the plant's symbol, so lookups by an
+    /// older name find it.
+    ///
+    /// Failures are logged, but are otherwise ignored.
+    async fn save_usda_synonyms(&self, plant_id: usize, plant: &Plant) {
+        let synonyms: Vec<PlantSynonym> = UsdaSymbols::builtin()
+            .synonyms(plant)
+            .iter()
+            .map(|name| PlantSynonym::new(name, SynonymKind::Scientific))
+            .collect();
+
+        if let Err(e) = self.save_plant_synonyms(plant_id, &synonyms).await {
+            warn!("save_usda_synonyms failed for {}: {e}", plant.scientific);
+        }
     }
 
     /// Saves an Image, returning a new Image with the database id populated.
@@ -164,8 +324,15 @@ impl Database {
         })
     }
 
-    /// Fetches one Plant by scientific name.  Returns None if it is not
-    /// found or if there is a database error.
+    /// Records a change to a plant in the audit log.
+    /// Returns Err if it fails to save.
+    pub async fn save_plant_change(&self, change: &PlantChange) -> anyhow::Result<()> {
+        self.sql_runner.insert_plant_change(change).await
+    }
+
+    /// Fetches one Plant by scientific name, or by one of its scientific
+    /// synonyms.  Returns None if it is not found or if there is a database
+    /// error.
     pub async fn get_plant_by_scientific_name(&self, scientific_name: &str) -> Option<Plant> {
         match self
             .sql_runner
@@ -194,6 +361,108 @@ impl Database {
         }
     }
 
+    /// Fetches a page of up to limit Plants with ids greater than after_id,
+    /// ordered by id.  Returns Err if there is a database error, so callers
+    /// can tell a failure from the end of the catalog.
+    pub async fn get_plants_after_id(
+        &self,
+        after_id: usize,
+        limit: usize,
+    ) -> anyhow::Result<Vec<Plant>> {
+        self.sql_runner
+            .select_plants_after_id(after_id, limit)
+            .await
+            .map_err(|e| anyhow!("get_plants_after_id failed to select: {e}"))
+    }
+
+    /// Parses the bloom, height and spread text of plants which aren't
+    /// structured yet into their structured fields and fills in missing USDA
+    /// symbols, saving the plants which changed.  Those plants also get their
+    /// USDA synonyms, so plants which are already filled in aren't touched
+    /// again.  Returns how many were updated.
+    pub async fn backfill_plant_details(&self) -> anyhow::Result<usize> {
+        const PAGE_SIZE: usize = 500;
+
+        let mut updated = 0;
+        let mut after_id = 0;
+        loop {
+            let plants = self
+                .sql_runner
+                .select_plants_missing_details_after_id(after_id, PAGE_SIZE)
+                .await
+                .map_err(|e| anyhow!("backfill_plant_details failed to select: {e}"))?;
+            let Some(last_id) = plants.last().and_then(|p| p.id) else {
+                return Ok(updated);
+            };
+
+            for plant in plants {
+                let parsed = UsdaSymbols::builtin().fill_in(&plant_details::parse_details(&plant));
+                if let Some(id) = parsed.id {
+                    self.save_usda_synonyms(id, &parsed).await;
+                }
+
+                if parsed.bloom_period == plant.bloom_period
+                    && parsed.bloom_colors == plant.bloom_colors
+                    && parsed.height_range == plant.height_range
+                    && parsed.spread_range == plant.spread_range
+                    && parsed.usda_symbol == plant.usda_symbol
+                    && parsed.usda_source == plant.usda_source
+                {
+                    continue;
+                }
+
+                let img_id = parsed.image.as_ref().and_then(|i| i.id);
+                self.sql_runner
+                    .update_plant(&parsed, img_id)
+                    .await
+                    .map_err(|e| anyhow!("backfill_plant_details failed to update: {e}"))?;
+                updated += 1;
+            }
+
+            after_id = last_id;
+        }
+    }
+
+    /// Fetches the regions each of the given plants is native to, by plant id.
+    /// Plants without regions are left out.
+    pub async fn get_plant_regions(
+        &self,
+        plant_ids: Vec<usize>,
+    ) -> anyhow::Result<HashMap<usize, Vec<PlantRegion>>> {
+        let regions = self
+            .sql_runner
+            .select_plant_regions(plant_ids)
+            .await
+            .map_err(|e| anyhow!("get_plant_regions failed to select: {e}"))?;
+
+        let mut regions_by_plant: HashMap<usize, Vec<PlantRegion>> = HashMap::new();
+        for (plant_id, region) in regions {
+            regions_by_plant.entry(plant_id).or_default().push(region);
+        }
+
+        Ok(regions_by_plant)
+    }
+
+    /// Fetches the other names each of the given plants is known by, by plant
+    /// id.  Plants without synonyms are left out.
+    pub async fn get_plant_synonyms(
+        &self,
+        plant_ids: Vec<usize>,
+    ) -> anyhow::Result<HashMap<usize, Vec<PlantSynonym>>> {
+        let synonyms = self
+            .sql_runner
+            .select_plant_synonyms(plant_ids)
+            .await
+            .map_err(|e| anyhow!("get_plant_synonyms failed to select: {e}"))?;
+
+        let mut synonyms_by_plant: HashMap<usize, Vec<PlantSynonym>> = HashMap::new();
+        for (plant_id, synonym) in synonyms {
+            synonyms_by_plant.entry(plant_id).or_default().push(synonym);
+        }
+
+        Ok(synonyms_by_plant)
+    }
+
     /// Fetches a garden by id.  The id may be the read_id or write_id.
     pub async fn get_garden(&self, id: &str) -> Option<Garden> {
         let mut garden = match self.sql_runner.select_garden_by_id(id, true).await {
@@ -253,11 +522,12 @@ impl Database {
         }
     }
 
-    /// Saves a new garden, returning the read_id and write_id.
+    /// Saves a new garden with its zones and (plant_id, details), returning
+    /// the read_id and write_id.
     pub async fn save_new_garden(
         &self,
         garden: &Garden,
-        plant_ids: Vec<usize>,
+        plants: Vec<(usize, GardenPlantDetails)>,
     ) -> anyhow::Result<(String, String)> {
         let read_id = self.get_unique_garden_id(5).await?;
         let write_id = self.get_unique_garden_id(20).await?;
@@ -267,9 +537,16 @@ impl Database {
             .await
             .map_err(|e| anyhow!("save_new_garden failed: {e}"))?;
 
+        if !garden.zones.is_empty() {
+            self.sql_runner
+                .replace_garden_zones(&write_id, &garden.zones)
+                .await
+                .map_err(|e| anyhow!("save_new_garden failed to replace zones: {e}"))?;
+        }
+
         match self
             .sql_runner
-            .replace_garden_plants(&write_id, plant_ids)
+            .replace_garden_plants(&write_id, plants)
             .await
         {
             Ok(()) => Ok((read_id, write_id)),
@@ -277,20 +554,35 @@ impl Database {
         }
     }
 
-    /// Updates an existing garden, returning an empty result.
+    /// Updates an existing garden, replacing its (plant_id, details) and
+    /// its zones when they're given, along with its shade and moisture
+    /// which are the first zone's.
     pub async fn save_existing_garden(
         &self,
         write_id: &str,
         name: &str,
-        plant_ids: Vec<usize>,
+        zones: Option<Vec<GardenZone>>,
+        plants: Vec<(usize, GardenPlantDetails)>,
     ) -> anyhow::Result<()> {
+        // The garden's own conditions are its first zone's
+        let conditions = zones
+            .as_ref()
+            .and_then(|zones| zones.first())
+            .map(|zone| (zone.shade, zone.moisture));
         self.sql_runner
-            .update_garden(write_id, name)
+            .update_garden(write_id, name, conditions)
             .await
             .map_err(|e| anyhow!("save_existing_garden failed: {e}"))?;
 
+        if let Some(zones) = zones {
+            self.sql_runner
+                .replace_garden_zones(write_id, &zones)
+                .await
+                .map_err(|e| anyhow!("save_existing_garden failed to replace zones: {e}"))?;
+        }
+
         self.sql_runner
-            .replace_garden_plants(write_id, plant_ids)
+            .replace_garden_plants(write_id, plants)
             .await
             .map_err(|e| anyhow!("save_new_garden failed to replace plants: {e}"))
     }
@@ -332,23 +624,101 @@ impl Database {
         }
     }
 
-    pub async fn find_plants_by_word_prefix(&self, word_prefix: &str) -> Vec<Plant> {
-        if word_prefix.len() < 3 {
+    /// Fetches every region, by name.
+    pub async fn get_regions(&self) -> anyhow::Result<Vec<Region>> {
+        self.sql_runner.select_regions().await
+    }
+
+    /// Fetches one region by id, Ok(None) if it doesn't exist.
+    pub async fn get_region(&self, id: usize) -> anyhow::Result<Option<Region>> {
+        self.sql_runner.select_region(id).await
+    }
+
+    /// Fetches the zipcodes a region covers.
+    pub async fn get_region_zipcodes(&self, id: usize) -> anyhow::Result<Vec<String>> {
+        self.sql_runner.select_region_zipcodes(id).await
+    }
+
+    /// Counts a region's plants, in total and by each shade and moisture.
+    pub async fn count_region_plants(&self, id: usize) -> anyhow::Result<RegionPlantCounts> {
+        self.sql_runner.count_region_plants(id).await
+    }
+
+    /// Fetches one page of the plants native to a region.
+    pub async fn get_region_plants(
+        &self,
+        id: usize,
+        page: &PlantPage,
+    ) -> anyhow::Result<Vec<Plant>> {
+        self.sql_runner.select_plants_by_region(id, page).await
+    }
+
+    /// Reads every plant and its synonyms into the name search index,
+    /// replacing what it had.  Returns how many plants were indexed.
+    pub async fn load_search_index(&self) -> anyhow::Result<usize> {
+        const PAGE_SIZE: usize = 500;
+
+        let mut indexed = vec![];
+        let mut after_id = 0;
+        loop {
+            let plants = self.get_plants_after_id(after_id, PAGE_SIZE).await?;
+            let Some(last_id) = plants.last().and_then(|p| p.id) else {
+                break;
+            };
+
+            let plant_ids = plants.iter().filter_map(|p| p.id).collect();
+            let mut synonyms = self.get_plant_synonyms(plant_ids).await?;
+            for plant in plants {
+                let plant_synonyms = plant
+                    .id
+                    .and_then(|id| synonyms.remove(&id))
+                    .unwrap_or_default();
+                indexed.push((plant, plant_synonyms));
+            }
+
+            after_id = last_id;
+        }
+
+        let count = indexed.len();
+        self.search_index.replace(indexed);
+        Ok(count)
+    }
+
+    /// Finds plants by any of their names, tolerating typos.  Until the
+    /// search index is loaded, it falls back to the database's stricter
+    /// word prefix search.
+    pub async fn search_plants_by_name(&self, name: &str) -> Vec<PlantNameMatch> {
+        if self.search_index.is_loaded() {
+            return self.search_index.search(name, NAME_SEARCH_LIMIT);
+        }
+
+        if name.len() < 3 {
             return vec![];
         }
 
-        let search_expression = build_word_prefix_expression(word_prefix);
-        match self
-            .sql_runner
-            .find_plants_by_word_prefix(&search_expression)
-            .await
-        {
+        let plants = match self.sql_runner.find_plants_by_word_prefix(name).await {
             Ok(plants) => plants,
             Err(e) => {
-                warn!("find_plants_by_word_prefix failed to select: {e}");
-                vec![]
+                warn!("search_plants_by_name failed to select: {e}");
+                return vec![];
             }
-        }
+        };
+
+        // Indexing the few plants found works out which names matched
+        let plant_ids = plants.iter().filter_map(|p| p.id).collect();
+        let mut synonyms = self.get_plant_synonyms(plant_ids).await.unwrap_or_default();
+        let plants = plants
+            .into_iter()
+            .map(|plant| {
+                let plant_synonyms = plant
+                    .id
+                    .and_then(|id| synonyms.remove(&id))
+                    .unwrap_or_default();
+                (plant, plant_synonyms)
+            })
+            .collect();
+
+        PlantSearchIndex::from_plants(plants).search(name, NAME_SEARCH_LIMIT)
     }
 
     pub async fn update_request_count(&self, uri: &str) {
@@ -360,17 +730,48 @@ impl Database {
         }
     }
 
+    /// Lists the schema changesets which have not been applied yet, in the
+    /// order they will be applied.
+    pub async fn get_pending_migrations(&self) -> anyhow::Result<Vec<Changeset>> {
+        let applied = self.sql_runner.select_applied_changesets().await?;
+
+        Ok(self
+            .sql_runner
+            .changesets()
+            .into_iter()
+            .filter(|c| !applied.contains(&(c.file.to_string(), c.id.clone())))
+            .collect())
+    }
+
+    /// Applies all pending schema changesets in order, returning the ones
+    /// which were applied.  Stops at the first failure.
+    pub async fn migrate(&self) -> anyhow::Result<Vec<Changeset>> {
+        let pending = self.get_pending_migrations().await?;
+
+        for changeset in &pending {
+            info!("Applying changeset {changeset}");
+            self.sql_runner.apply_changeset(changeset).await?;
+        }
+
+        Ok(pending)
+    }
+
     pub async fn get_monthly_request_count(&self, uri: &str) -> usize {
         match self.sql_runner.select_monthly_request_count(uri).await {
             Ok(count) => count,
             Err(e) => {
                 warn!("select_monthly_request_count failed: {e}");
-                std::usize::MAX
+                usize::MAX
             }
         }
     }
 }
 
+/// Whether the text looks like a zipcode, ex: "43081"
+fn is_zipcode(zip: &str) -> bool {
+    zip.len() == 5 && zip.chars().all(|c| c.is_ascii_digit())
+}
+
 fn generate_random_string(length: u8) -> String {
     let mut rng = thread_rng();
     (0..length)
@@ -378,23 +779,11 @@ fn generate_random_string(length: u8) -> String {
         .collect()
 }
 
-fn build_word_prefix_expression(word_prefix: &str) -> String {
-    let mut expression = String::new();
-
-    for word in word_prefix.split_whitespace() {
-        if !word.is_empty() {
-            expression.push_str(&format!("+{}* ", word));
-        }
-    }
-
-    expression.trim().to_string()
-}
-
 #[cfg(test)]
 mod tests {
     use mockall::Sequence;
 
-    use super::{sql::MockSqlRunner, *};
+    use super::{storage::MockStorage, *};
 
     #[tokio::test]
     async fn test_lookup_closest_valid_zip_too_short() {
@@ -444,19 +833,27 @@ mod tests {
     async fn test_lookup_closest_valid_zip_not_exists() {
         let db = make_db_with_mock(|mock| {
             mock.expect_check_zip_exists().returning(|_| Ok(false));
-            mock.expect_select_closest_zip()
-                .returning(|_| Ok("43081".into()));
+            mock.expect_select_zip_prefix_centroid()
+                .withf(|prefix| prefix == "430")
+                .returning(|_| Ok(Some(Coordinates::new(40.1, -82.9).unwrap())));
+            mock.expect_select_zipcodes_near().returning(|_, _| {
+                Ok(vec![
+                    ("43081".into(), Coordinates::new(40.1126, -82.9201).unwrap()),
+                    ("43085".into(), Coordinates::new(40.0998, -83.0156).unwrap()),
+                ])
+            });
         });
 
-        let result = db.lookup_closest_valid_zip("43083").await;
-        assert_eq!(result.unwrap(), "43081")
+        let result = db.resolve_zip("43083").await.unwrap().unwrap();
+        assert_eq!(result.zip, "43081");
+        assert!((result.distance_miles - 1.4).abs() < 0.1);
     }
 
     #[tokio::test]
     async fn test_lookup_closest_valid_zip_not_exists_err() {
         let db = make_db_with_mock(|mock| {
             mock.expect_check_zip_exists().returning(|_| Ok(false));
-            mock.expect_select_closest_zip()
+            mock.expect_select_zip_prefix_centroid()
                 .returning(|_| Err(anyhow!("oops")));
         });
 
@@ -464,6 +861,80 @@ mod tests {
         assert_eq!(result.unwrap_err().to_string(), "oops")
     }
 
+    #[tokio::test]
+    async fn test_lookup_closest_valid_zip_unknown_prefix() {
+        let db = make_db_with_mock(|mock| {
+            mock.expect_check_zip_exists().returning(|_| Ok(false));
+            mock.expect_select_zip_prefix_centroid()
+                .returning(|_| Ok(None));
+        });
+
+        let result = db.lookup_closest_valid_zip("09001").await;
+        assert_eq!(result.unwrap_err().to_string(), "no zipcodes near 09001");
+
+        assert!(db.resolve_zip("09001").await.unwrap().is_none());
+        assert!(db.resolve_zip("0900z").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_save_existing_garden_keeps_first_zone_conditions() {
+        let db = make_db_with_mock(|mock| {
+            mock.expect_update_garden()
+                .withf(|write_id, name, conditions| {
+                    write_id == "write"
+                        && name == "Renamed"
+                        && *conditions == Some((Shade::Lots, Moisture::Some))
+                })
+                .times(1)
+                .returning(|_, _, _| Ok(()));
+            mock.expect_replace_garden_zones()
+                .times(1)
+                .returning(|_, _| Ok(()));
+            mock.expect_replace_garden_plants().returning(|_, _| Ok(()));
+        });
+
+        let zones = vec![
+            GardenZone::new("Woods", Shade::Lots, Moisture::Some),
+            GardenZone::new("Lawn", Shade::None, Moisture::None),
+        ];
+        db.save_existing_garden("write", "Renamed", Some(zones), vec![])
+            .await
+            .unwrap();
+
+        let db = make_db_with_mock(|mock| {
+            mock.expect_update_garden()
+                .withf(|_, _, conditions| conditions.is_none())
+                .times(1)
+                .returning(|_, _, _| Ok(()));
+            mock.expect_replace_garden_zones().never();
+            mock.expect_replace_garden_plants().returning(|_, _| Ok(()));
+        });
+        db.save_existing_garden("write", "Renamed", None, vec![])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_lookup_closest_zip_by_location_grows_search() {
+        let db = make_db_with_mock(|mock| {
+            mock.expect_select_zipcodes_near().returning(|_, degrees| {
+                // Nothing nearby, then one zip far enough that a closer
+                // one could be outside the box, then both.
+                let far = ("43085".to_string(), Coordinates::new(40.0, -84.0).unwrap());
+                let near = ("43081".to_string(), Coordinates::new(40.0, -83.5).unwrap());
+                Ok(match degrees {
+                    d if d < 1.0 => vec![],
+                    d if d < 4.0 => vec![far],
+                    _ => vec![far, near],
+                })
+            });
+        });
+
+        let location = Coordinates::new(40.0, -82.0).unwrap();
+        let result = db.lookup_closest_zip_by_location(&location).await;
+        assert_eq!(result.unwrap().unwrap().zip, "43081");
+    }
+
     #[tokio::test]
     async fn test_generate_garden_id_first_try() {
         let db = make_db_with_mock(|mock| {
@@ -532,32 +1003,222 @@ mod tests {
         assert!(result.is_err());
     }
 
-    #[test]
-    fn test_build_word_prefix_expression() {
-        assert_eq!(build_word_prefix_expression("foo bar"), "+foo* +bar*");
-        assert_eq!(build_word_prefix_expression("foo      bar"), "+foo* +bar*");
-        assert_eq!(build_word_prefix_expression("  foo   bar "), "+foo* +bar*");
-        assert_eq!(build_word_prefix_expression("  foo    "), "+foo*");
-        assert_eq!(build_word_prefix_expression("  foobar    "), "+foobar*");
+    #[tokio::test]
+    async fn test_backfill_plant_details_updates_changed_plants() {
+        let db = make_db_with_mock(|mock| {
+            mock.expect_select_plants_missing_details_after_id()
+                .returning(|after_id, _| {
+                    if after_id > 0 {
+                        return Ok(vec![]);
+                    }
+
+                    let mut parsed = Plant::new("parsed", "parsed");
+                    parsed.id = Some(1);
+                    parsed.height = Some("2 ft".to_string());
+                    parsed.height_range = Some(SizeRange {
+                        min_inches: 24,
+                        max_inches: 24,
+                    });
+
+                    let mut unparsed = Plant::new("unparsed", "unparsed");
+                    unparsed.id = Some(2);
+                    unparsed.bloom = Some("White, May".to_string());
+
+                    let mut no_symbol = Plant::new("Asclepias tuberosa", "Butterfly Weed");
+                    no_symbol.id = Some(3);
+
+                    Ok(vec![parsed, unparsed, no_symbol])
+                });
+
+            mock.expect_update_plant()
+                .withf(|plant, _| {
+                    plant.id == Some(2)
+                        && plant.bloom_period.map(|b| b.start_month) == Some(5)
+                        && plant.bloom_colors == vec!["white"]
+                })
+                .times(1)
+                .returning(|_, _| Ok(()));
+            mock.expect_update_plant()
+                .withf(|plant, _| {
+                    plant.id == Some(3) && plant.usda_symbol.as_deref() == Some("ASTU")
+                })
+                .times(1)
+                .returning(|_, _| Ok(()));
+        });
+
+        assert_eq!(db.backfill_plant_details().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_applies_pending_in_order() {
+        let db = make_db_with_mock(|mock| {
+            mock.expect_changesets().returning(|| {
+                ["doug:1", "doug:2", "doug:3"]
+                    .iter()
+                    .map(|id| Changeset {
+                        file: "migrations/foo.sql",
+                        id: id.to_string(),
+                        sql: "SELECT 1".to_string(),
+                    })
+                    .collect()
+            });
+            mock.expect_select_applied_changesets().returning(|| {
+                Ok(HashSet::from([(
+                    "migrations/foo.sql".to_string(),
+                    "doug:2".to_string(),
+                )]))
+            });
+
+            let mut seq = Sequence::new();
+            for id in ["doug:1", "doug:3"] {
+                mock.expect_apply_changeset()
+                    .withf(move |c| c.id == id)
+                    .times(1)
+                    .in_sequence(&mut seq)
+                    .returning(|_| Ok(()));
+            }
+        });
+
+        let applied = db.migrate().await.unwrap();
+        let applied_ids: Vec<&str> = applied.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(applied_ids, vec!["doug:1", "doug:3"]);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_stops_at_failure() {
+        let db = make_db_with_mock(|mock| {
+            mock.expect_changesets().returning(|| {
+                ["doug:1", "doug:2"]
+                    .iter()
+                    .map(|id| Changeset {
+                        file: "migrations/foo.sql",
+                        id: id.to_string(),
+                        sql: "SELECT 1".to_string(),
+                    })
+                    .collect()
+            });
+            mock.expect_select_applied_changesets()
+                .returning(|| Ok(HashSet::new()));
+            mock.expect_apply_changeset()
+                .times(1)
+                .returning(|_| Err(anyhow!("oops")));
+        });
+
+        assert_eq!(db.migrate().await.unwrap_err().to_string(), "oops");
+    }
+
+    #[tokio::test]
+    async fn test_search_plants_by_name_uses_loaded_index() {
+        let db = make_db_with_mock(|mock| {
+            mock.expect_select_plants_after_id()
+                .returning(|after_id, _| {
+                    if after_id > 0 {
+                        return Ok(vec![]);
+                    }
+
+                    let mut aster = Plant::new("Symphyotrichum novae-angliae", "New England Aster");
+                    aster.id = Some(1);
+                    Ok(vec![aster])
+                });
+            mock.expect_select_plant_synonyms().returning(|_| {
+                Ok(vec![(
+                    1,
+                    PlantSynonym::new("Aster novae-angliae", SynonymKind::Scientific),
+                )])
+            });
+            mock.expect_update_plant().returning(|_, _| Ok(()));
+            mock.expect_insert_plant_synonyms().returning(|_, _| Ok(()));
+            mock.expect_find_plants_by_word_prefix().never();
+        });
+
+        assert_eq!(db.load_search_index().await.unwrap(), 1);
+        let found = db.search_plants_by_name("astr nov").await;
+        assert_eq!(found[0].matched.name, "Aster novae-angliae");
+
+        // Saving a plant updates the index
+        let mut renamed = found[0].plant.clone();
+        renamed.common = "Michaelmas Daisy".to_string();
+        db.save_plant(&renamed).await.unwrap();
+        let found = db.search_plants_by_name("michael").await;
+        assert_eq!(found[0].plant.id, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_load_search_index_again_finds_plants_written_elsewhere() {
+        let db = make_db_with_mock(|mock| {
+            let mut loads = 0;
+            mock.expect_select_plants_after_id()
+                .returning(move |after_id, _| {
+                    if after_id > 0 {
+                        return Ok(vec![]);
+                    }
+
+                    // Another process, like import, adds a plant after the
+                    // index is first loaded
+                    loads += 1;
+                    let mut plants = vec![Plant::new("Asclepias syriaca", "Common Milkweed")];
+                    if loads > 1 {
+                        plants.push(Plant::new("Solidago speciosa", "Showy Goldenrod"));
+                    }
+                    for (i, plant) in plants.iter_mut().enumerate() {
+                        plant.id = Some(i + 1);
+                    }
+                    Ok(plants)
+                });
+            mock.expect_select_plant_synonyms()
+                .returning(|_| Ok(vec![]));
+            mock.expect_find_plants_by_word_prefix().never();
+        });
+
+        assert_eq!(db.load_search_index().await.unwrap(), 1);
+        assert!(db.search_plants_by_name("goldenrod").await.is_empty());
+
+        assert_eq!(db.load_search_index().await.unwrap(), 2);
+        let found = db.search_plants_by_name("goldenrod").await;
+        assert_eq!(found[0].plant.id, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_search_plants_by_name_falls_back_without_index() {
+        let db = make_db_with_mock(|mock| {
+            mock.expect_find_plants_by_word_prefix()
+                .times(1)
+                .returning(|_| {
+                    let mut milkweed = Plant::new("Asclepias syriaca", "Common Milkweed");
+                    milkweed.id = Some(1);
+                    Ok(vec![milkweed])
+                });
+            mock.expect_select_plant_synonyms()
+                .returning(|_| Ok(vec![]));
+        });
+
+        let found = db.search_plants_by_name("milkweed").await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].matched.ranges, vec![[7, 15]]);
+
+        // Too short to search without the index
+        assert!(db.search_plants_by_name("mi").await.is_empty());
     }
 
     fn make_db() -> Database {
-        let sql_mock = SqlRunner::default();
+        let sql_mock = MockStorage::new();
 
         Database {
-            sql_runner: sql_mock,
+            sql_runner: Box::new(sql_mock),
+            search_index: PlantSearchIndex::default(),
         }
     }
 
     fn make_db_with_mock<F>(create_mocks: F) -> Database
     where
-        F: FnOnce(&mut MockSqlRunner),
+        F: FnOnce(&mut MockStorage),
     {
-        let mut sql_mock = SqlRunner::default();
+        let mut sql_mock = MockStorage::new();
         create_mocks(&mut sql_mock);
 
         Database {
-            sql_runner: sql_mock,
+            sql_runner: Box::new(sql_mock),
+            search_index: PlantSearchIndex::default(),
         }
     }
 }
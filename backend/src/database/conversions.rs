@@ -45,11 +45,25 @@ impl FromRow for Plant {
         let original_url = take_lenient(&mut row, "original_url");
         let author = take_lenient(&mut row, "author");
         let license = take_lenient(&mut row, "license");
+        let usda_symbol: Option<String> = take_lenient(&mut row, "usda_symbol");
         let usda_source: Option<String> = take_lenient(&mut row, "usda_source");
         let wiki_source: Option<String> = take_lenient(&mut row, "wiki_source");
         let height: Option<String> = take_lenient(&mut row, "height");
         let spread: Option<String> = take_lenient(&mut row, "spread");
 
+        // Structured bloom and size are only usable when both bounds are known
+        let bloom_period = take_lenient(&mut row, "bloom_start_month")
+            .zip(take_lenient(&mut row, "bloom_end_month"))
+            .map(|(start_month, end_month)| BloomPeriod {
+                start_month,
+                end_month,
+            });
+        let bloom_colors: Vec<String> = take_lenient(&mut row, "bloom_colors")
+            .map(|s: String| s.split(',').map(str::to_string).collect())
+            .unwrap_or_default();
+        let height_range = take_size_range(&mut row, "height");
+        let spread_range = take_size_range(&mut row, "spread");
+
         // These are comma separated, ex: "Some,Lots"
         // This parses them into their respective vectors of enums
         let moistures: Vec<Moisture> = take_lenient(&mut row, "moistures")
@@ -66,12 +80,17 @@ impl FromRow for Plant {
             bloom,
             height,
             spread,
+            bloom_period,
+            bloom_colors,
+            height_range,
+            spread_range,
             moistures,
             shades,
             pollinator_rating,
             bird_rating,
             spread_rating,
             deer_resistance_rating,
+            usda_symbol,
             usda_source,
             wiki_source,
             image: img_id.map(|_| {
@@ -97,12 +116,61 @@ impl FromRow for Plant {
     }
 }
 
+impl FromRow for GardenPlant {
+    fn from_row_opt(mut row: mysql_async::Row) -> Result<Self, FromRowError>
+    where
+        Self: Sized,
+    {
+        let quantity = row.take("quantity").unwrap();
+        let zone = row.take("zone").unwrap();
+        let notes = take_lenient(&mut row, "notes");
+        let planted_date = take_lenient(&mut row, "planted_date");
+        let status: String = row.take("status").unwrap();
+        let status = GardenPlantStatus::from_str(&status)
+            .expect("gardens_plants.status should have valid values");
+
+        Ok(GardenPlant {
+            plant: Plant::from_row_opt(row)?,
+            details: GardenPlantDetails {
+                quantity,
+                notes,
+                planted_date,
+                status,
+                zone,
+            },
+            warnings: vec![],
+        })
+    }
+}
+
+impl FromRow for GardenZone {
+    fn from_row_opt(row: mysql_async::Row) -> Result<Self, FromRowError>
+    where
+        Self: Sized,
+    {
+        let (name, shade, moisture) = mysql_async::from_row_opt(row)?;
+
+        let shade: String = shade;
+        let shade = Shade::from_str(&shade).expect("garden_zones.shade should have valid values");
+
+        let moisture: String = moisture;
+        let moisture =
+            Moisture::from_str(&moisture).expect("garden_zones.moisture should have valid values");
+
+        Ok(GardenZone {
+            name,
+            shade,
+            moisture,
+        })
+    }
+}
+
 impl FromRow for Garden {
     fn from_row_opt(row: mysql_async::Row) -> Result<Self, FromRowError>
     where
         Self: Sized,
     {
-        let (name, zipcode, region_name, shade, moisture, read_id, latitude, longitude) =
+        let (name, zipcode, region_name, shade, moisture, read_id, latitude, longitude, region_id) =
             mysql_async::from_row_opt(row)?;
 
         let zipcode: usize = zipcode;
@@ -118,17 +186,46 @@ impl FromRow for Garden {
             name,
             zipcode,
             region_name,
+            region_id,
             shade,
             moisture,
             read_id,
             write_id: None,
             plants: vec![],
+            zones: vec![],
+            latitude,
+            longitude,
+        })
+    }
+}
+
+impl FromRow for Region {
+    fn from_row_opt(row: mysql_async::Row) -> Result<Self, FromRowError>
+    where
+        Self: Sized,
+    {
+        let (id, name, latitude, longitude, radius_miles) = mysql_async::from_row_opt(row)?;
+        Ok(Region {
+            id,
+            name,
             latitude,
             longitude,
+            radius_miles,
         })
     }
 }
 
+/// Takes a SizeRange from the {dimension}_min_inches/{dimension}_max_inches columns
+fn take_size_range(row: &mut Row, dimension: &str) -> Option<SizeRange> {
+    let min = take_lenient(row, &format!("{dimension}_min_inches"));
+    let max = take_lenient(row, &format!("{dimension}_max_inches"));
+
+    min.zip(max).map(|(min_inches, max_inches)| SizeRange {
+        min_inches,
+        max_inches,
+    })
+}
+
 pub fn take_lenient<T>(row: &mut Row, field: &str) -> Option<T>
 where
     T: FromValue,
@@ -0,0 +1,260 @@
+use std::fmt::{Display, Formatter};
+
+// The populate scripts are plain inserts/updates which work in both MySQL and
+// SQLite, so both changelogs share them.
+const POPULATE_ZIPCODES: &str = include_str!("../../db/migrations/populate-zipcodes.sql");
+const POPULATE_NURSERIES: &str = include_str!("../../db/migrations/populate-nurseries.sql");
+const POPULATE_REGIONS: &str = include_str!("../../db/migrations/populate-regions.sql");
+
+/// Embedded copy of db/migrations/changelog.xml, in the same order.
+/// File names match the ones liquibase records in DATABASECHANGELOG.
+const MYSQL_CHANGELOG: &[(&str, &str)] = &[
+    (
+        "migrations/create-region-table.sql",
+        include_str!("../../db/migrations/create-region-table.sql"),
+    ),
+    (
+        "migrations/create-zipcode-table.sql",
+        include_str!("../../db/migrations/create-zipcode-table.sql"),
+    ),
+    (
+        "migrations/create-nursery-tables.sql",
+        include_str!("../../db/migrations/create-nursery-tables.sql"),
+    ),
+    (
+        "migrations/create-images-table.sql",
+        include_str!("../../db/migrations/create-images-table.sql"),
+    ),
+    (
+        "migrations/create-plants-table.sql",
+        include_str!("../../db/migrations/create-plants-table.sql"),
+    ),
+    (
+        "migrations/create-query-tables.sql",
+        include_str!("../../db/migrations/create-query-tables.sql"),
+    ),
+    (
+        "migrations/create-region-plants-table.sql",
+        include_str!("../../db/migrations/create-region-plants-table.sql"),
+    ),
+    (
+        "migrations/create-garden-tables.sql",
+        include_str!("../../db/migrations/create-garden-tables.sql"),
+    ),
+    (
+        "migrations/create-request-count-table.sql",
+        include_str!("../../db/migrations/create-request-count-table.sql"),
+    ),
+    ("migrations/populate-zipcodes.sql", POPULATE_ZIPCODES),
+    ("migrations/populate-nurseries.sql", POPULATE_NURSERIES),
+    ("migrations/populate-regions.sql", POPULATE_REGIONS),
+    (
+        "migrations/add-plant-details.sql",
+        include_str!("../../db/migrations/add-plant-details.sql"),
+    ),
+    (
+        "migrations/add-moistures-shades.sql",
+        include_str!("../../db/migrations/add-moistures-shades.sql"),
+    ),
+    (
+        "migrations/add-garden-lat-lng.sql",
+        include_str!("../../db/migrations/add-garden-lat-lng.sql"),
+    ),
+    (
+        "migrations/create-plant-changes-table.sql",
+        include_str!("../../db/migrations/create-plant-changes-table.sql"),
+    ),
+    (
+        "migrations/add-structured-plant-details.sql",
+        include_str!("../../db/migrations/add-structured-plant-details.sql"),
+    ),
+    (
+        "migrations/create-zipcode-prefixes-table.sql",
+        include_str!("../../db/migrations/create-zipcode-prefixes-table.sql"),
+    ),
+    (
+        "migrations/add-region-plant-provenance.sql",
+        include_str!("../../db/migrations/add-region-plant-provenance.sql"),
+    ),
+    (
+        "migrations/add-usda-symbol.sql",
+        include_str!("../../db/migrations/add-usda-symbol.sql"),
+    ),
+    (
+        "migrations/create-plant-synonyms-table.sql",
+        include_str!("../../db/migrations/create-plant-synonyms-table.sql"),
+    ),
+    (
+        "migrations/add-garden-plant-details.sql",
+        include_str!("../../db/migrations/add-garden-plant-details.sql"),
+    ),
+    (
+        "migrations/create-garden-zones-table.sql",
+        include_str!("../../db/migrations/create-garden-zones-table.sql"),
+    ),
+];
+
+/// SQLite creates its original tables in one file, then shares the populate
+/// scripts.  Later tables get their own files.
+const SQLITE_CHANGELOG: &[(&str, &str)] = &[
+    (
+        "sqlite/create-tables.sql",
+        include_str!("../../db/sqlite/create-tables.sql"),
+    ),
+    ("migrations/populate-zipcodes.sql", POPULATE_ZIPCODES),
+    ("migrations/populate-nurseries.sql", POPULATE_NURSERIES),
+    ("migrations/populate-regions.sql", POPULATE_REGIONS),
+    (
+        "sqlite/create-plant-changes-table.sql",
+        include_str!("../../db/sqlite/create-plant-changes-table.sql"),
+    ),
+    (
+        "sqlite/add-structured-plant-details.sql",
+        include_str!("../../db/sqlite/add-structured-plant-details.sql"),
+    ),
+    (
+        "sqlite/create-zipcode-prefixes-table.sql",
+        include_str!("../../db/sqlite/create-zipcode-prefixes-table.sql"),
+    ),
+    (
+        "sqlite/add-region-plant-provenance.sql",
+        include_str!("../../db/sqlite/add-region-plant-provenance.sql"),
+    ),
+    (
+        "sqlite/add-usda-symbol.sql",
+        include_str!("../../db/sqlite/add-usda-symbol.sql"),
+    ),
+    (
+        "sqlite/create-plant-synonyms-table.sql",
+        include_str!("../../db/sqlite/create-plant-synonyms-table.sql"),
+    ),
+    (
+        "sqlite/add-garden-plant-details.sql",
+        include_str!("../../db/sqlite/add-garden-plant-details.sql"),
+    ),
+    (
+        "sqlite/create-garden-zones-table.sql",
+        include_str!("../../db/sqlite/create-garden-zones-table.sql"),
+    ),
+];
+
+/// One liquibase changeset: a block of sql starting with "--changeset author:id".
+#[derive(Debug, Clone, PartialEq)]
+pub struct Changeset {
+    /// The changelog file this came from, ex: "migrations/create-plants-table.sql"
+    pub file: &'static str,
+
+    /// The changeset's "author:id", unique within its file
+    pub id: String,
+
+    /// The statements to execute
+    pub sql: String,
+}
+
+impl Display for Changeset {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}::{}", self.file, self.id)
+    }
+}
+
+/// All changesets for the MySQL schema, in the order they must be applied.
+pub fn mysql_changesets() -> Vec<Changeset> {
+    parse_changelog(MYSQL_CHANGELOG)
+}
+
+/// All changesets for the SQLite schema, in the order they must be applied.
+pub fn sqlite_changesets() -> Vec<Changeset> {
+    parse_changelog(SQLITE_CHANGELOG)
+}
+
+fn parse_changelog(changelog: &[(&'static str, &str)]) -> Vec<Changeset> {
+    changelog
+        .iter()
+        .flat_map(|(file, contents)| parse_changesets(file, contents))
+        .collect()
+}
+
+/// Splits a liquibase formatted sql file into its changesets.  Anything
+/// before the first changeset (like the "--liquibase formatted sql" header)
+/// is ignored.
+pub fn parse_changesets(file: &'static str, contents: &str) -> Vec<Changeset> {
+    let mut changesets: Vec<Changeset> = vec![];
+
+    for line in contents.lines() {
+        if let Some(attributes) = line.strip_prefix("--changeset ") {
+            // Attributes after the id (ex: "splitStatements:false") are ignored
+            let id = attributes.split_whitespace().next().unwrap_or_default();
+
+            changesets.push(Changeset {
+                file,
+                id: id.to_string(),
+                sql: String::new(),
+            });
+        } else if let Some(changeset) = changesets.last_mut() {
+            changeset.sql.push_str(line);
+            changeset.sql.push('\n');
+        }
+    }
+
+    for changeset in &mut changesets {
+        changeset.sql = changeset.sql.trim().to_string();
+    }
+
+    changesets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_changesets() {
+        let contents = r"--liquibase formatted sql
+
+--changeset doug:1
+CREATE TABLE foo (id INT);
+
+--changeset doug:2 splitStatements:false
+INSERT INTO foo VALUES (1);
+INSERT INTO foo VALUES (2);
+";
+
+        let changesets = parse_changesets("migrations/foo.sql", contents);
+
+        assert_eq!(changesets.len(), 2);
+        assert_eq!(changesets[0].id, "doug:1");
+        assert_eq!(changesets[0].sql, "CREATE TABLE foo (id INT);");
+        assert_eq!(changesets[1].id, "doug:2");
+        assert_eq!(
+            changesets[1].sql,
+            "INSERT INTO foo VALUES (1);\nINSERT INTO foo VALUES (2);"
+        );
+        assert_eq!(changesets[1].to_string(), "migrations/foo.sql::doug:2");
+    }
+
+    #[test]
+    fn test_mysql_changelog_matches_liquibase_changelog() {
+        let changelog_xml = include_str!("../../db/migrations/changelog.xml");
+        let liquibase_files: Vec<&str> = changelog_xml
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("<include file=\""))
+            .filter_map(|line| line.split('"').next())
+            .collect();
+
+        let embedded_files: Vec<&str> = MYSQL_CHANGELOG.iter().map(|(file, _)| *file).collect();
+
+        assert_eq!(embedded_files, liquibase_files);
+    }
+
+    #[test]
+    fn test_changeset_ids_are_unique() {
+        for changesets in [mysql_changesets(), sqlite_changesets()] {
+            let mut keys: Vec<String> = changesets.iter().map(|c| c.to_string()).collect();
+            let count = keys.len();
+            keys.sort();
+            keys.dedup();
+
+            assert_eq!(keys.len(), count);
+        }
+    }
+}
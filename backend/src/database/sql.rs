@@ -1,30 +1,37 @@
 use crate::domain::*;
 use anyhow::anyhow;
-use mockall::automock;
-use mysql_async::{prelude::*, Conn, Opts, Pool};
+use async_trait::async_trait;
+use mysql_async::{prelude::*, Conn, Opts, Params, Pool};
 use std::{collections::HashSet, fmt::Display};
-use tracing::log::warn;
+use tracing::log::{info, warn};
 
-// Looks for the closest neighboring zip code to the one provided on both sides,
-// then selects the one that is closest.
-const SELECT_CLOSEST_ZIP_QUERY: &str = r"
-SELECT
-  CASE WHEN prev IS NULL THEN nxt
-       WHEN nxt IS NULL THEN prev
-       WHEN ABS(:zip - IFNULL(prev, nxt)) < ABS(:zip - IFNULL(nxt, prev)) THEN prev
-       ELSE nxt
-  END AS closest_key
-  FROM (
-    SELECT :zip as zipcode,
-       (SELECT MAX(zipcode) FROM zipcodes AS prev WHERE prev.zipcode < :zip) AS prev,
-       (SELECT MIN(zipcode) FROM zipcodes AS nxt WHERE nxt.zipcode > :zip) AS nxt
-  ) AS subquery";
+use super::{
+    migrations::{mysql_changesets, Changeset},
+    storage::{plant_page_clauses, Storage, PLANT_FILTER_CONDITIONS},
+};
+
+// The plants in a zip's region which grow in any of the comma separated
+// :moistures and any of the :shades.  Plants with unknown moistures or shades
+// are assumed to grow anywhere.
+const FROM_PLANTS_BY_ZIP_MOISTURE_SHADE: &str = r"
+FROM plants p
+INNER JOIN regions_plants rp on rp.plant_id = p.id
+INNER JOIN zipcodes z ON z.region_id = rp.region_id
+LEFT JOIN images i ON i.id = p.image_id
+WHERE z.zipcode = :zipcode
+  AND (p.moistures is NULL
+    OR (FIND_IN_SET('None', :moistures) AND FIND_IN_SET('None', p.moistures))
+    OR (FIND_IN_SET('Some', :moistures) AND FIND_IN_SET('Some', p.moistures))
+    OR (FIND_IN_SET('Lots', :moistures) AND FIND_IN_SET('Lots', p.moistures)))
+  AND (p.shades is NULL
+    OR (FIND_IN_SET('None', :shades) AND FIND_IN_SET('None', p.shades))
+    OR (FIND_IN_SET('Some', :shades) AND FIND_IN_SET('Some', p.shades))
+    OR (FIND_IN_SET('Lots', :shades) AND FIND_IN_SET('Lots', p.shades)))";
 
 pub struct SqlRunner {
     pool: Option<Pool>,
 }
 
-#[automock]
 impl SqlRunner {
     pub fn new(url: &str) -> Self {
         if Opts::try_from(url).is_err() {
@@ -51,10 +58,13 @@ impl SqlRunner {
             Err(anyhow!("db is not connected"))
         }
     }
+}
 
+#[async_trait]
+impl Storage for SqlRunner {
     /// Inserts a new Query into the database.
     /// Returns Err if it fails.
-    pub async fn upsert_query(
+    async fn upsert_query(
         &self,
         zip: &str,
         moisture: &Moisture,
@@ -76,7 +86,7 @@ impl SqlRunner {
         }
     }
 
-    pub async fn check_zip_exists(&self, zip: &str) -> anyhow::Result<bool> {
+    async fn check_zip_exists(&self, zip: &str) -> anyhow::Result<bool> {
         let mut conn = self.get_connection().await?;
         let query_result: Result<Option<u8>, mysql_async::Error> =
             r"SELECT 1 from zipcodes where zipcode = :zip"
@@ -93,27 +103,30 @@ impl SqlRunner {
         }
     }
 
-    pub async fn select_closest_zip(&self, zip: &str) -> anyhow::Result<String> {
+    async fn select_zip_prefix_centroid(
+        &self,
+        prefix: &str,
+    ) -> anyhow::Result<Option<Coordinates>> {
         let mut conn = self.get_connection().await?;
 
-        let query_result: Result<Option<usize>, mysql_async::Error> = SELECT_CLOSEST_ZIP_QUERY
+        r"SELECT latitude, longitude FROM zipcode_prefixes WHERE prefix = :prefix"
             .with(params! {
-                "zip" => zip,
+                "prefix" => prefix,
             })
             .first(&mut conn)
-            .await;
-
-        match query_result {
-            // db has this as integer, so format to 5 chars w/ leading zeros
-            Ok(Some(closest_zip)) => Ok(format!("{closest_zip:05}")),
-            Ok(None) => Err(anyhow!("select_closest_zip closest zip not found")),
-            Err(e) => Err(anyhow!("select_closest_zip error finding closest zip: {e}")),
-        }
+            .await
+            .map(|centroid: Option<(f64, f64)>| {
+                centroid.map(|(latitude, longitude)| Coordinates {
+                    latitude,
+                    longitude,
+                })
+            })
+            .map_err(|e| anyhow!("select_zip_prefix_centroid failed: {e}"))
     }
 
     /// Selects one plant by scientific name.
     /// Returns Err if it fails, Ok(None) if not found.
-    pub async fn select_query_count(
+    async fn select_query_count(
         &self,
         zip: &str,
         moisture: &Moisture,
@@ -140,22 +153,30 @@ AND region_id = (SELECT region_id from zipcodes where zipcode = :zip)"
 
     /// Inserts into regions_plants.
     /// Returns Err if it fails.
-    pub async fn insert_region_plants(
+    async fn insert_region_plants(
         &self,
         zip: &str,
         plant_ids: HashSet<usize>,
+        provenance: &NativeProvenance,
     ) -> anyhow::Result<()> {
         let mut conn = self.get_connection().await?;
 
-        // Some rows could already exist, this ignores duplicate key errors
-        // The "dummy update" is required to make this statement valid.
-        r"INSERT INTO regions_plants (region_id, plant_id)
-            VALUES ((SELECT region_id from zipcodes where zipcode = :zip), :plant_id)
-            ON DUPLICATE KEY UPDATE region_id=region_id, plant_id=plant_id"
+        // Some rows could already exist.  Generated plants shouldn't replace
+        // a better source, so those keep what they had.
+        r"INSERT INTO regions_plants (region_id, plant_id, source, confidence, added_at)
+            VALUES (
+              (SELECT region_id from zipcodes where zipcode = :zip), :plant_id,
+              :source, :confidence, CURRENT_TIMESTAMP
+            )
+            ON DUPLICATE KEY UPDATE
+              confidence = IF(VALUES(source) = 'Generated', confidence, VALUES(confidence)),
+              source = IF(VALUES(source) = 'Generated', source, VALUES(source))"
             .with(plant_ids.iter().map(|id| {
                 params! {
                     "zip" => zip,
-                    "plant_id" => id
+                    "plant_id" => id,
+                    "source" => provenance.source.to_string(),
+                    "confidence" => provenance.confidence.to_string(),
                 }
             }))
             .batch(&mut conn)
@@ -165,16 +186,20 @@ AND region_id = (SELECT region_id from zipcodes where zipcode = :zip)"
 
     /// Updates one plant.
     /// Returns Err if it fails.
-    pub async fn update_plant(&self, plant: &Plant, img_id: Option<usize>) -> anyhow::Result<()> {
+    async fn update_plant(&self, plant: &Plant, img_id: Option<usize>) -> anyhow::Result<()> {
         let mut conn = self.get_connection().await?;
 
         r"UPDATE plants
-              SET pollinator_rating = :pollinator_rating,
+              SET scientific_name = :scientific_name,
+                  common_name = :common_name,
+
+                  pollinator_rating = :pollinator_rating,
                   bird_rating = :bird_rating,
 
                   spread_rating = :spread_rating,
                   deer_resistance_rating = :deer_resistance_rating,
 
+                  usda_symbol = :usda_symbol,
                   usda_source = :usda_source,
                   wiki_source = :wiki_source,
 
@@ -182,6 +207,14 @@ AND region_id = (SELECT region_id from zipcodes where zipcode = :zip)"
                   height = :height,
                   spread = :spread,
 
+                  bloom_start_month = :bloom_start_month,
+                  bloom_end_month = :bloom_end_month,
+                  bloom_colors = :bloom_colors,
+                  height_min_inches = :height_min_inches,
+                  height_max_inches = :height_max_inches,
+                  spread_min_inches = :spread_min_inches,
+                  spread_max_inches = :spread_max_inches,
+
                   moistures = :moistures,
                   shades = :shades,
 
@@ -190,12 +223,16 @@ AND region_id = (SELECT region_id from zipcodes where zipcode = :zip)"
             .with(params! {
                 "id" => plant.id,
 
+                "scientific_name" => &plant.scientific,
+                "common_name" => &plant.common,
+
                 "pollinator_rating" => plant.pollinator_rating,
                 "bird_rating" => plant.bird_rating,
 
                 "spread_rating" => plant.spread_rating,
                 "deer_resistance_rating" => plant.deer_resistance_rating,
 
+                "usda_symbol" => &plant.usda_symbol,
                 "usda_source" => &plant.usda_source,
                 "wiki_source" => &plant.wiki_source,
 
@@ -203,6 +240,14 @@ AND region_id = (SELECT region_id from zipcodes where zipcode = :zip)"
                 "height" => &plant.height,
                 "spread" => &plant.spread,
 
+                "bloom_start_month" => plant.bloom_period.map(|b| b.start_month),
+                "bloom_end_month" => plant.bloom_period.map(|b| b.end_month),
+                "bloom_colors" => to_comma_separated_string(&plant.bloom_colors),
+                "height_min_inches" => plant.height_range.map(|r| r.min_inches),
+                "height_max_inches" => plant.height_range.map(|r| r.max_inches),
+                "spread_min_inches" => plant.spread_range.map(|r| r.min_inches),
+                "spread_max_inches" => plant.spread_range.map(|r| r.max_inches),
+
                 "moistures" => to_comma_separated_string(&plant.moistures),
                 "shades" => to_comma_separated_string(&plant.shades),
 
@@ -215,30 +260,30 @@ AND region_id = (SELECT region_id from zipcodes where zipcode = :zip)"
 
     /// Inserts one plant.
     /// Returns Err if it fails.
-    pub async fn insert_plant(
-        &self,
-        plant: &Plant,
-        img_id: Option<usize>,
-    ) -> anyhow::Result<usize> {
+    async fn insert_plant(&self, plant: &Plant, img_id: Option<usize>) -> anyhow::Result<usize> {
         let mut conn = self.get_connection().await?;
 
         r"INSERT INTO plants
         (scientific_name, common_name,
          bloom, height, spread,
+         bloom_start_month, bloom_end_month, bloom_colors,
+         height_min_inches, height_max_inches, spread_min_inches, spread_max_inches,
          moistures, shades,
          pollinator_rating,
          bird_rating,
          spread_rating, deer_resistance_rating,
-         usda_source, wiki_source,
+         usda_symbol, usda_source, wiki_source,
          image_id)
       VALUES
         (:scientific_name, :common_name,
          :bloom, :height, :spread,
+         :bloom_start_month, :bloom_end_month, :bloom_colors,
+         :height_min_inches, :height_max_inches, :spread_min_inches, :spread_max_inches,
          :moistures, :shades,
          :pollinator_rating,
          :bird_rating,
          :spread_rating, :deer_resistance_rating,
-         :usda_source, :wiki_source,
+         :usda_symbol, :usda_source, :wiki_source,
          :image_id)
             RETURNING id"
             .with(params! {
@@ -249,12 +294,21 @@ AND region_id = (SELECT region_id from zipcodes where zipcode = :zip)"
                 "height" => &plant.height,
                 "spread" => &plant.spread,
 
+                "bloom_start_month" => plant.bloom_period.map(|b| b.start_month),
+                "bloom_end_month" => plant.bloom_period.map(|b| b.end_month),
+                "bloom_colors" => to_comma_separated_string(&plant.bloom_colors),
+                "height_min_inches" => plant.height_range.map(|r| r.min_inches),
+                "height_max_inches" => plant.height_range.map(|r| r.max_inches),
+                "spread_min_inches" => plant.spread_range.map(|r| r.min_inches),
+                "spread_max_inches" => plant.spread_range.map(|r| r.max_inches),
+
                 "pollinator_rating" => plant.pollinator_rating,
                 "bird_rating" => plant.bird_rating,
 
                 "spread_rating" => plant.spread_rating,
                 "deer_resistance_rating" => plant.deer_resistance_rating,
 
+                "usda_symbol" => &plant.usda_symbol,
                 "usda_source" => &plant.usda_source,
                 "wiki_source" => &plant.wiki_source,
 
@@ -269,52 +323,72 @@ AND region_id = (SELECT region_id from zipcodes where zipcode = :zip)"
             .map_err(|e| anyhow!("save_plant failed to insert: {}", e))
     }
 
-    /// Selects multiple plants by zip/moisture/shade.
+    /// Selects one page of plants by zip which grow in any of the moistures
+    /// and any of the shades, narrowed by filters.
     /// Returns Err if it fails.
-    pub async fn select_plants_by_zip_moisture_shade(
+    async fn select_plants_by_zip_moisture_shade(
         &self,
         zip: &str,
-        moisture: &Moisture,
-        shade: &Shade,
+        moistures: &[Moisture],
+        shades: &[Shade],
+        filters: &PlantFilters,
+        page: &PlantPage,
     ) -> anyhow::Result<Vec<Plant>> {
         let mut conn = self.get_connection().await?;
 
-        r"
+        format!(
+            r"
 SELECT
   p.id, p.scientific_name, p.common_name,
   p.bloom, p.height, p.spread,
+  p.bloom_start_month, p.bloom_end_month, p.bloom_colors,
+  p.height_min_inches, p.height_max_inches, p.spread_min_inches, p.spread_max_inches,
   p.moistures, p.shades,
   p.pollinator_rating,
   p.bird_rating,
   p.spread_rating, p.deer_resistance_rating,
-  p.usda_source, p.wiki_source,
+  p.usda_symbol, p.usda_source, p.wiki_source,
   i.id as image_id, i.title, i.card_url, i.original_url, i.author, i.license
-FROM plants p
+{FROM_PLANTS_BY_ZIP_MOISTURE_SHADE}
+  {PLANT_FILTER_CONDITIONS}
+{}
+",
+            plant_page_clauses(page)
+        )
+        .with(zip_moisture_shade_params(zip, moistures, shades, filters))
+        .map(&mut conn, |plant: Plant| plant)
+        .await
+        .map_err(|e| anyhow!(e))
+    }
 
-INNER JOIN regions_plants rp on rp.plant_id = p.id
-INNER JOIN zipcodes z ON z.region_id = rp.region_id
-LEFT JOIN images i ON i.id = p.image_id
-WHERE z.zipcode = :zipcode
-  AND (p.moistures is NULL OR FIND_IN_SET(:moisture, p.moistures))
-  AND (p.shades is NULL OR FIND_IN_SET(:shade, p.shades))
-ORDER BY
-  p.moistures IS NOT NULL and p.shades IS NOT NULL desc,
-  POW(p.pollinator_rating, 3) + POW(p.bird_rating, 3) desc
+    /// Counts every plant select_plants_by_zip_moisture_shade could return.
+    /// Returns Err if it fails.
+    async fn count_plants_by_zip_moisture_shade(
+        &self,
+        zip: &str,
+        moistures: &[Moisture],
+        shades: &[Shade],
+        filters: &PlantFilters,
+    ) -> anyhow::Result<usize> {
+        let mut conn = self.get_connection().await?;
 
+        format!(
+            r"
+SELECT COUNT(*)
+{FROM_PLANTS_BY_ZIP_MOISTURE_SHADE}
+  {PLANT_FILTER_CONDITIONS}
 "
-        .with(params! {
-            "zipcode" => zip,
-            "moisture" => moisture.to_string(),
-            "shade" => shade.to_string(),
-        })
-        .map(&mut conn, |plant: Plant| plant)
+        )
+        .with(zip_moisture_shade_params(zip, moistures, shades, filters))
+        .first(&mut conn)
         .await
-        .map_err(|e| anyhow!(e))
+        .map(|count| count.unwrap_or(0))
+        .map_err(|e| anyhow!("count_plants_by_zip_moisture_shade failed: {e}"))
     }
 
     /// Selects one plant by scientific name.
     /// Returns Err if it fails, Ok(None) if not found.
-    pub async fn select_plant_by_scientific_name(
+    async fn select_plant_by_scientific_name(
         &self,
         scientific_name: &str,
     ) -> anyhow::Result<Option<Plant>> {
@@ -324,15 +398,23 @@ ORDER BY
 SELECT
   p.id, p.scientific_name, p.common_name,
   p.bloom, p.height, p.spread,
+  p.bloom_start_month, p.bloom_end_month, p.bloom_colors,
+  p.height_min_inches, p.height_max_inches, p.spread_min_inches, p.spread_max_inches,
   p.moistures, p.shades,
   p.pollinator_rating,
   p.bird_rating,
   p.spread_rating, p.deer_resistance_rating,
-  p.usda_source, p.wiki_source,
+  p.usda_symbol, p.usda_source, p.wiki_source,
   i.id as image_id, i.title, i.card_url, i.original_url, i.author, i.license
 FROM plants p
 LEFT JOIN images i ON i.id = p.image_id
-WHERE scientific_name = :scientific_name"
+WHERE scientific_name = :scientific_name
+   OR p.id IN (
+     SELECT plant_id FROM plant_synonyms
+     WHERE kind = 'Scientific' AND name = :scientific_name
+   )
+ORDER BY scientific_name = :scientific_name DESC
+LIMIT 1"
             .with(params! {
                 "scientific_name" => scientific_name,
             })
@@ -343,18 +425,20 @@ WHERE scientific_name = :scientific_name"
 
     /// Selects one plant by id.
     /// Returns Err if it fails, Ok(None) if not found.
-    pub async fn select_plant_by_id(&self, id: usize) -> anyhow::Result<Option<Plant>> {
+    async fn select_plant_by_id(&self, id: usize) -> anyhow::Result<Option<Plant>> {
         let mut conn = self.get_connection().await?;
 
         r"
 SELECT
   p.id, p.scientific_name, p.common_name,
   p.bloom, p.height, p.spread,
+  p.bloom_start_month, p.bloom_end_month, p.bloom_colors,
+  p.height_min_inches, p.height_max_inches, p.spread_min_inches, p.spread_max_inches,
   p.moistures, p.shades,
   p.pollinator_rating,
   p.bird_rating,
   p.spread_rating, p.deer_resistance_rating,
-  p.usda_source, p.wiki_source,
+  p.usda_symbol, p.usda_source, p.wiki_source,
   i.id as image_id, i.title, i.card_url, i.original_url, i.author, i.license
 FROM plants p
 LEFT JOIN images i ON i.id = p.image_id
@@ -367,9 +451,204 @@ WHERE p.id = :id"
             .map_err(|e| anyhow!(e))
     }
 
+    /// Selects a page of plants, ordered by id.
+    /// Returns Err if it fails.
+    async fn select_plants_after_id(
+        &self,
+        after_id: usize,
+        limit: usize,
+    ) -> anyhow::Result<Vec<Plant>> {
+        let mut conn = self.get_connection().await?;
+
+        r"
+SELECT
+  p.id, p.scientific_name, p.common_name,
+  p.bloom, p.height, p.spread,
+  p.bloom_start_month, p.bloom_end_month, p.bloom_colors,
+  p.height_min_inches, p.height_max_inches, p.spread_min_inches, p.spread_max_inches,
+  p.moistures, p.shades,
+  p.pollinator_rating,
+  p.bird_rating,
+  p.spread_rating, p.deer_resistance_rating,
+  p.usda_symbol, p.usda_source, p.wiki_source,
+  i.id as image_id, i.title, i.card_url, i.original_url, i.author, i.license
+FROM plants p
+LEFT JOIN images i ON i.id = p.image_id
+WHERE p.id > :after_id
+ORDER BY p.id
+LIMIT :limit"
+            .with(params! {
+                "after_id" => after_id,
+                "limit" => limit,
+            })
+            .map(&mut conn, |plant: Plant| plant)
+            .await
+            .map_err(|e| anyhow!(e))
+    }
+
+    async fn select_plants_missing_details_after_id(
+        &self,
+        after_id: usize,
+        limit: usize,
+    ) -> anyhow::Result<Vec<Plant>> {
+        let mut conn = self.get_connection().await?;
+
+        r"
+SELECT
+  p.id, p.scientific_name, p.common_name,
+  p.bloom, p.height, p.spread,
+  p.bloom_start_month, p.bloom_end_month, p.bloom_colors,
+  p.height_min_inches, p.height_max_inches, p.spread_min_inches, p.spread_max_inches,
+  p.moistures, p.shades,
+  p.pollinator_rating,
+  p.bird_rating,
+  p.spread_rating, p.deer_resistance_rating,
+  p.usda_symbol, p.usda_source, p.wiki_source,
+  i.id as image_id, i.title, i.card_url, i.original_url, i.author, i.license
+FROM plants p
+LEFT JOIN images i ON i.id = p.image_id
+WHERE p.id > :after_id
+  AND ((p.bloom IS NOT NULL AND p.bloom_start_month IS NULL)
+    OR (p.height IS NOT NULL AND p.height_min_inches IS NULL)
+    OR (p.spread IS NOT NULL AND p.spread_min_inches IS NULL)
+    OR p.usda_symbol IS NULL)
+ORDER BY p.id
+LIMIT :limit"
+            .with(params! {
+                "after_id" => after_id,
+                "limit" => limit,
+            })
+            .map(&mut conn, |plant: Plant| plant)
+            .await
+            .map_err(|e| anyhow!(e))
+    }
+
+    /// Inserts into plant_synonyms, skipping names the plant already has.
+    /// Returns Err if it fails.
+    async fn insert_plant_synonyms(
+        &self,
+        plant_id: usize,
+        synonyms: &[PlantSynonym],
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_connection().await?;
+
+        r"INSERT IGNORE INTO plant_synonyms (plant_id, name, kind)
+            VALUES (:plant_id, :name, :kind)"
+            .with(synonyms.iter().map(|synonym| {
+                params! {
+                    "plant_id" => plant_id,
+                    "name" => &synonym.name,
+                    "kind" => synonym.kind.to_string(),
+                }
+            }))
+            .batch(&mut conn)
+            .await
+            .map_err(|e| anyhow!("insert_plant_synonyms failed: {e}"))
+    }
+
+    /// Selects the synonyms of each plant.
+    /// Returns Err if it fails.
+    async fn select_plant_synonyms(
+        &self,
+        plant_ids: Vec<usize>,
+    ) -> anyhow::Result<Vec<(usize, PlantSynonym)>> {
+        if plant_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut conn = self.get_connection().await?;
+
+        let placeholders = vec!["?"; plant_ids.len()].join(", ");
+        format!(
+            r"
+SELECT plant_id, name, kind
+FROM plant_synonyms
+WHERE plant_id IN ({placeholders})
+ORDER BY plant_id, kind, name"
+        )
+        .with(plant_ids)
+        .map(
+            &mut conn,
+            |(plant_id, name, kind): (usize, String, String)| {
+                (
+                    plant_id,
+                    PlantSynonym {
+                        name,
+                        kind: kind
+                            .parse()
+                            .expect("plant_synonyms.kind should have valid values"),
+                    },
+                )
+            },
+        )
+        .await
+        .map_err(|e| anyhow!("select_plant_synonyms failed: {e}"))
+    }
+
+    /// Selects the regions for each plant, with the lowest zipcode in each.
+    /// Returns Err if it fails.
+    async fn select_plant_regions(
+        &self,
+        plant_ids: Vec<usize>,
+    ) -> anyhow::Result<Vec<(usize, PlantRegion)>> {
+        if plant_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut conn = self.get_connection().await?;
+
+        let placeholders = vec!["?"; plant_ids.len()].join(", ");
+        format!(
+            r"
+SELECT
+  rp.plant_id, r.id, r.name, MIN(z.zipcode),
+  rp.source, rp.confidence, DATE_FORMAT(rp.added_at, '%Y-%m-%d')
+FROM regions_plants rp
+INNER JOIN regions r ON r.id = rp.region_id
+INNER JOIN zipcodes z ON z.region_id = r.id
+WHERE rp.plant_id IN ({placeholders})
+GROUP BY rp.plant_id, r.id, r.name, rp.source, rp.confidence, rp.added_at
+ORDER BY rp.plant_id, r.name"
+        )
+        .with(plant_ids)
+        .map(
+            &mut conn,
+            |(plant_id, region_id, name, zipcode, source, confidence, date_added): (
+                usize,
+                usize,
+                String,
+                usize,
+                String,
+                String,
+                Option<String>,
+            )| {
+                (
+                    plant_id,
+                    PlantRegion {
+                        region_id,
+                        name,
+                        // db has this as integer, so format to 5 chars w/ leading zeros
+                        zipcode: format!("{zipcode:05}"),
+                        provenance: NativeProvenance {
+                            source: source
+                                .parse()
+                                .expect("regions_plants.source should have valid values"),
+                            confidence: confidence
+                                .parse()
+                                .expect("regions_plants.confidence should have valid values"),
+                            date_added,
+                        },
+                    },
+                )
+            },
+        )
+        .await
+        .map_err(|e| anyhow!("select_plant_regions failed: {e}"))
+    }
+
     /// Inserts one image.
     /// Returns Err if it fails.
-    pub async fn insert_image(&self, image: &Image) -> anyhow::Result<usize> {
+    async fn insert_image(&self, image: &Image) -> anyhow::Result<usize> {
         let mut conn = self.get_connection().await?;
         r"INSERT INTO images (title, card_url, original_url, author, license)
             VALUES (:title, :card_url, :original_url, :author, :license)
@@ -387,9 +666,26 @@ WHERE p.id = :id"
             .map_err(|e| anyhow!("save_image failed to insert: {}", e))
     }
 
+    /// Inserts one entry in the plant audit log.
+    /// Returns Err if it fails.
+    async fn insert_plant_change(&self, change: &PlantChange) -> anyhow::Result<()> {
+        let mut conn = self.get_connection().await?;
+        r"INSERT INTO plant_changes (plant_id, changed_by, action, changes)
+            VALUES (:plant_id, :changed_by, :action, :changes)"
+            .with(params! {
+                "plant_id" => change.plant_id,
+                "changed_by" => &change.changed_by,
+                "action" => change.action.to_string(),
+                "changes" => change.changes.to_string(),
+            })
+            .ignore(&mut conn)
+            .await
+            .map_err(|e| anyhow!("insert_plant_change failed: {e}"))
+    }
+
     /// Selects all nurseries which match the given zipcode.
     /// Returns Err if it fails, Ok(empty vec) if none are found.
-    pub async fn select_nurseries_by_zip(&self, zip: &str) -> anyhow::Result<Vec<Nursery>> {
+    async fn select_nurseries_by_zip(&self, zip: &str) -> anyhow::Result<Vec<Nursery>> {
         let mut conn = self.get_connection().await?;
 
         r"
@@ -407,7 +703,7 @@ ORDER BY miles ASC"
 
     /// Selects a region's name for the given zipcode.
     /// Returns Err if it fails, Ok(None) if none are found.
-    pub async fn select_region_name_by_zip(&self, zip: &str) -> anyhow::Result<Option<String>> {
+    async fn select_region_name_by_zip(&self, zip: &str) -> anyhow::Result<Option<String>> {
         let mut conn = self.get_connection().await?;
 
         r"
@@ -422,7 +718,145 @@ WHERE z.zipcode = ?"
             .map_err(|e| anyhow!(e))
     }
 
-    pub async fn select_garden_by_id(
+    async fn select_regions(&self) -> anyhow::Result<Vec<Region>> {
+        let mut conn = self.get_connection().await?;
+
+        r"
+SELECT id, name, latitude, longitude, radius_miles
+FROM regions
+ORDER BY name"
+            .with(())
+            .map(&mut conn, |region: Region| region)
+            .await
+            .map_err(|e| anyhow!("select_regions failed: {e}"))
+    }
+
+    async fn select_region(&self, id: usize) -> anyhow::Result<Option<Region>> {
+        let mut conn = self.get_connection().await?;
+
+        r"
+SELECT id, name, latitude, longitude, radius_miles
+FROM regions
+WHERE id = ?"
+            .with((id,))
+            .first(&mut conn)
+            .await
+            .map_err(|e| anyhow!("select_region failed: {e}"))
+    }
+
+    async fn select_region_zipcodes(&self, id: usize) -> anyhow::Result<Vec<String>> {
+        let mut conn = self.get_connection().await?;
+
+        r"
+SELECT zipcode
+FROM zipcodes
+WHERE region_id = ?
+ORDER BY zipcode"
+            .with((id,))
+            // db has this as integer, so format to 5 chars w/ leading zeros
+            .map(&mut conn, |zipcode: usize| format!("{zipcode:05}"))
+            .await
+            .map_err(|e| anyhow!("select_region_zipcodes failed: {e}"))
+    }
+
+    async fn count_region_plants(&self, id: usize) -> anyhow::Result<RegionPlantCounts> {
+        let mut conn = self.get_connection().await?;
+
+        let counts: Option<(usize, usize, usize, usize, usize, usize, usize)> = r"
+SELECT
+  COUNT(*),
+  COUNT(CASE WHEN p.shades IS NULL OR FIND_IN_SET('None', p.shades) THEN 1 END),
+  COUNT(CASE WHEN p.shades IS NULL OR FIND_IN_SET('Some', p.shades) THEN 1 END),
+  COUNT(CASE WHEN p.shades IS NULL OR FIND_IN_SET('Lots', p.shades) THEN 1 END),
+  COUNT(CASE WHEN p.moistures IS NULL OR FIND_IN_SET('None', p.moistures) THEN 1 END),
+  COUNT(CASE WHEN p.moistures IS NULL OR FIND_IN_SET('Some', p.moistures) THEN 1 END),
+  COUNT(CASE WHEN p.moistures IS NULL OR FIND_IN_SET('Lots', p.moistures) THEN 1 END)
+FROM regions_plants rp
+INNER JOIN plants p ON p.id = rp.plant_id
+WHERE rp.region_id = ?"
+            .with((id,))
+            .first(&mut conn)
+            .await
+            .map_err(|e| anyhow!("count_region_plants failed: {e}"))?;
+
+        let (total, sun, part_shade, shade, low, medium, high) = counts.unwrap_or_default();
+        Ok(RegionPlantCounts::new(
+            total,
+            [sun, part_shade, shade],
+            [low, medium, high],
+        ))
+    }
+
+    async fn select_plants_by_region(
+        &self,
+        id: usize,
+        page: &PlantPage,
+    ) -> anyhow::Result<Vec<Plant>> {
+        let mut conn = self.get_connection().await?;
+
+        format!(
+            r"
+SELECT
+  p.id, p.scientific_name, p.common_name,
+  p.bloom, p.height, p.spread,
+  p.bloom_start_month, p.bloom_end_month, p.bloom_colors,
+  p.height_min_inches, p.height_max_inches, p.spread_min_inches, p.spread_max_inches,
+  p.moistures, p.shades,
+  p.pollinator_rating,
+  p.bird_rating,
+  p.spread_rating, p.deer_resistance_rating,
+  p.usda_symbol, p.usda_source, p.wiki_source,
+  i.id as image_id, i.title, i.card_url, i.original_url, i.author, i.license
+FROM plants p
+INNER JOIN regions_plants rp on rp.plant_id = p.id
+LEFT JOIN images i ON i.id = p.image_id
+WHERE rp.region_id = :region_id
+{}
+",
+            plant_page_clauses(page)
+        )
+        .with(params! { "region_id" => id })
+        .map(&mut conn, |plant: Plant| plant)
+        .await
+        .map_err(|e| anyhow!("select_plants_by_region failed: {e}"))
+    }
+
+    async fn select_zipcodes_near(
+        &self,
+        location: Coordinates,
+        degrees: f64,
+    ) -> anyhow::Result<Vec<(String, Coordinates)>> {
+        let mut conn = self.get_connection().await?;
+
+        r"
+SELECT zipcode, latitude, longitude
+FROM zipcodes
+WHERE region_id IS NOT NULL
+  AND latitude BETWEEN :min_latitude AND :max_latitude
+  AND longitude BETWEEN :min_longitude AND :max_longitude"
+            .with(params! {
+                "min_latitude" => location.latitude - degrees,
+                "max_latitude" => location.latitude + degrees,
+                "min_longitude" => location.longitude - degrees,
+                "max_longitude" => location.longitude + degrees,
+            })
+            .map(
+                &mut conn,
+                |(zipcode, latitude, longitude): (usize, f64, f64)| {
+                    (
+                        format!("{zipcode:05}"),
+                        Coordinates {
+                            latitude,
+                            longitude,
+                        },
+                    )
+                },
+            )
+            .await
+            .map_err(|e| anyhow!("select_zipcodes_near failed: {e}"))
+    }
+
+    async fn select_garden_by_id(
         &self,
         id: &str,
         read_only: bool,
@@ -431,9 +865,9 @@ WHERE z.zipcode = ?"
 
         let id_field_name = if read_only { "read_id" } else { "write_id" };
 
-        format!(
+        let garden: Option<Garden> = format!(
             "
-SELECT g.name, g.zipcode, r.name, shade, moisture, g.read_id, g.latitude, g.longitude
+SELECT g.name, g.zipcode, r.name, shade, moisture, g.read_id, g.latitude, g.longitude, r.id
 FROM gardens g
 INNER JOIN zipcodes z ON z.zipcode = g.zipcode
 INNER JOIN regions r ON r.id = z.region_id
@@ -442,22 +876,37 @@ WHERE {id_field_name} = ?"
         .with((id,))
         .first(&mut conn)
         .await
-        .map_err(|e| anyhow!(e))
+        .map_err(|e| anyhow!(e))?;
+
+        let Some(garden) = garden else {
+            return Ok(None);
+        };
+
+        let zones: Vec<GardenZone> = format!(
+            "
+SELECT gz.name, gz.shade, gz.moisture
+FROM garden_zones gz
+INNER JOIN gardens g ON g.id = gz.garden_id
+WHERE g.{id_field_name} = ?
+ORDER BY gz.zone"
+        )
+        .with((id,))
+        .map(&mut conn, |zone: GardenZone| zone)
+        .await
+        .map_err(|e| anyhow!("select garden zones failed: {e}"))?;
+
+        Ok(Some(garden.with_zones(zones)))
     }
 
-    pub async fn select_gardens(
-        &self,
-        require_precise_location: bool,
-    ) -> anyhow::Result<Vec<Garden>> {
+    async fn select_gardens(&self, require_precise_location: bool) -> anyhow::Result<Vec<Garden>> {
         let mut conn = self.get_connection().await?;
 
-        let mut query = format!(
-            "
-SELECT g.name, g.zipcode, r.name, shade, moisture, g.read_id, g.latitude, g.longitude
+        let mut query = "
+SELECT g.name, g.zipcode, r.name, shade, moisture, g.read_id, g.latitude, g.longitude, r.id
 FROM gardens g
 INNER JOIN zipcodes z ON z.zipcode = g.zipcode
 INNER JOIN regions r ON r.id = z.region_id"
-        );
+            .to_string();
 
         if require_precise_location {
             query.push_str("\nWHERE g.latitude IS NOT NULL and g.longitude IS NOT NULL");
@@ -469,11 +918,11 @@ INNER JOIN regions r ON r.id = z.region_id"
             .map_err(|e| anyhow!(e))
     }
 
-    pub async fn select_plants_by_garden_id(
+    async fn select_plants_by_garden_id(
         &self,
         garden_id: &str,
         read_only: bool,
-    ) -> anyhow::Result<Vec<Plant>> {
+    ) -> anyhow::Result<Vec<GardenPlant>> {
         let mut conn = self.get_connection().await?;
 
         let id_field_name = if read_only { "read_id" } else { "write_id" };
@@ -481,13 +930,17 @@ INNER JOIN regions r ON r.id = z.region_id"
         format!(
             r"
 SELECT
+  gp.quantity, gp.notes, DATE_FORMAT(gp.planted_date, '%Y-%m-%d') as planted_date, gp.status,
+  gp.zone,
   p.id, p.scientific_name, p.common_name,
   p.bloom, p.height, p.spread,
+  p.bloom_start_month, p.bloom_end_month, p.bloom_colors,
+  p.height_min_inches, p.height_max_inches, p.spread_min_inches, p.spread_max_inches,
   p.moistures, p.shades,
   p.pollinator_rating,
   p.bird_rating,
   p.spread_rating, p.deer_resistance_rating,
-  p.usda_source, p.wiki_source,
+  p.usda_symbol, p.usda_source, p.wiki_source,
   i.id as image_id, i.title, i.card_url, i.original_url, i.author, i.license
 FROM plants p
 INNER JOIN gardens_plants gp on gp.plant_id = p.id
@@ -500,13 +953,13 @@ ORDER BY gp.ordering
         .with(params! {
             "garden_id" => garden_id,
         })
-        .map(&mut conn, |plant: Plant| plant)
+        .map(&mut conn, |plant: GardenPlant| plant)
         .await
         .map_err(|e| anyhow!(e))
     }
 
     /// Inserts a Garden (but not the plants!), returning its id.
-    pub async fn insert_garden(
+    async fn insert_garden(
         &self,
         garden: &Garden,
         read_id: &str,
@@ -531,32 +984,83 @@ ORDER BY gp.ordering
     }
 
     /// Updates an existing Garden (but not the plants!).
-    pub async fn update_garden(&self, write_id: &str, name: &str) -> anyhow::Result<()> {
+    async fn update_garden(
+        &self,
+        write_id: &str,
+        name: &str,
+        conditions: Option<(Shade, Moisture)>,
+    ) -> anyhow::Result<()> {
         let mut conn = self.get_connection().await?;
 
         r"UPDATE gardens
-              SET name = :name
+              SET name = :name,
+                shade = COALESCE(:shade, shade),
+                moisture = COALESCE(:moisture, moisture)
               WHERE write_id = :write_id"
             .with(params! {
                 "write_id" => write_id,
-
-                "name" => name
-
+                "name" => name,
+                "shade" => conditions.map(|(shade, _)| shade.to_string()),
+                "moisture" => conditions.map(|(_, moisture)| moisture.to_string()),
             })
             .ignore(&mut conn)
             .await
             .map_err(|e| anyhow!("update_garden failed: {}", e))
     }
 
-    pub async fn replace_garden_plants(
+    async fn replace_garden_zones(
         &self,
         write_id: &str,
-        plant_ids: Vec<usize>,
+        zones: &[GardenZone],
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_connection().await?;
+        let mut transaction = conn
+            .start_transaction(mysql_async::TxOpts::default())
+            .await?;
+
+        "DELETE gz FROM garden_zones gz
+            INNER JOIN gardens g on g.id = gz.garden_id
+            WHERE write_id = :write_id"
+            .with(params! {
+                "write_id" => write_id
+            })
+            .ignore(&mut transaction)
+            .await
+            .map_err(|e| anyhow!("replace_garden_zones delete failed: {e}"))?;
+
+        "INSERT INTO garden_zones (garden_id, zone, name, shade, moisture)
+           VALUES (
+             (SELECT id from gardens where write_id = :write_id), :zone,
+             :name, :shade, :moisture
+           )"
+        .with(zones.iter().enumerate().map(|(index, zone)| {
+            params! {
+                "write_id" => write_id,
+                "zone" => index,
+                "name" => &zone.name,
+                "shade" => zone.shade.to_string(),
+                "moisture" => zone.moisture.to_string(),
+            }
+        }))
+        .batch(&mut transaction)
+        .await
+        .map_err(|e| anyhow!("replace_garden_zones insert failed: {e}"))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| anyhow!("replace_garden_zones commit failed: {e}"))
+    }
+
+    async fn replace_garden_plants(
+        &self,
+        write_id: &str,
+        plants: Vec<(usize, GardenPlantDetails)>,
     ) -> anyhow::Result<()> {
         // Remove duplicates - they cause issues w/ unique constraints
-        let mut plant_ids = plant_ids.clone();
-        let mut seen_plant_ids = HashSet::new();
-        plant_ids.retain(|p| seen_plant_ids.insert(*p));
+        let mut plants = plants;
+        let mut seen = HashSet::new();
+        plants.retain(|(id, details)| seen.insert((details.zone, *id)));
 
         let mut conn = self.get_connection().await?;
         let mut transaction = conn
@@ -573,18 +1077,27 @@ ORDER BY gp.ordering
             .await
             .map_err(|e| anyhow!("replace_garden_plants delete failed: {e}"))?;
 
-        "INSERT INTO gardens_plants (garden_id, plant_id, ordering)
-           VALUES ((SELECT id from gardens where write_id = :write_id), :plant_id, :ordering)"
-            .with(plant_ids.iter().enumerate().map(|(ordering, id)| {
-                params! {
-                    "write_id" => write_id,
-                    "plant_id" => id,
-                    "ordering" => ordering
-                }
-            }))
-            .batch(&mut transaction)
-            .await
-            .map_err(|e| anyhow!("replace_garden_plants insert failed: {e}"))?;
+        "INSERT INTO gardens_plants
+           (garden_id, plant_id, zone, ordering, quantity, notes, planted_date, status)
+           VALUES (
+             (SELECT id from gardens where write_id = :write_id), :plant_id, :zone, :ordering,
+             :quantity, :notes, :planted_date, :status
+           )"
+        .with(plants.iter().enumerate().map(|(ordering, (id, details))| {
+            params! {
+                "write_id" => write_id,
+                "plant_id" => id,
+                "zone" => details.zone,
+                "ordering" => ordering,
+                "quantity" => details.quantity,
+                "notes" => &details.notes,
+                "planted_date" => &details.planted_date,
+                "status" => details.status.to_string(),
+            }
+        }))
+        .batch(&mut transaction)
+        .await
+        .map_err(|e| anyhow!("replace_garden_plants insert failed: {e}"))?;
 
         transaction
             .commit()
@@ -592,13 +1105,19 @@ ORDER BY gp.ordering
             .map_err(|e| anyhow!("replace_garden_plants commit failed: {e}"))
     }
 
-    pub async fn find_plants_by_word_prefix(&self, expression: &str) -> anyhow::Result<Vec<Plant>> {
+    async fn find_plants_by_word_prefix(&self, word_prefix: &str) -> anyhow::Result<Vec<Plant>> {
         let mut conn = self.get_connection().await?;
 
+        let expression = build_word_prefix_expression(word_prefix);
+
         r"
  SELECT id, scientific_name, common_name 
  FROM plants 
  WHERE MATCH(scientific_name, common_name) AGAINST (:expression IN BOOLEAN MODE)
+    OR id IN (
+      SELECT plant_id FROM plant_synonyms
+      WHERE MATCH(name) AGAINST (:expression IN BOOLEAN MODE)
+    )
  LIMIT 10
 "
         .with(params! {
@@ -629,7 +1148,7 @@ ORDER BY gp.ordering
         }
     }
     */
-    pub async fn upsert_request_count(&self, uri: &str) -> anyhow::Result<usize> {
+    async fn upsert_request_count(&self, uri: &str) -> anyhow::Result<usize> {
         let mut conn = self.get_connection().await?;
         let count_result: Result<Option<usize>, mysql_async::Error> =
             r"INSERT INTO request_counts (uri, date, count) VALUES
@@ -648,7 +1167,7 @@ ORDER BY gp.ordering
         }
     }
 
-    pub async fn select_monthly_request_count(&self, uri: &str) -> anyhow::Result<usize> {
+    async fn select_monthly_request_count(&self, uri: &str) -> anyhow::Result<usize> {
         let mut conn = self.get_connection().await?;
         let count_result: Result<Option<usize>, mysql_async::Error> =
             r"SELECT SUM(count) FROM request_counts
@@ -666,6 +1185,81 @@ ORDER BY gp.ordering
             Err(e) => Err(anyhow!("select monthly request count failed: {}", e)),
         }
     }
+    fn changesets(&self) -> Vec<Changeset> {
+        mysql_changesets()
+    }
+
+    async fn select_applied_changesets(&self) -> anyhow::Result<HashSet<(String, String)>> {
+        let mut conn = self.get_connection().await?;
+
+        r"CREATE TABLE IF NOT EXISTS schema_changesets (
+            file VARCHAR(255),
+            changeset VARCHAR(255),
+            applied_at DATETIME,
+
+            PRIMARY KEY (file, changeset)
+          )"
+        .ignore(&mut conn)
+        .await
+        .map_err(|e| anyhow!("create schema_changesets failed: {e}"))?;
+
+        // Databases set up by liquibase already have these applied, copy
+        // them over so they aren't applied twice.  Fails harmlessly when
+        // liquibase was never used.
+        let imported = r"INSERT IGNORE INTO schema_changesets (file, changeset, applied_at)
+            SELECT FILENAME, CONCAT(AUTHOR, ':', ID), DATEEXECUTED FROM DATABASECHANGELOG"
+            .ignore(&mut conn)
+            .await;
+        if let Err(e) = imported {
+            info!("not importing liquibase changesets: {e}");
+        }
+
+        "SELECT file, changeset FROM schema_changesets"
+            .fetch(&mut conn)
+            .await
+            .map(|applied: Vec<(String, String)>| applied.into_iter().collect())
+            .map_err(|e| anyhow!("select from schema_changesets failed: {e}"))
+    }
+
+    async fn apply_changeset(&self, changeset: &Changeset) -> anyhow::Result<()> {
+        let mut conn = self.get_connection().await?;
+
+        // MySQL commits DDL implicitly, so this can't be one transaction.
+        conn.query_drop(&changeset.sql)
+            .await
+            .map_err(|e| anyhow!("apply_changeset {changeset} failed: {e}"))?;
+
+        r"INSERT INTO schema_changesets (file, changeset, applied_at)
+            VALUES (:file, :changeset, NOW())"
+            .with(params! {
+                "file" => changeset.file,
+                "changeset" => &changeset.id,
+            })
+            .ignore(&mut conn)
+            .await
+            .map_err(|e| anyhow!("apply_changeset {changeset} failed to record: {e}"))
+    }
+}
+
+/// The params for FROM_PLANTS_BY_ZIP_MOISTURE_SHADE and PLANT_FILTER_CONDITIONS
+fn zip_moisture_shade_params(
+    zip: &str,
+    moistures: &[Moisture],
+    shades: &[Shade],
+    filters: &PlantFilters,
+) -> Params {
+    params! {
+        "zipcode" => zip,
+        "moistures" => to_comma_separated_string(moistures),
+        "shades" => to_comma_separated_string(shades),
+        "min_height" => filters.min_height_inches,
+        "max_height" => filters.max_height_inches,
+        "min_pollinator_rating" => filters.min_pollinator_rating,
+        "min_bird_rating" => filters.min_bird_rating,
+        "min_deer_resistance_rating" => filters.min_deer_resistance_rating,
+        "bloom_start" => filters.bloom.map(|b| b.start_month),
+        "bloom_end" => filters.bloom.map(|b| b.end_month),
+    }
 }
 
 fn to_comma_separated_string<T: Display>(vec: &[T]) -> Option<String> {
@@ -682,3 +1276,29 @@ fn to_comma_separated_string<T: Display>(vec: &[T]) -> Option<String> {
             .join(","),
     )
 }
+
+fn build_word_prefix_expression(word_prefix: &str) -> String {
+    let mut expression = String::new();
+
+    for word in word_prefix.split_whitespace() {
+        if !word.is_empty() {
+            expression.push_str(&format!("+{}* ", word));
+        }
+    }
+
+    expression.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_word_prefix_expression() {
+        assert_eq!(build_word_prefix_expression("foo bar"), "+foo* +bar*");
+        assert_eq!(build_word_prefix_expression("foo      bar"), "+foo* +bar*");
+        assert_eq!(build_word_prefix_expression("  foo   bar "), "+foo* +bar*");
+        assert_eq!(build_word_prefix_expression("  foo    "), "+foo*");
+        assert_eq!(build_word_prefix_expression("  foobar    "), "+foobar*");
+    }
+}
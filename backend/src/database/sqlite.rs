@@ -0,0 +1,2076 @@
+use crate::domain::*;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use rusqlite::{named_params, params_from_iter, Connection, OptionalExtension, Row, ToSql};
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    str::FromStr,
+    sync::{Mutex, MutexGuard},
+};
+use tracing::log::warn;
+
+use super::{
+    migrations::{sqlite_changesets, Changeset},
+    storage::{plant_page_clauses, Storage, PLANT_FILTER_CONDITIONS},
+};
+
+// Same columns as the MySQL plant queries, shared by every plant select.
+const SELECT_PLANT_COLUMNS: &str = r"
+SELECT
+  p.id, p.scientific_name, p.common_name,
+  p.bloom, p.height, p.spread,
+  p.bloom_start_month, p.bloom_end_month, p.bloom_colors,
+  p.height_min_inches, p.height_max_inches, p.spread_min_inches, p.spread_max_inches,
+  p.moistures, p.shades,
+  p.pollinator_rating,
+  p.bird_rating,
+  p.spread_rating, p.deer_resistance_rating,
+  p.usda_symbol, p.usda_source, p.wiki_source,
+  i.id as image_id, i.title, i.card_url, i.original_url, i.author, i.license
+FROM plants p";
+
+// The plants in a zip's region which grow in any of the comma separated
+// :moistures and any of the :shades, following a "FROM plants p".  SQLite has
+// no FIND_IN_SET, so that is spelled out.
+const PLANTS_BY_ZIP_MOISTURE_SHADE: &str = r"
+INNER JOIN regions_plants rp on rp.plant_id = p.id
+INNER JOIN zipcodes z ON z.region_id = rp.region_id
+LEFT JOIN images i ON i.id = p.image_id
+WHERE z.zipcode = :zipcode
+  AND (p.moistures is NULL
+    OR (',' || :moistures || ',' LIKE '%,None,%' AND ',' || p.moistures || ',' LIKE '%,None,%')
+    OR (',' || :moistures || ',' LIKE '%,Some,%' AND ',' || p.moistures || ',' LIKE '%,Some,%')
+    OR (',' || :moistures || ',' LIKE '%,Lots,%' AND ',' || p.moistures || ',' LIKE '%,Lots,%'))
+  AND (p.shades is NULL
+    OR (',' || :shades || ',' LIKE '%,None,%' AND ',' || p.shades || ',' LIKE '%,None,%')
+    OR (',' || :shades || ',' LIKE '%,Some,%' AND ',' || p.shades || ',' LIKE '%,Some,%')
+    OR (',' || :shades || ',' LIKE '%,Lots,%' AND ',' || p.shades || ',' LIKE '%,Lots,%'))";
+
+const SELECT_GARDEN_COLUMNS: &str = r"
+SELECT g.name, g.zipcode, r.name, shade, moisture, g.read_id, g.latitude, g.longitude, r.id
+FROM gardens g
+INNER JOIN zipcodes z ON z.zipcode = g.zipcode
+INNER JOIN regions r ON r.id = z.region_id";
+
+/// Runs queries against a SQLite database file, so the app can run without
+/// a MySQL server.  Selected with a url like "sqlite://planting_life.db" or
+/// "sqlite::memory:".
+///
+/// SQLite calls are local and quick, so they run inline rather than on a
+/// separate thread.
+pub struct SqliteRunner {
+    conn: Option<Mutex<Connection>>,
+}
+
+impl SqliteRunner {
+    pub fn is_sqlite_url(url: &str) -> bool {
+        url.starts_with("sqlite:")
+    }
+
+    pub fn new(url: &str) -> Self {
+        let path = url.trim_start_matches("sqlite:").trim_start_matches("//");
+
+        let conn = if path == ":memory:" {
+            Connection::open_in_memory()
+        } else {
+            Connection::open(path)
+        };
+
+        match conn {
+            Ok(conn) => Self {
+                conn: Some(Mutex::new(conn)),
+            },
+            Err(e) => {
+                warn!("Starting server without database!  can't open sqlite db: {e}");
+                Self { conn: None }
+            }
+        }
+    }
+
+    fn get_connection(&self) -> anyhow::Result<MutexGuard<'_, Connection>> {
+        if let Some(conn) = &self.conn {
+            conn.lock()
+                .map_err(|e| anyhow!("can't lock sqlite connection: {e}"))
+        } else {
+            warn!("tried to get db connection, but db is not connected");
+            Err(anyhow!("db is not connected"))
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for SqliteRunner {
+    async fn upsert_query(
+        &self,
+        zip: &str,
+        moisture: &Moisture,
+        shade: &Shade,
+    ) -> anyhow::Result<()> {
+        let conn = self.get_connection()?;
+
+        conn.execute(
+            r"INSERT INTO queries (moisture, shade, region_id, count) VALUES
+            (:moisture, :shade, (SELECT region_id from zipcodes where zipcode = :zip), 1)
+            ON CONFLICT (region_id, shade, moisture) DO UPDATE SET count = count + 1",
+            named_params! {
+                ":moisture": moisture.to_string(),
+                ":shade": shade.to_string(),
+                ":zip": zip,
+            },
+        )
+        .map(|_| ())
+        .map_err(|e| anyhow!("insert into queries failed: {}", e))
+    }
+
+    async fn check_zip_exists(&self, zip: &str) -> anyhow::Result<bool> {
+        let conn = self.get_connection()?;
+
+        conn.query_row(
+            "SELECT 1 from zipcodes where zipcode = :zip",
+            named_params! { ":zip": zip },
+            |_| Ok(()),
+        )
+        .optional()
+        .map(|found| found.is_some())
+        .map_err(|e| anyhow!("select from zipcodes failed: {e}"))
+    }
+
+    async fn select_zip_prefix_centroid(
+        &self,
+        prefix: &str,
+    ) -> anyhow::Result<Option<Coordinates>> {
+        let conn = self.get_connection()?;
+        let prefix: i64 = prefix.parse()?;
+
+        conn.query_row(
+            "SELECT latitude, longitude FROM zipcode_prefixes WHERE prefix = :prefix",
+            named_params! { ":prefix": prefix },
+            |row| {
+                Ok(Coordinates {
+                    latitude: row.get(0)?,
+                    longitude: row.get(1)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| anyhow!("select_zip_prefix_centroid failed: {e}"))
+    }
+
+    async fn select_query_count(
+        &self,
+        zip: &str,
+        moisture: &Moisture,
+        shade: &Shade,
+    ) -> anyhow::Result<usize> {
+        let conn = self.get_connection()?;
+
+        conn.query_row(
+            r"
+SELECT count
+FROM queries
+WHERE moisture = :moisture
+AND shade = :shade
+AND region_id = (SELECT region_id from zipcodes where zipcode = :zip)",
+            named_params! {
+                ":moisture": moisture.to_string(),
+                ":shade": shade.to_string(),
+                ":zip": zip,
+            },
+            |row| row.get(0),
+        )
+        .optional()
+        .map(|count| count.unwrap_or(0)) // Not found, count as 0
+        .map_err(|e| anyhow!("select_query_count failed: {e}"))
+    }
+
+    async fn insert_region_plants(
+        &self,
+        zip: &str,
+        plant_ids: HashSet<usize>,
+        provenance: &NativeProvenance,
+    ) -> anyhow::Result<()> {
+        let conn = self.get_connection()?;
+
+        // Some rows could already exist.  Generated plants shouldn't replace
+        // a better source, so those keep what they had.
+        let mut statement = conn.prepare(
+            r"INSERT INTO regions_plants (region_id, plant_id, source, confidence, added_at)
+            VALUES (
+              (SELECT region_id from zipcodes where zipcode = :zip), :plant_id,
+              :source, :confidence, CURRENT_TIMESTAMP
+            )
+            ON CONFLICT (region_id, plant_id) DO UPDATE
+              SET source = excluded.source, confidence = excluded.confidence
+              WHERE excluded.source <> 'Generated'",
+        )?;
+
+        for plant_id in plant_ids {
+            statement.execute(named_params! {
+                ":zip": zip,
+                ":plant_id": plant_id,
+                ":source": provenance.source.to_string(),
+                ":confidence": provenance.confidence.to_string(),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    async fn update_plant(&self, plant: &Plant, img_id: Option<usize>) -> anyhow::Result<()> {
+        let conn = self.get_connection()?;
+
+        conn.execute(
+            r"UPDATE plants
+              SET scientific_name = :scientific_name,
+                  common_name = :common_name,
+
+                  pollinator_rating = :pollinator_rating,
+                  bird_rating = :bird_rating,
+
+                  spread_rating = :spread_rating,
+                  deer_resistance_rating = :deer_resistance_rating,
+
+                  usda_symbol = :usda_symbol,
+                  usda_source = :usda_source,
+                  wiki_source = :wiki_source,
+
+                  bloom = :bloom,
+                  height = :height,
+                  spread = :spread,
+
+                  bloom_start_month = :bloom_start_month,
+                  bloom_end_month = :bloom_end_month,
+                  bloom_colors = :bloom_colors,
+                  height_min_inches = :height_min_inches,
+                  height_max_inches = :height_max_inches,
+                  spread_min_inches = :spread_min_inches,
+                  spread_max_inches = :spread_max_inches,
+
+                  moistures = :moistures,
+                  shades = :shades,
+
+                  image_id = :image_id
+              WHERE id = :id",
+            named_params! {
+                ":id": plant.id,
+
+                ":scientific_name": &plant.scientific,
+                ":common_name": &plant.common,
+
+                ":pollinator_rating": plant.pollinator_rating,
+                ":bird_rating": plant.bird_rating,
+
+                ":spread_rating": plant.spread_rating,
+                ":deer_resistance_rating": plant.deer_resistance_rating,
+
+                ":usda_symbol": &plant.usda_symbol,
+                ":usda_source": &plant.usda_source,
+                ":wiki_source": &plant.wiki_source,
+
+                ":bloom": &plant.bloom,
+                ":height": &plant.height,
+                ":spread": &plant.spread,
+
+                ":bloom_start_month": plant.bloom_period.map(|b| b.start_month),
+                ":bloom_end_month": plant.bloom_period.map(|b| b.end_month),
+                ":bloom_colors": to_comma_separated_string(&plant.bloom_colors),
+                ":height_min_inches": plant.height_range.map(|r| r.min_inches),
+                ":height_max_inches": plant.height_range.map(|r| r.max_inches),
+                ":spread_min_inches": plant.spread_range.map(|r| r.min_inches),
+                ":spread_max_inches": plant.spread_range.map(|r| r.max_inches),
+
+                ":moistures": to_comma_separated_string(&plant.moistures),
+                ":shades": to_comma_separated_string(&plant.shades),
+
+                ":image_id": img_id,
+            },
+        )
+        .map(|_| ())
+        .map_err(|e| anyhow!("update_plant failed to update: {}", e))
+    }
+
+    async fn insert_plant(&self, plant: &Plant, img_id: Option<usize>) -> anyhow::Result<usize> {
+        let conn = self.get_connection()?;
+
+        conn.execute(
+            r"INSERT INTO plants
+        (scientific_name, common_name,
+         bloom, height, spread,
+         bloom_start_month, bloom_end_month, bloom_colors,
+         height_min_inches, height_max_inches, spread_min_inches, spread_max_inches,
+         moistures, shades,
+         pollinator_rating,
+         bird_rating,
+         spread_rating, deer_resistance_rating,
+         usda_symbol, usda_source, wiki_source,
+         image_id)
+      VALUES
+        (:scientific_name, :common_name,
+         :bloom, :height, :spread,
+         :bloom_start_month, :bloom_end_month, :bloom_colors,
+         :height_min_inches, :height_max_inches, :spread_min_inches, :spread_max_inches,
+         :moistures, :shades,
+         :pollinator_rating,
+         :bird_rating,
+         :spread_rating, :deer_resistance_rating,
+         :usda_symbol, :usda_source, :wiki_source,
+         :image_id)",
+            named_params! {
+                ":scientific_name": &plant.scientific,
+                ":common_name": &plant.common,
+
+                ":bloom": &plant.bloom,
+                ":height": &plant.height,
+                ":spread": &plant.spread,
+
+                ":bloom_start_month": plant.bloom_period.map(|b| b.start_month),
+                ":bloom_end_month": plant.bloom_period.map(|b| b.end_month),
+                ":bloom_colors": to_comma_separated_string(&plant.bloom_colors),
+                ":height_min_inches": plant.height_range.map(|r| r.min_inches),
+                ":height_max_inches": plant.height_range.map(|r| r.max_inches),
+                ":spread_min_inches": plant.spread_range.map(|r| r.min_inches),
+                ":spread_max_inches": plant.spread_range.map(|r| r.max_inches),
+
+                ":pollinator_rating": plant.pollinator_rating,
+                ":bird_rating": plant.bird_rating,
+
+                ":spread_rating": plant.spread_rating,
+                ":deer_resistance_rating": plant.deer_resistance_rating,
+
+                ":usda_symbol": &plant.usda_symbol,
+                ":usda_source": &plant.usda_source,
+                ":wiki_source": &plant.wiki_source,
+
+                ":moistures": to_comma_separated_string(&plant.moistures),
+                ":shades": to_comma_separated_string(&plant.shades),
+
+                ":image_id": img_id,
+            },
+        )
+        .map(|_| conn.last_insert_rowid() as usize)
+        .map_err(|e| anyhow!("save_plant failed to insert: {}", e))
+    }
+
+    async fn select_plants_by_zip_moisture_shade(
+        &self,
+        zip: &str,
+        moistures: &[Moisture],
+        shades: &[Shade],
+        filters: &PlantFilters,
+        page: &PlantPage,
+    ) -> anyhow::Result<Vec<Plant>> {
+        let conn = self.get_connection()?;
+
+        let query = format!(
+            r"{SELECT_PLANT_COLUMNS}
+{PLANTS_BY_ZIP_MOISTURE_SHADE}
+  {PLANT_FILTER_CONDITIONS}
+{}
+",
+            plant_page_clauses(page)
+        );
+
+        let params = zip_moisture_shade_params(zip, moistures, shades, filters);
+        let mut statement = conn.prepare(&query)?;
+        let plants = statement
+            .query_map(named_params_slice(&params).as_slice(), plant_from_row)?
+            .collect::<Result<Vec<Plant>, _>>()?;
+
+        Ok(plants)
+    }
+
+    async fn count_plants_by_zip_moisture_shade(
+        &self,
+        zip: &str,
+        moistures: &[Moisture],
+        shades: &[Shade],
+        filters: &PlantFilters,
+    ) -> anyhow::Result<usize> {
+        let conn = self.get_connection()?;
+
+        let query = format!(
+            r"SELECT COUNT(*) FROM plants p
+{PLANTS_BY_ZIP_MOISTURE_SHADE}
+  {PLANT_FILTER_CONDITIONS}
+"
+        );
+
+        let params = zip_moisture_shade_params(zip, moistures, shades, filters);
+        conn.query_row(&query, named_params_slice(&params).as_slice(), |row| {
+            row.get(0)
+        })
+        .map_err(|e| anyhow!("count_plants_by_zip_moisture_shade failed: {e}"))
+    }
+
+    async fn select_plant_by_scientific_name(
+        &self,
+        scientific_name: &str,
+    ) -> anyhow::Result<Option<Plant>> {
+        let conn = self.get_connection()?;
+
+        conn.query_row(
+            &format!(
+                r"{SELECT_PLANT_COLUMNS}
+LEFT JOIN images i ON i.id = p.image_id
+WHERE scientific_name = :scientific_name
+   OR p.id IN (
+     SELECT plant_id FROM plant_synonyms
+     WHERE kind = 'Scientific' AND name = :scientific_name
+   )
+ORDER BY scientific_name = :scientific_name DESC
+LIMIT 1"
+            ),
+            named_params! { ":scientific_name": scientific_name },
+            plant_from_row,
+        )
+        .optional()
+        .map_err(|e| anyhow!(e))
+    }
+
+    async fn select_plant_by_id(&self, id: usize) -> anyhow::Result<Option<Plant>> {
+        let conn = self.get_connection()?;
+
+        conn.query_row(
+            &format!(
+                r"{SELECT_PLANT_COLUMNS}
+LEFT JOIN images i ON i.id = p.image_id
+WHERE p.id = :id"
+            ),
+            named_params! { ":id": id },
+            plant_from_row,
+        )
+        .optional()
+        .map_err(|e| anyhow!(e))
+    }
+
+    async fn select_plants_after_id(
+        &self,
+        after_id: usize,
+        limit: usize,
+    ) -> anyhow::Result<Vec<Plant>> {
+        let conn = self.get_connection()?;
+
+        let mut statement = conn.prepare(&format!(
+            r"{SELECT_PLANT_COLUMNS}
+LEFT JOIN images i ON i.id = p.image_id
+WHERE p.id > :after_id
+ORDER BY p.id
+LIMIT :limit"
+        ))?;
+
+        let plants = statement
+            .query_map(
+                named_params! {
+                    ":after_id": after_id,
+                    ":limit": limit,
+                },
+                plant_from_row,
+            )?
+            .collect::<Result<Vec<Plant>, _>>()?;
+
+        Ok(plants)
+    }
+
+    async fn select_plants_missing_details_after_id(
+        &self,
+        after_id: usize,
+        limit: usize,
+    ) -> anyhow::Result<Vec<Plant>> {
+        let conn = self.get_connection()?;
+
+        let mut statement = conn.prepare(&format!(
+            r"{SELECT_PLANT_COLUMNS}
+LEFT JOIN images i ON i.id = p.image_id
+WHERE p.id > :after_id
+  AND ((p.bloom IS NOT NULL AND p.bloom_start_month IS NULL)
+    OR (p.height IS NOT NULL AND p.height_min_inches IS NULL)
+    OR (p.spread IS NOT NULL AND p.spread_min_inches IS NULL)
+    OR p.usda_symbol IS NULL)
+ORDER BY p.id
+LIMIT :limit"
+        ))?;
+
+        let plants = statement
+            .query_map(
+                named_params! {
+                    ":after_id": after_id,
+                    ":limit": limit,
+                },
+                plant_from_row,
+            )?
+            .collect::<Result<Vec<Plant>, _>>()?;
+
+        Ok(plants)
+    }
+
+    async fn insert_plant_synonyms(
+        &self,
+        plant_id: usize,
+        synonyms: &[PlantSynonym],
+    ) -> anyhow::Result<()> {
+        let conn = self.get_connection()?;
+
+        let mut statement = conn.prepare(
+            r"INSERT OR IGNORE INTO plant_synonyms (plant_id, name, kind)
+            VALUES (:plant_id, :name, :kind)",
+        )?;
+
+        for synonym in synonyms {
+            statement
+                .execute(named_params! {
+                    ":plant_id": plant_id,
+                    ":name": synonym.name,
+                    ":kind": synonym.kind.to_string(),
+                })
+                .map_err(|e| anyhow!("insert_plant_synonyms failed: {e}"))?;
+        }
+
+        Ok(())
+    }
+
+    async fn select_plant_synonyms(
+        &self,
+        plant_ids: Vec<usize>,
+    ) -> anyhow::Result<Vec<(usize, PlantSynonym)>> {
+        if plant_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let conn = self.get_connection()?;
+
+        let placeholders = vec!["?"; plant_ids.len()].join(", ");
+        let mut statement = conn.prepare(&format!(
+            r"
+SELECT plant_id, name, kind
+FROM plant_synonyms
+WHERE plant_id IN ({placeholders})
+ORDER BY plant_id, kind, name"
+        ))?;
+
+        let synonyms = statement
+            .query_map(params_from_iter(plant_ids.iter()), |row| {
+                let kind: String = row.get(2)?;
+                Ok((
+                    row.get(0)?,
+                    PlantSynonym {
+                        name: row.get(1)?,
+                        kind: SynonymKind::from_str(&kind)
+                            .expect("plant_synonyms.kind should have valid values"),
+                    },
+                ))
+            })?
+            .collect::<Result<Vec<(usize, PlantSynonym)>, _>>()
+            .map_err(|e| anyhow!("select_plant_synonyms failed: {e}"))?;
+
+        Ok(synonyms)
+    }
+
+    async fn select_plant_regions(
+        &self,
+        plant_ids: Vec<usize>,
+    ) -> anyhow::Result<Vec<(usize, PlantRegion)>> {
+        if plant_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let conn = self.get_connection()?;
+
+        let placeholders = vec!["?"; plant_ids.len()].join(", ");
+        let mut statement = conn.prepare(&format!(
+            r"
+SELECT
+  rp.plant_id, r.id, r.name, MIN(z.zipcode),
+  rp.source, rp.confidence, date(rp.added_at)
+FROM regions_plants rp
+INNER JOIN regions r ON r.id = rp.region_id
+INNER JOIN zipcodes z ON z.region_id = r.id
+WHERE rp.plant_id IN ({placeholders})
+GROUP BY rp.plant_id, r.id, r.name, rp.source, rp.confidence, rp.added_at
+ORDER BY rp.plant_id, r.name"
+        ))?;
+
+        let regions = statement
+            .query_map(params_from_iter(plant_ids.iter()), |row| {
+                let zipcode: usize = row.get(3)?;
+                let source: String = row.get(4)?;
+                let confidence: String = row.get(5)?;
+                Ok((
+                    row.get(0)?,
+                    PlantRegion {
+                        region_id: row.get(1)?,
+                        name: row.get(2)?,
+                        zipcode: format!("{zipcode:05}"),
+                        provenance: NativeProvenance {
+                            source: NativeSource::from_str(&source)
+                                .expect("regions_plants.source should have valid values"),
+                            confidence: NativeConfidence::from_str(&confidence)
+                                .expect("regions_plants.confidence should have valid values"),
+                            date_added: row.get(6)?,
+                        },
+                    },
+                ))
+            })?
+            .collect::<Result<Vec<(usize, PlantRegion)>, _>>()
+            .map_err(|e| anyhow!("select_plant_regions failed: {e}"))?;
+
+        Ok(regions)
+    }
+
+    async fn insert_image(&self, image: &Image) -> anyhow::Result<usize> {
+        let conn = self.get_connection()?;
+
+        conn.execute(
+            r"INSERT INTO images (title, card_url, original_url, author, license)
+            VALUES (:title, :card_url, :original_url, :author, :license)",
+            named_params! {
+                ":title": &image.title,
+                ":card_url": &image.card_url,
+                ":original_url": &image.original_url,
+                ":author": &image.author,
+                ":license": &image.license,
+            },
+        )
+        .map(|_| conn.last_insert_rowid() as usize)
+        .map_err(|e| anyhow!("save_image failed to insert: {}", e))
+    }
+
+    async fn insert_plant_change(&self, change: &PlantChange) -> anyhow::Result<()> {
+        let conn = self.get_connection()?;
+
+        conn.execute(
+            r"INSERT INTO plant_changes (plant_id, changed_by, action, changes)
+            VALUES (:plant_id, :changed_by, :action, :changes)",
+            named_params! {
+                ":plant_id": change.plant_id,
+                ":changed_by": &change.changed_by,
+                ":action": change.action.to_string(),
+                ":changes": change.changes.to_string(),
+            },
+        )
+        .map(|_| ())
+        .map_err(|e| anyhow!("insert_plant_change failed: {e}"))
+    }
+
+    async fn select_nurseries_by_zip(&self, zip: &str) -> anyhow::Result<Vec<Nursery>> {
+        let conn = self.get_connection()?;
+
+        let mut statement = conn.prepare(
+            r"
+SELECT miles, name, url, address, city, state, n.zipcode
+FROM zipcodes_nurseries zn
+INNER JOIN nurseries n
+  ON n.id = zn.nursery_id
+WHERE zn.zipcode = ?
+ORDER BY miles ASC",
+        )?;
+
+        let nurseries = statement
+            .query_map([zip], |row| {
+                Ok(Nursery {
+                    miles: row.get(0)?,
+                    name: row.get(1)?,
+                    url: row.get(2)?,
+                    address: row.get(3)?,
+                    city: row.get(4)?,
+                    state: row.get(5)?,
+                    zip: row.get(6)?,
+                    map_url: None,
+                })
+            })?
+            .collect::<Result<Vec<Nursery>, _>>()?;
+
+        Ok(nurseries)
+    }
+
+    async fn select_region_name_by_zip(&self, zip: &str) -> anyhow::Result<Option<String>> {
+        let conn = self.get_connection()?;
+
+        conn.query_row(
+            r"
+SELECT name
+FROM regions r
+INNER JOIN zipcodes z
+  ON z.region_id = r.id
+WHERE z.zipcode = ?",
+            [zip],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| anyhow!(e))
+    }
+
+    async fn select_regions(&self) -> anyhow::Result<Vec<Region>> {
+        let conn = self.get_connection()?;
+
+        let mut statement = conn.prepare(
+            r"
+SELECT id, name, latitude, longitude, radius_miles
+FROM regions
+ORDER BY name",
+        )?;
+        let regions = statement
+            .query_map([], region_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(regions)
+    }
+
+    async fn select_region(&self, id: usize) -> anyhow::Result<Option<Region>> {
+        let conn = self.get_connection()?;
+
+        conn.query_row(
+            r"
+SELECT id, name, latitude, longitude, radius_miles
+FROM regions
+WHERE id = :id",
+            named_params! { ":id": id },
+            region_from_row,
+        )
+        .optional()
+        .map_err(|e| anyhow!("select_region failed: {e}"))
+    }
+
+    async fn select_region_zipcodes(&self, id: usize) -> anyhow::Result<Vec<String>> {
+        let conn = self.get_connection()?;
+
+        let mut statement = conn.prepare(
+            r"
+SELECT zipcode
+FROM zipcodes
+WHERE region_id = :id
+ORDER BY zipcode",
+        )?;
+        let zipcodes = statement
+            .query_map(named_params! { ":id": id }, |row| {
+                let zipcode: usize = row.get(0)?;
+                Ok(format!("{zipcode:05}"))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(zipcodes)
+    }
+
+    async fn count_region_plants(&self, id: usize) -> anyhow::Result<RegionPlantCounts> {
+        let conn = self.get_connection()?;
+
+        conn.query_row(
+            r"
+SELECT
+  COUNT(*),
+  COUNT(CASE WHEN p.shades IS NULL OR ',' || p.shades || ',' LIKE '%,None,%' THEN 1 END),
+  COUNT(CASE WHEN p.shades IS NULL OR ',' || p.shades || ',' LIKE '%,Some,%' THEN 1 END),
+  COUNT(CASE WHEN p.shades IS NULL OR ',' || p.shades || ',' LIKE '%,Lots,%' THEN 1 END),
+  COUNT(CASE WHEN p.moistures IS NULL OR ',' || p.moistures || ',' LIKE '%,None,%' THEN 1 END),
+  COUNT(CASE WHEN p.moistures IS NULL OR ',' || p.moistures || ',' LIKE '%,Some,%' THEN 1 END),
+  COUNT(CASE WHEN p.moistures IS NULL OR ',' || p.moistures || ',' LIKE '%,Lots,%' THEN 1 END)
+FROM regions_plants rp
+INNER JOIN plants p ON p.id = rp.plant_id
+WHERE rp.region_id = :id",
+            named_params! { ":id": id },
+            |row| {
+                Ok(RegionPlantCounts::new(
+                    row.get(0)?,
+                    [row.get(1)?, row.get(2)?, row.get(3)?],
+                    [row.get(4)?, row.get(5)?, row.get(6)?],
+                ))
+            },
+        )
+        .map_err(|e| anyhow!("count_region_plants failed: {e}"))
+    }
+
+    async fn select_plants_by_region(
+        &self,
+        id: usize,
+        page: &PlantPage,
+    ) -> anyhow::Result<Vec<Plant>> {
+        let conn = self.get_connection()?;
+
+        let query = format!(
+            r"{SELECT_PLANT_COLUMNS}
+INNER JOIN regions_plants rp on rp.plant_id = p.id
+LEFT JOIN images i ON i.id = p.image_id
+WHERE rp.region_id = :region_id
+{}
+",
+            plant_page_clauses(page)
+        );
+
+        let mut statement = conn.prepare(&query)?;
+        let plants = statement
+            .query_map(named_params! { ":region_id": id }, plant_from_row)?
+            .collect::<Result<Vec<Plant>, _>>()?;
+
+        Ok(plants)
+    }
+
+    async fn select_zipcodes_near(
+        &self,
+        location: Coordinates,
+        degrees: f64,
+    ) -> anyhow::Result<Vec<(String, Coordinates)>> {
+        let conn = self.get_connection()?;
+
+        let mut statement = conn.prepare(
+            r"
+SELECT zipcode, latitude, longitude
+FROM zipcodes
+WHERE region_id IS NOT NULL
+  AND latitude BETWEEN :min_latitude AND :max_latitude
+  AND longitude BETWEEN :min_longitude AND :max_longitude",
+        )?;
+
+        let zipcodes = statement
+            .query_map(
+                named_params! {
+                    ":min_latitude": location.latitude - degrees,
+                    ":max_latitude": location.latitude + degrees,
+                    ":min_longitude": location.longitude - degrees,
+                    ":max_longitude": location.longitude + degrees,
+                },
+                |row| {
+                    let zipcode: usize = row.get(0)?;
+                    Ok((
+                        format!("{zipcode:05}"),
+                        Coordinates {
+                            latitude: row.get(1)?,
+                            longitude: row.get(2)?,
+                        },
+                    ))
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(zipcodes)
+    }
+
+    async fn select_garden_by_id(
+        &self,
+        id: &str,
+        read_only: bool,
+    ) -> anyhow::Result<Option<Garden>> {
+        let conn = self.get_connection()?;
+
+        let id_field_name = if read_only { "read_id" } else { "write_id" };
+
+        let Some(garden) = conn
+            .query_row(
+                &format!("{SELECT_GARDEN_COLUMNS}\nWHERE {id_field_name} = ?"),
+                [id],
+                garden_from_row,
+            )
+            .optional()?
+        else {
+            return Ok(None);
+        };
+
+        let mut statement = conn.prepare(&format!(
+            r"
+SELECT gz.name, gz.shade, gz.moisture
+FROM garden_zones gz
+INNER JOIN gardens g ON g.id = gz.garden_id
+WHERE g.{id_field_name} = ?
+ORDER BY gz.zone"
+        ))?;
+
+        let zones = statement
+            .query_map([id], |row| {
+                let shade: String = row.get(1)?;
+                let moisture: String = row.get(2)?;
+                Ok(GardenZone {
+                    name: row.get(0)?,
+                    shade: Shade::from_str(&shade)
+                        .expect("garden_zones.shade should have valid values"),
+                    moisture: Moisture::from_str(&moisture)
+                        .expect("garden_zones.moisture should have valid values"),
+                })
+            })?
+            .collect::<Result<Vec<GardenZone>, _>>()?;
+
+        Ok(Some(garden.with_zones(zones)))
+    }
+
+    async fn select_gardens(&self, require_precise_location: bool) -> anyhow::Result<Vec<Garden>> {
+        let conn = self.get_connection()?;
+
+        let mut query = SELECT_GARDEN_COLUMNS.to_string();
+        if require_precise_location {
+            query.push_str("\nWHERE g.latitude IS NOT NULL and g.longitude IS NOT NULL");
+        }
+
+        let mut statement = conn.prepare(&query)?;
+        let gardens = statement
+            .query_map([], garden_from_row)?
+            .collect::<Result<Vec<Garden>, _>>()?;
+
+        Ok(gardens)
+    }
+
+    async fn select_plants_by_garden_id(
+        &self,
+        garden_id: &str,
+        read_only: bool,
+    ) -> anyhow::Result<Vec<GardenPlant>> {
+        let conn = self.get_connection()?;
+
+        let id_field_name = if read_only { "read_id" } else { "write_id" };
+
+        let mut statement = conn.prepare(&format!(
+            r"
+SELECT
+  gp.quantity, gp.notes, gp.planted_date, gp.status, gp.zone,
+  p.id, p.scientific_name, p.common_name,
+  p.bloom, p.height, p.spread,
+  p.bloom_start_month, p.bloom_end_month, p.bloom_colors,
+  p.height_min_inches, p.height_max_inches, p.spread_min_inches, p.spread_max_inches,
+  p.moistures, p.shades,
+  p.pollinator_rating,
+  p.bird_rating,
+  p.spread_rating, p.deer_resistance_rating,
+  p.usda_symbol, p.usda_source, p.wiki_source,
+  i.id as image_id, i.title, i.card_url, i.original_url, i.author, i.license
+FROM plants p
+INNER JOIN gardens_plants gp on gp.plant_id = p.id
+INNER JOIN gardens g on g.id = gp.garden_id
+LEFT JOIN images i ON i.id = p.image_id
+WHERE g.{id_field_name} = :garden_id
+ORDER BY gp.ordering
+"
+        ))?;
+
+        let plants = statement
+            .query_map(named_params! { ":garden_id": garden_id }, |row| {
+                let status: String = row.get("status")?;
+                Ok(GardenPlant {
+                    plant: plant_from_row(row)?,
+                    details: GardenPlantDetails {
+                        quantity: row.get("quantity")?,
+                        notes: row.get("notes")?,
+                        planted_date: row.get("planted_date")?,
+                        status: GardenPlantStatus::from_str(&status)
+                            .expect("gardens_plants.status should have valid values"),
+                        zone: row.get("zone")?,
+                    },
+                    warnings: vec![],
+                })
+            })?
+            .collect::<Result<Vec<GardenPlant>, _>>()?;
+
+        Ok(plants)
+    }
+
+    async fn insert_garden(
+        &self,
+        garden: &Garden,
+        read_id: &str,
+        write_id: &str,
+    ) -> anyhow::Result<usize> {
+        let conn = self.get_connection()?;
+
+        conn.execute(
+            r"INSERT INTO gardens (read_id, write_id, name, shade, moisture, zipcode)
+            VALUES (:read_id, :write_id, :name, :shade, :moisture, :zipcode)",
+            named_params! {
+                ":read_id": read_id,
+                ":write_id": write_id,
+                ":name": &garden.name,
+                ":shade": garden.shade.to_string(),
+                ":moisture": garden.moisture.to_string(),
+                ":zipcode": &garden.zipcode,
+            },
+        )
+        .map(|_| conn.last_insert_rowid() as usize)
+        .map_err(|e| anyhow!("insert_garden failed: {}", e))
+    }
+
+    async fn update_garden(
+        &self,
+        write_id: &str,
+        name: &str,
+        conditions: Option<(Shade, Moisture)>,
+    ) -> anyhow::Result<()> {
+        let conn = self.get_connection()?;
+
+        conn.execute(
+            r"UPDATE gardens
+              SET name = :name,
+                shade = COALESCE(:shade, shade),
+                moisture = COALESCE(:moisture, moisture)
+              WHERE write_id = :write_id",
+            named_params! {
+                ":write_id": write_id,
+                ":name": name,
+                ":shade": conditions.map(|(shade, _)| shade.to_string()),
+                ":moisture": conditions.map(|(_, moisture)| moisture.to_string()),
+            },
+        )
+        .map(|_| ())
+        .map_err(|e| anyhow!("update_garden failed: {}", e))
+    }
+
+    async fn replace_garden_zones(
+        &self,
+        write_id: &str,
+        zones: &[GardenZone],
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_connection()?;
+        let transaction = conn.transaction()?;
+
+        transaction
+            .execute(
+                r"DELETE FROM garden_zones
+            WHERE garden_id = (SELECT id FROM gardens WHERE write_id = :write_id)",
+                named_params! { ":write_id": write_id },
+            )
+            .map_err(|e| anyhow!("replace_garden_zones delete failed: {e}"))?;
+
+        {
+            let mut statement = transaction.prepare(
+                r"INSERT INTO garden_zones (garden_id, zone, name, shade, moisture)
+           VALUES (
+             (SELECT id from gardens where write_id = :write_id), :zone,
+             :name, :shade, :moisture
+           )",
+            )?;
+
+            for (index, zone) in zones.iter().enumerate() {
+                statement
+                    .execute(named_params! {
+                        ":write_id": write_id,
+                        ":zone": index,
+                        ":name": zone.name,
+                        ":shade": zone.shade.to_string(),
+                        ":moisture": zone.moisture.to_string(),
+                    })
+                    .map_err(|e| anyhow!("replace_garden_zones insert failed: {e}"))?;
+            }
+        }
+
+        transaction
+            .commit()
+            .map_err(|e| anyhow!("replace_garden_zones commit failed: {e}"))
+    }
+
+    async fn replace_garden_plants(
+        &self,
+        write_id: &str,
+        plants: Vec<(usize, GardenPlantDetails)>,
+    ) -> anyhow::Result<()> {
+        // Remove duplicates - they cause issues w/ unique constraints
+        let mut plants = plants;
+        let mut seen = HashSet::new();
+        plants.retain(|(id, details)| seen.insert((details.zone, *id)));
+
+        let mut conn = self.get_connection()?;
+        let transaction = conn.transaction()?;
+
+        transaction
+            .execute(
+                r"DELETE FROM gardens_plants
+            WHERE garden_id = (SELECT id FROM gardens WHERE write_id = :write_id)",
+                named_params! { ":write_id": write_id },
+            )
+            .map_err(|e| anyhow!("replace_garden_plants delete failed: {e}"))?;
+
+        {
+            let mut statement = transaction.prepare(
+                r"INSERT INTO gardens_plants
+           (garden_id, plant_id, zone, ordering, quantity, notes, planted_date, status)
+           VALUES (
+             (SELECT id from gardens where write_id = :write_id), :plant_id, :zone, :ordering,
+             :quantity, :notes, :planted_date, :status
+           )",
+            )?;
+
+            for (ordering, (plant_id, details)) in plants.iter().enumerate() {
+                statement
+                    .execute(named_params! {
+                        ":write_id": write_id,
+                        ":plant_id": plant_id,
+                        ":zone": details.zone,
+                        ":ordering": ordering,
+                        ":quantity": details.quantity,
+                        ":notes": details.notes,
+                        ":planted_date": details.planted_date,
+                        ":status": details.status.to_string(),
+                    })
+                    .map_err(|e| anyhow!("replace_garden_plants insert failed: {e}"))?;
+            }
+        }
+
+        transaction
+            .commit()
+            .map_err(|e| anyhow!("replace_garden_plants commit failed: {e}"))
+    }
+
+    async fn find_plants_by_word_prefix(&self, word_prefix: &str) -> anyhow::Result<Vec<Plant>> {
+        let conn = self.get_connection()?;
+
+        // There is no FULLTEXT index, so every word must begin some word in
+        // the plant's names or synonyms.  Names are padded with a space so
+        // the first word matches like the others.
+        let words: Vec<String> = word_prefix
+            .split_whitespace()
+            .map(|word| format!("% {}%", escape_like(word)))
+            .collect();
+        if words.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let condition =
+            r"(' ' || scientific_name || ' ' || common_name || ' ' || synonyms) LIKE ? ESCAPE '\'";
+        let conditions = vec![condition; words.len()].join(" AND ");
+
+        let mut statement = conn.prepare(&format!(
+            r"
+ SELECT id, scientific_name, common_name
+ FROM (
+   SELECT id, scientific_name, common_name,
+     COALESCE(
+       (SELECT group_concat(name, ' ') FROM plant_synonyms s WHERE s.plant_id = p.id),
+       ''
+     ) AS synonyms
+   FROM plants p
+ )
+ WHERE {conditions}
+ LIMIT 10
+"
+        ))?;
+
+        let plants = statement
+            .query_map(params_from_iter(words.iter()), |row| {
+                Ok(Plant {
+                    id: Some(row.get("id")?),
+                    ..Plant::new(
+                        &row.get::<_, String>("scientific_name")?,
+                        &row.get::<_, String>("common_name")?,
+                    )
+                })
+            })?
+            .collect::<Result<Vec<Plant>, _>>()?;
+
+        Ok(plants)
+    }
+
+    async fn upsert_request_count(&self, uri: &str) -> anyhow::Result<usize> {
+        let conn = self.get_connection()?;
+
+        conn.query_row(
+            r"INSERT INTO request_counts (uri, date, count) VALUES
+            (?, DATE('now', 'localtime'), 1)
+            ON CONFLICT (uri, date) DO UPDATE SET count = count + 1
+            RETURNING count",
+            [uri],
+            |row| row.get(0),
+        )
+        .map_err(|e| anyhow!("insert into queries failed: {}", e))
+    }
+
+    async fn select_monthly_request_count(&self, uri: &str) -> anyhow::Result<usize> {
+        let conn = self.get_connection()?;
+
+        conn.query_row(
+            r"SELECT SUM(count) FROM request_counts
+            WHERE strftime('%Y-%m', date) = strftime('%Y-%m', 'now', 'localtime')
+            AND uri = ?",
+            [uri],
+            |row| row.get::<_, Option<usize>>(0),
+        )
+        .map(|count| count.unwrap_or(0))
+        .map_err(|e| anyhow!("select monthly request count failed: {}", e))
+    }
+    fn changesets(&self) -> Vec<Changeset> {
+        sqlite_changesets()
+    }
+
+    async fn select_applied_changesets(&self) -> anyhow::Result<HashSet<(String, String)>> {
+        let conn = self.get_connection()?;
+
+        conn.execute(
+            r"CREATE TABLE IF NOT EXISTS schema_changesets (
+            file VARCHAR(255),
+            changeset VARCHAR(255),
+            applied_at DATETIME,
+
+            PRIMARY KEY (file, changeset)
+          )",
+            [],
+        )
+        .map_err(|e| anyhow!("create schema_changesets failed: {e}"))?;
+
+        let mut statement = conn.prepare("SELECT file, changeset FROM schema_changesets")?;
+        let applied = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashSet<(String, String)>, _>>()?;
+
+        Ok(applied)
+    }
+
+    async fn apply_changeset(&self, changeset: &Changeset) -> anyhow::Result<()> {
+        let mut conn = self.get_connection()?;
+        let transaction = conn.transaction()?;
+
+        transaction
+            .execute_batch(&changeset.sql)
+            .map_err(|e| anyhow!("apply_changeset {changeset} failed: {e}"))?;
+
+        transaction
+            .execute(
+                r"INSERT INTO schema_changesets (file, changeset, applied_at)
+            VALUES (?, ?, DATETIME('now'))",
+                [changeset.file, changeset.id.as_str()],
+            )
+            .map_err(|e| anyhow!("apply_changeset {changeset} failed to record: {e}"))?;
+
+        transaction
+            .commit()
+            .map_err(|e| anyhow!("apply_changeset {changeset} commit failed: {e}"))
+    }
+}
+
+fn region_from_row(row: &Row) -> rusqlite::Result<Region> {
+    Ok(Region {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        latitude: row.get(2)?,
+        longitude: row.get(3)?,
+        radius_miles: row.get(4)?,
+    })
+}
+
+fn plant_from_row(row: &Row) -> rusqlite::Result<Plant> {
+    let image = match row.get::<_, Option<usize>>("image_id")? {
+        Some(img_id) => {
+            let license: String = row.get("license")?;
+
+            Some(Image {
+                id: Some(img_id),
+                title: row.get("title")?,
+                card_url: row.get("card_url")?,
+                original_url: row.get("original_url")?,
+                author: row.get("author")?,
+                license_url: Image::get_license_url(&license).unwrap_or_default(),
+                license,
+            })
+        }
+        None => None,
+    };
+
+    Ok(Plant {
+        id: Some(row.get("id")?),
+        scientific: row.get("scientific_name")?,
+        common: row.get("common_name")?,
+        bloom: row.get("bloom")?,
+        height: row.get("height")?,
+        spread: row.get("spread")?,
+        bloom_period: row
+            .get::<_, Option<u8>>("bloom_start_month")?
+            .zip(row.get("bloom_end_month")?)
+            .map(|(start_month, end_month)| BloomPeriod {
+                start_month,
+                end_month,
+            }),
+        bloom_colors: from_comma_separated_string(row.get("bloom_colors")?),
+        height_range: size_from_row(row, "height")?,
+        spread_range: size_from_row(row, "spread")?,
+        moistures: from_comma_separated_string(row.get("moistures")?),
+        shades: from_comma_separated_string(row.get("shades")?),
+        pollinator_rating: row.get("pollinator_rating")?,
+        bird_rating: row.get("bird_rating")?,
+        spread_rating: row.get("spread_rating")?,
+        deer_resistance_rating: row.get("deer_resistance_rating")?,
+        usda_symbol: row.get("usda_symbol")?,
+        usda_source: row.get("usda_source")?,
+        wiki_source: row.get("wiki_source")?,
+        image,
+        highlights: vec![],
+        done_loading: true,
+    })
+}
+
+/// Reads a SizeRange from the {dimension}_min_inches/{dimension}_max_inches columns
+fn size_from_row(row: &Row, dimension: &str) -> rusqlite::Result<Option<SizeRange>> {
+    let min: Option<u32> = row.get(format!("{dimension}_min_inches").as_str())?;
+    let max: Option<u32> = row.get(format!("{dimension}_max_inches").as_str())?;
+
+    Ok(min.zip(max).map(|(min_inches, max_inches)| SizeRange {
+        min_inches,
+        max_inches,
+    }))
+}
+
+fn garden_from_row(row: &Row) -> rusqlite::Result<Garden> {
+    let zipcode: usize = row.get(1)?;
+    let shade: String = row.get(3)?;
+    let moisture: String = row.get(4)?;
+
+    Ok(Garden {
+        name: row.get(0)?,
+        zipcode: format!("{zipcode:05}"),
+        region_name: row.get(2)?,
+        region_id: row.get(8)?,
+        shade: Shade::from_str(&shade).expect("gardens.shade should have valid values"),
+        moisture: Moisture::from_str(&moisture).expect("gardens.moisture should have valid values"),
+        read_id: row.get(5)?,
+        write_id: None,
+        plants: vec![],
+        zones: vec![],
+        latitude: row.get(6)?,
+        longitude: row.get(7)?,
+    })
+}
+
+fn escape_like(word: &str) -> String {
+    word.replace('\\', r"\\")
+        .replace('%', r"\%")
+        .replace('_', r"\_")
+}
+
+/// The params for PLANTS_BY_ZIP_MOISTURE_SHADE and PLANT_FILTER_CONDITIONS
+fn zip_moisture_shade_params(
+    zip: &str,
+    moistures: &[Moisture],
+    shades: &[Shade],
+    filters: &PlantFilters,
+) -> Vec<(&'static str, Box<dyn ToSql>)> {
+    vec![
+        (":zipcode", Box::new(zip.to_string())),
+        (":moistures", Box::new(to_comma_separated_string(moistures))),
+        (":shades", Box::new(to_comma_separated_string(shades))),
+        (":min_height", Box::new(filters.min_height_inches)),
+        (":max_height", Box::new(filters.max_height_inches)),
+        (
+            ":min_pollinator_rating",
+            Box::new(filters.min_pollinator_rating),
+        ),
+        (":min_bird_rating", Box::new(filters.min_bird_rating)),
+        (
+            ":min_deer_resistance_rating",
+            Box::new(filters.min_deer_resistance_rating),
+        ),
+        (
+            ":bloom_start",
+            Box::new(filters.bloom.map(|b| b.start_month)),
+        ),
+        (":bloom_end", Box::new(filters.bloom.map(|b| b.end_month))),
+    ]
+}
+
+/// Borrows owned named params in the form rusqlite expects
+fn named_params_slice<'a>(
+    params: &'a [(&'static str, Box<dyn ToSql>)],
+) -> Vec<(&'a str, &'a dyn ToSql)> {
+    params
+        .iter()
+        .map(|(name, value)| (*name, value.as_ref()))
+        .collect()
+}
+
+fn to_comma_separated_string<T: Display>(vec: &[T]) -> Option<String> {
+    // If the vector is empty, we want to keep these as null in the db
+    // A null value indicates we should try to populate it again next time
+    if vec.is_empty() {
+        return None;
+    }
+
+    Some(
+        vec.iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<String>>()
+            .join(","),
+    )
+}
+
+fn from_comma_separated_string<T: FromStr>(value: Option<String>) -> Vec<T> {
+    value
+        .map(|s| s.split(',').filter_map(|v| v.parse().ok()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an in memory database with the schema, but only a tiny
+    /// amount of data (the populate scripts are slow).
+    fn make_runner() -> SqliteRunner {
+        let runner = SqliteRunner::new("sqlite::memory:");
+
+        let conn = runner.get_connection().unwrap();
+        for changeset in sqlite_changesets() {
+            if changeset.file.starts_with("sqlite/") {
+                conn.execute_batch(&changeset.sql).unwrap();
+            }
+        }
+        conn.execute_batch(
+            r"
+INSERT INTO regions (id, name, latitude, longitude, radius_miles)
+  VALUES (1, 'Columbus, OH', 39.9612, -82.9988, 75);
+INSERT INTO zipcodes (zipcode, latitude, longitude, region_id) VALUES
+  (43081, 40.1126, -82.9201, 1),
+  (43085, 40.0998, -83.0156, 1);",
+        )
+        .unwrap();
+        drop(conn);
+
+        runner
+    }
+
+    #[tokio::test]
+    async fn test_save_and_select_plant() {
+        let runner = make_runner();
+
+        let mut plant = Plant::new("Asclepias incarnata", "Swamp Milkweed");
+        plant.moistures = vec![Moisture::Some, Moisture::Lots];
+        plant.shades = vec![Shade::None];
+        plant.pollinator_rating = Some(9);
+
+        let id = runner.insert_plant(&plant, None).await.unwrap();
+        runner
+            .insert_region_plants("43081", HashSet::from([id]), &generated())
+            .await
+            .unwrap();
+
+        let found = runner
+            .select_plants_by_zip_moisture_shade(
+                "43081",
+                &[Moisture::Lots],
+                &[Shade::None],
+                &PlantFilters::default(),
+                &PlantPage::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].moistures, vec![Moisture::Some, Moisture::Lots]);
+        assert_eq!(found[0].pollinator_rating, Some(9));
+
+        let not_found = runner
+            .select_plants_by_zip_moisture_shade(
+                "43081",
+                &[Moisture::None],
+                &[Shade::None],
+                &PlantFilters::default(),
+                &PlantPage::default(),
+            )
+            .await
+            .unwrap();
+        assert!(not_found.is_empty());
+
+        // Matches when it grows in any of the moistures and any of the shades
+        let found = runner
+            .select_plants_by_zip_moisture_shade(
+                "43081",
+                &[Moisture::None, Moisture::Lots],
+                &[Shade::Some, Shade::None],
+                &PlantFilters::default(),
+                &PlantPage::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+
+        let not_found = runner
+            .select_plants_by_zip_moisture_shade(
+                "43081",
+                &[Moisture::Some],
+                &[Shade::Some, Shade::Lots],
+                &PlantFilters::default(),
+                &PlantPage::default(),
+            )
+            .await
+            .unwrap();
+        assert!(not_found.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_update_plant_structured_details() {
+        let runner = make_runner();
+
+        let plant = Plant::new("Asclepias tuberosa", "Butterfly Weed");
+        let id = runner.insert_plant(&plant, None).await.unwrap();
+
+        let mut plant = runner.select_plant_by_id(id).await.unwrap().unwrap();
+        assert_eq!(plant.bloom_period, None);
+        assert!(plant.bloom_colors.is_empty());
+
+        plant.common = "Butterfly Milkweed".to_string();
+        plant.bloom_period = Some(BloomPeriod {
+            start_month: 6,
+            end_month: 8,
+        });
+        plant.bloom_colors = vec!["orange".to_string(), "yellow".to_string()];
+        plant.height_range = Some(SizeRange {
+            min_inches: 12,
+            max_inches: 24,
+        });
+        runner.update_plant(&plant, None).await.unwrap();
+
+        let found = runner.select_plant_by_id(id).await.unwrap().unwrap();
+        assert_eq!(found.common, "Butterfly Milkweed");
+        assert_eq!(found.bloom_period, plant.bloom_period);
+        assert_eq!(found.bloom_colors, plant.bloom_colors);
+        assert_eq!(found.height_range, plant.height_range);
+        assert_eq!(found.spread_range, None);
+    }
+
+    #[tokio::test]
+    async fn test_insert_plant_change() {
+        let runner = make_runner();
+
+        let plant = Plant::new("Asclepias tuberosa", "Butterfly Weed");
+        let id = runner.insert_plant(&plant, None).await.unwrap();
+
+        let change = PlantChange {
+            plant_id: id,
+            changed_by: "doug".to_string(),
+            action: PlantChangeAction::Update,
+            changes: serde_json::json!({"birdRating": {"from": 4, "to": 5}}),
+        };
+        runner.insert_plant_change(&change).await.unwrap();
+
+        // Stored the same way the api writes it
+        let conn = runner.get_connection().unwrap();
+        let action: String = conn
+            .query_row("SELECT action FROM plant_changes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(serde_json::to_value(change.action).unwrap(), action);
+        assert_eq!(
+            PlantChangeAction::from_str(&action).unwrap(),
+            PlantChangeAction::Update
+        );
+    }
+
+    #[tokio::test]
+    async fn test_select_plants_with_filters() {
+        let runner = make_runner();
+
+        let mut short = Plant::new("short", "short");
+        short.height_range = Some(SizeRange {
+            min_inches: 12,
+            max_inches: 24,
+        });
+        short.bloom_period = Some(BloomPeriod {
+            start_month: 7,
+            end_month: 9,
+        });
+        short.pollinator_rating = Some(9);
+
+        let mut tall = Plant::new("tall", "tall");
+        tall.height_range = Some(SizeRange {
+            min_inches: 48,
+            max_inches: 72,
+        });
+        tall.bloom_period = Some(BloomPeriod {
+            start_month: 11,
+            end_month: 2,
+        });
+        tall.pollinator_rating = Some(5);
+        tall.deer_resistance_rating = Some(8);
+
+        let unknown = Plant::new("unknown", "unknown");
+
+        let mut ids = HashSet::new();
+        for plant in [&short, &tall, &unknown] {
+            ids.insert(runner.insert_plant(plant, None).await.unwrap());
+        }
+        runner
+            .insert_region_plants("43081", ids, &generated())
+            .await
+            .unwrap();
+
+        let search = |filters: PlantFilters| {
+            let runner = &runner;
+            async move {
+                runner
+                    .select_plants_by_zip_moisture_shade(
+                        "43081",
+                        &[Moisture::Some],
+                        &[Shade::Some],
+                        &filters,
+                        &PlantPage::default(),
+                    )
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|p| p.scientific)
+                    .collect::<Vec<String>>()
+            }
+        };
+
+        assert_eq!(search(PlantFilters::default()).await.len(), 3);
+
+        let filters = PlantFilters {
+            max_height_inches: Some(36),
+            ..Default::default()
+        };
+        assert_eq!(search(filters).await, vec!["short"]);
+
+        let filters = PlantFilters {
+            min_pollinator_rating: Some(5),
+            min_deer_resistance_rating: Some(7),
+            ..Default::default()
+        };
+        assert_eq!(search(filters).await, vec!["tall"]);
+
+        // Both the plant's and the filter's bloom may wrap the new year
+        let bloom = |start_month, end_month| PlantFilters {
+            bloom: Some(BloomPeriod {
+                start_month,
+                end_month,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(search(bloom(8, 8)).await, vec!["short"]);
+        assert_eq!(search(bloom(1, 1)).await, vec!["tall"]);
+        assert_eq!(search(bloom(12, 3)).await, vec!["tall"]);
+        assert_eq!(search(bloom(6, 12)).await.len(), 2);
+        assert!(search(bloom(4, 5)).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_regions() {
+        let runner = make_runner();
+
+        let mut sunny = Plant::new("a", "Sunny");
+        sunny.shades = vec![Shade::None];
+        sunny.moistures = vec![Moisture::None, Moisture::Some];
+        let unknown = Plant::new("b", "Unknown");
+
+        let mut ids = HashSet::new();
+        ids.insert(runner.insert_plant(&sunny, None).await.unwrap());
+        ids.insert(runner.insert_plant(&unknown, None).await.unwrap());
+        runner
+            .insert_region_plants("43081", ids, &generated())
+            .await
+            .unwrap();
+
+        let regions = runner.select_regions().await.unwrap();
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].name, "Columbus, OH");
+        assert_eq!(regions[0].radius_miles, 75);
+        assert_eq!(
+            runner.select_region(1).await.unwrap(),
+            Some(regions[0].clone())
+        );
+        assert_eq!(runner.select_region(2).await.unwrap(), None);
+
+        assert_eq!(
+            runner.select_region_zipcodes(1).await.unwrap(),
+            vec!["43081", "43085"]
+        );
+
+        // Plants with unknown conditions count towards all of them
+        let counts = runner.count_region_plants(1).await.unwrap();
+        assert_eq!(counts, RegionPlantCounts::new(2, [2, 1, 1], [2, 2, 1]));
+
+        let page = PlantPage {
+            sort: PlantSort::Name,
+            offset: 1,
+            limit: Some(1),
+        };
+        let plants = runner.select_plants_by_region(1, &page).await.unwrap();
+        assert_eq!(plants.len(), 1);
+        assert_eq!(plants[0].common, "Unknown");
+    }
+
+    #[tokio::test]
+    async fn test_select_plants_pages_and_sorts() {
+        let runner = make_runner();
+
+        let mut ids = HashSet::new();
+        for (name, deer_resistance) in [("c", Some(3)), ("a", None), ("b", Some(9))] {
+            let mut plant = Plant::new(name, name);
+            plant.deer_resistance_rating = deer_resistance;
+            ids.insert(runner.insert_plant(&plant, None).await.unwrap());
+        }
+        runner
+            .insert_region_plants("43081", ids, &generated())
+            .await
+            .unwrap();
+
+        let search = |sort, offset, limit| {
+            let runner = &runner;
+            async move {
+                let page = PlantPage {
+                    sort,
+                    offset,
+                    limit,
+                };
+                runner
+                    .select_plants_by_zip_moisture_shade(
+                        "43081",
+                        &[Moisture::Some],
+                        &[Shade::Some],
+                        &PlantFilters::default(),
+                        &page,
+                    )
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|p| p.common)
+                    .collect::<Vec<String>>()
+            }
+        };
+
+        assert_eq!(search(PlantSort::Name, 0, None).await, vec!["a", "b", "c"]);
+        assert_eq!(search(PlantSort::Name, 1, Some(1)).await, vec!["b"]);
+        assert_eq!(search(PlantSort::Name, 2, None).await, vec!["c"]);
+        assert_eq!(
+            search(PlantSort::DeerResistance, 0, None).await,
+            vec!["b", "c", "a"]
+        );
+
+        let total = runner
+            .count_plants_by_zip_moisture_shade(
+                "43081",
+                &[Moisture::Some],
+                &[Shade::Some],
+                &PlantFilters::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(total, 3);
+    }
+
+    #[tokio::test]
+    async fn test_closest_zip() {
+        let runner = make_runner();
+
+        assert!(runner.check_zip_exists("43081").await.unwrap());
+        assert!(!runner.check_zip_exists("43082").await.unwrap());
+
+        // The migration computes prefixes before make_runner adds zipcodes
+        runner
+            .get_connection()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO zipcode_prefixes (prefix, latitude, longitude)
+                   SELECT zipcode / 100, AVG(latitude), AVG(longitude)
+                   FROM zipcodes GROUP BY zipcode / 100",
+            )
+            .unwrap();
+
+        let centroid = runner.select_zip_prefix_centroid("430").await.unwrap();
+        let centroid = centroid.unwrap();
+        assert!((centroid.latitude - 40.1062).abs() < 0.0001);
+        assert!((centroid.longitude + 82.96785).abs() < 0.0001);
+        assert_eq!(
+            runner.select_zip_prefix_centroid("431").await.unwrap(),
+            None
+        );
+
+        let location = Coordinates::new(40.11, -82.93).unwrap();
+        let near = runner.select_zipcodes_near(location, 0.05).await.unwrap();
+        assert_eq!(near.len(), 1);
+        assert_eq!(near[0].0, "43081");
+        assert_eq!(
+            runner
+                .select_zipcodes_near(location, 1.0)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_plants_by_word_prefix() {
+        let runner = make_runner();
+
+        let plant = Plant::new("Asclepias incarnata", "Swamp Milkweed");
+        runner.insert_plant(&plant, None).await.unwrap();
+
+        let found = runner.find_plants_by_word_prefix("milk swa").await.unwrap();
+        assert_eq!(found.len(), 1);
+
+        let found = runner.find_plants_by_word_prefix("weed").await.unwrap();
+        assert!(found.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_plant_synonyms() {
+        let runner = make_runner();
+
+        let plant = Plant::new("Symphyotrichum novae-angliae", "New England Aster");
+        let id = runner.insert_plant(&plant, None).await.unwrap();
+        let synonyms = vec![
+            PlantSynonym::new("Aster novae-angliae", SynonymKind::Scientific),
+            PlantSynonym::new("Michaelmas Daisy", SynonymKind::Common),
+        ];
+        runner.insert_plant_synonyms(id, &synonyms).await.unwrap();
+
+        // Inserting the same names again keeps one of each
+        runner.insert_plant_synonyms(id, &synonyms).await.unwrap();
+        let selected = runner.select_plant_synonyms(vec![id]).await.unwrap();
+        assert_eq!(
+            selected,
+            vec![(id, synonyms[1].clone()), (id, synonyms[0].clone())]
+        );
+
+        let found = runner
+            .select_plant_by_scientific_name("Aster novae-angliae")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, Some(id));
+        assert_eq!(found.scientific, "Symphyotrichum novae-angliae");
+
+        // Common names aren't scientific names
+        let found = runner
+            .select_plant_by_scientific_name("Michaelmas Daisy")
+            .await
+            .unwrap();
+        assert!(found.is_none());
+
+        let found = runner
+            .find_plants_by_word_prefix("aster nov")
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        let found = runner.find_plants_by_word_prefix("michael").await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].scientific, "Symphyotrichum novae-angliae");
+    }
+
+    #[tokio::test]
+    async fn test_garden_round_trip() {
+        let runner = make_runner();
+
+        let plant = Plant::new("Asclepias incarnata", "Swamp Milkweed");
+        let plant_id = runner.insert_plant(&plant, None).await.unwrap();
+
+        let garden = Garden::empty(
+            "My Garden".to_string(),
+            "43081".to_string(),
+            Shade::Some,
+            Moisture::Lots,
+        );
+        runner
+            .insert_garden(&garden, "read", "write")
+            .await
+            .unwrap();
+        let details = GardenPlantDetails {
+            quantity: 3,
+            notes: Some("By the downspout".to_string()),
+            planted_date: Some("2024-05-18".to_string()),
+            status: GardenPlantStatus::Established,
+            zone: 0,
+        };
+        runner
+            .replace_garden_plants(
+                "write",
+                vec![
+                    (plant_id, details.clone()),
+                    (plant_id, GardenPlantDetails::default()),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let found = runner.select_garden_by_id("read", true).await.unwrap();
+        let found = found.unwrap();
+        assert_eq!(found.region_name, Some("Columbus, OH".to_string()));
+        assert_eq!(found.region_id, Some(1));
+        assert_eq!(found.shade, Shade::Some);
+        assert_eq!(
+            found.zones,
+            vec![GardenZone::new("Garden", Shade::Some, Moisture::Lots)]
+        );
+
+        let plants = runner
+            .select_plants_by_garden_id("read", true)
+            .await
+            .unwrap();
+        assert_eq!(plants.len(), 1);
+        assert_eq!(plants[0].plant.id, Some(plant_id));
+        assert_eq!(plants[0].details, details);
+
+        // The same plant can be in more than one zone
+        let zones = vec![
+            GardenZone::new("Front", Shade::None, Moisture::Some),
+            GardenZone::new("Rain garden", Shade::Some, Moisture::Lots),
+        ];
+        runner.replace_garden_zones("write", &zones).await.unwrap();
+        runner
+            .update_garden("write", "Renamed", Some((Shade::None, Moisture::Some)))
+            .await
+            .unwrap();
+        let in_rain_garden = GardenPlantDetails {
+            zone: 1,
+            ..GardenPlantDetails::default()
+        };
+        runner
+            .replace_garden_plants(
+                "write",
+                vec![
+                    (plant_id, details.clone()),
+                    (plant_id, in_rain_garden.clone()),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let found = runner
+            .select_garden_by_id("write", false)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.zones, zones);
+        assert_eq!(found.name, "Renamed");
+        assert_eq!((found.shade, found.moisture), (Shade::None, Moisture::Some));
+
+        // Without conditions, the garden keeps the ones it has
+        runner
+            .update_garden("write", "My Garden", None)
+            .await
+            .unwrap();
+        let found = runner.select_garden_by_id("read", true).await.unwrap();
+        assert_eq!(found.unwrap().shade, Shade::None);
+
+        let plants = runner
+            .select_plants_by_garden_id("read", true)
+            .await
+            .unwrap();
+        assert_eq!(plants.len(), 2);
+        assert_eq!(plants[1].details, in_rain_garden);
+    }
+
+    #[tokio::test]
+    async fn test_apply_changeset() {
+        let runner = make_runner();
+
+        let changeset = Changeset {
+            file: "migrations/test.sql",
+            id: "test:1".to_string(),
+            sql: "CREATE TABLE foo (id INTEGER);\nINSERT INTO foo VALUES (1);".to_string(),
+        };
+
+        assert!(runner.select_applied_changesets().await.unwrap().is_empty());
+        runner.apply_changeset(&changeset).await.unwrap();
+
+        let applied = runner.select_applied_changesets().await.unwrap();
+        assert!(applied.contains(&("migrations/test.sql".to_string(), "test:1".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_apply_changeset_rolls_back_on_failure() {
+        let runner = make_runner();
+        runner.select_applied_changesets().await.unwrap();
+
+        let changeset = Changeset {
+            file: "migrations/test.sql",
+            id: "test:1".to_string(),
+            sql: "CREATE TABLE foo (id INTEGER);\nNOT VALID SQL;".to_string(),
+        };
+
+        assert!(runner.apply_changeset(&changeset).await.is_err());
+        assert!(runner.select_applied_changesets().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_select_plants_after_id_with_regions() {
+        let runner = make_runner();
+
+        let mut ids = vec![];
+        for name in ["first", "second", "third"] {
+            ids.push(
+                runner
+                    .insert_plant(&Plant::new(name, name), None)
+                    .await
+                    .unwrap(),
+            );
+        }
+        runner
+            .insert_region_plants("43085", HashSet::from([ids[1]]), &generated())
+            .await
+            .unwrap();
+
+        let page = runner.select_plants_after_id(ids[0], 1).await.unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].scientific, "second");
+
+        let regions = runner.select_plant_regions(ids.clone()).await.unwrap();
+        assert_eq!(regions.len(), 1);
+        let (plant_id, region) = &regions[0];
+        assert_eq!(*plant_id, ids[1]);
+        assert_eq!(region.region_id, 1);
+        assert_eq!(region.name, "Columbus, OH");
+        assert_eq!(region.zipcode, "43081");
+        assert_eq!(region.provenance.source, NativeSource::Generated);
+        assert_eq!(region.provenance.confidence, NativeConfidence::Low);
+        assert_eq!(
+            region.provenance.date_added.as_ref().map(String::len),
+            Some(10)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_insert_region_plants_keeps_better_provenance() {
+        let runner = make_runner();
+        let id = runner
+            .insert_plant(&Plant::new("a", "a"), None)
+            .await
+            .unwrap();
+
+        let source = |source| {
+            let runner = &runner;
+            async move {
+                runner
+                    .insert_region_plants(
+                        "43081",
+                        HashSet::from([id]),
+                        &NativeProvenance::new(source),
+                    )
+                    .await
+                    .unwrap();
+                runner.select_plant_regions(vec![id]).await.unwrap()[0]
+                    .1
+                    .provenance
+                    .source
+            }
+        };
+
+        assert_eq!(
+            source(NativeSource::Generated).await,
+            NativeSource::Generated
+        );
+        assert_eq!(source(NativeSource::Usda).await, NativeSource::Usda);
+        assert_eq!(source(NativeSource::Generated).await, NativeSource::Usda);
+        assert_eq!(source(NativeSource::Curator).await, NativeSource::Curator);
+    }
+
+    #[tokio::test]
+    async fn test_select_plants_missing_details_after_id() {
+        let runner = make_runner();
+
+        let mut filled = Plant::new("filled", "filled");
+        filled.height = Some("2 ft".to_string());
+        filled.height_range = Some(SizeRange {
+            min_inches: 24,
+            max_inches: 24,
+        });
+        filled.usda_symbol = Some("FILL".to_string());
+
+        let mut unparsed = Plant::new("unparsed", "unparsed");
+        unparsed.height = Some("2 ft".to_string());
+        unparsed.usda_symbol = Some("UNPA".to_string());
+
+        let no_symbol = Plant::new("no symbol", "no symbol");
+
+        for plant in [&filled, &unparsed, &no_symbol] {
+            runner.insert_plant(plant, None).await.unwrap();
+        }
+
+        let missing = runner
+            .select_plants_missing_details_after_id(0, 10)
+            .await
+            .unwrap();
+        let names: Vec<&str> = missing.iter().map(|p| p.scientific.as_str()).collect();
+        assert_eq!(names, vec!["unparsed", "no symbol"]);
+    }
+
+    fn generated() -> NativeProvenance {
+        NativeProvenance::new(NativeSource::Generated)
+    }
+}
@@ -0,0 +1,298 @@
+use crate::domain::*;
+use async_trait::async_trait;
+use mockall::automock;
+use std::collections::HashSet;
+
+use super::migrations::Changeset;
+
+/// Conditions narrowing a plant search by PlantFilters, shared by both
+/// backends.  Each one is skipped when its parameter is NULL.  Bloom periods
+/// may wrap around the new year, so two periods overlap when either one's
+/// start month falls within the other.
+pub(crate) const PLANT_FILTER_CONDITIONS: &str = r"
+  AND (:min_height IS NULL OR p.height_min_inches >= :min_height)
+  AND (:max_height IS NULL OR p.height_max_inches <= :max_height)
+  AND (:min_pollinator_rating IS NULL OR p.pollinator_rating >= :min_pollinator_rating)
+  AND (:min_bird_rating IS NULL OR p.bird_rating >= :min_bird_rating)
+  AND (:min_deer_resistance_rating IS NULL
+    OR p.deer_resistance_rating >= :min_deer_resistance_rating)
+  AND (:bloom_start IS NULL OR (
+    (p.bloom_start_month <= p.bloom_end_month
+      AND :bloom_start BETWEEN p.bloom_start_month AND p.bloom_end_month)
+    OR (p.bloom_start_month > p.bloom_end_month
+      AND (:bloom_start >= p.bloom_start_month OR :bloom_start <= p.bloom_end_month))
+    OR (:bloom_start <= :bloom_end
+      AND p.bloom_start_month BETWEEN :bloom_start AND :bloom_end)
+    OR (:bloom_start > :bloom_end
+      AND (p.bloom_start_month >= :bloom_start OR p.bloom_start_month <= :bloom_end))
+  ))
+";
+
+/// The ORDER BY and LIMIT clauses for a page of plant search results, shared
+/// by both backends.  Plants without a value to sort by go last, and ties are
+/// broken by id so pages don't shift between requests.
+pub(crate) fn plant_page_clauses(page: &PlantPage) -> String {
+    let order = match page.sort {
+        PlantSort::Wildlife => {
+            r"p.moistures IS NOT NULL and p.shades IS NOT NULL desc,
+  p.pollinator_rating * p.pollinator_rating * p.pollinator_rating
+    + p.bird_rating * p.bird_rating * p.bird_rating desc"
+        }
+        PlantSort::DeerResistance => {
+            "p.deer_resistance_rating IS NULL, p.deer_resistance_rating desc"
+        }
+        PlantSort::Name => "LOWER(p.common_name)",
+        PlantSort::Bloom => "p.bloom_start_month IS NULL, p.bloom_start_month, p.bloom_end_month",
+    };
+
+    let limit = match (page.limit, page.offset) {
+        (Some(limit), offset) => format!("LIMIT {limit} OFFSET {offset}"),
+        (None, 0) => String::new(),
+        // Both databases only allow OFFSET after a LIMIT
+        (None, offset) => format!("LIMIT {} OFFSET {offset}", i64::MAX),
+    };
+
+    format!("ORDER BY\n  {order},\n  p.id\n{limit}")
+}
+
+/// The queries Database needs from a storage backend.  Implemented by
+/// SqlRunner (MySQL/MariaDB) and SqliteRunner (SQLite).
+#[automock]
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Inserts a new Query into the database, or increments its count.
+    /// Returns Err if it fails.
+    async fn upsert_query(
+        &self,
+        zip: &str,
+        moisture: &Moisture,
+        shade: &Shade,
+    ) -> anyhow::Result<()>;
+
+    /// Checks if the zipcode exists in the zipcodes table.
+    async fn check_zip_exists(&self, zip: &str) -> anyhow::Result<bool>;
+
+    /// Selects the center of the zipcodes starting with a three digit prefix.
+    /// Returns Err if it fails, Ok(None) if no zipcodes have the prefix.
+    async fn select_zip_prefix_centroid(&self, prefix: &str)
+        -> anyhow::Result<Option<Coordinates>>;
+
+    /// Selects how many times a query has been executed.
+    /// Returns Err if it fails, Ok(0) if not found.
+    async fn select_query_count(
+        &self,
+        zip: &str,
+        moisture: &Moisture,
+        shade: &Shade,
+    ) -> anyhow::Result<usize>;
+
+    /// Inserts into regions_plants, dated today.  Rows which already exist
+    /// take the new provenance, unless it was generated.
+    /// Returns Err if it fails.
+    async fn insert_region_plants(
+        &self,
+        zip: &str,
+        plant_ids: HashSet<usize>,
+        provenance: &NativeProvenance,
+    ) -> anyhow::Result<()>;
+
+    /// Updates one plant.
+    /// Returns Err if it fails.
+    async fn update_plant(&self, plant: &Plant, img_id: Option<usize>) -> anyhow::Result<()>;
+
+    /// Inserts one plant, returning its id.
+    /// Returns Err if it fails.
+    async fn insert_plant(&self, plant: &Plant, img_id: Option<usize>) -> anyhow::Result<usize>;
+
+    /// Selects one page of plants by zip which grow in any of the moistures
+    /// and any of the shades, narrowed by filters.
+    /// Returns Err if it fails.
+    async fn select_plants_by_zip_moisture_shade(
+        &self,
+        zip: &str,
+        moistures: &[Moisture],
+        shades: &[Shade],
+        filters: &PlantFilters,
+        page: &PlantPage,
+    ) -> anyhow::Result<Vec<Plant>>;
+
+    /// Counts every plant select_plants_by_zip_moisture_shade could return,
+    /// across all pages.  Returns Err if it fails.
+    async fn count_plants_by_zip_moisture_shade(
+        &self,
+        zip: &str,
+        moistures: &[Moisture],
+        shades: &[Shade],
+        filters: &PlantFilters,
+    ) -> anyhow::Result<usize>;
+
+    /// Selects one plant by scientific name.
+    /// Returns Err if it fails, Ok(None) if not found.
+    async fn select_plant_by_scientific_name(
+        &self,
+        scientific_name: &str,
+    ) -> anyhow::Result<Option<Plant>>;
+
+    /// Selects one plant by id.
+    /// Returns Err if it fails, Ok(None) if not found.
+    async fn select_plant_by_id(&self, id: usize) -> anyhow::Result<Option<Plant>>;
+
+    /// Selects up to limit plants with ids greater than after_id, ordered by id.
+    /// Returns Err if it fails.
+    async fn select_plants_after_id(
+        &self,
+        after_id: usize,
+        limit: usize,
+    ) -> anyhow::Result<Vec<Plant>>;
+
+    /// Selects up to limit plants with ids greater than after_id, ordered by
+    /// id, which have bloom, height or spread text without its structured
+    /// columns, or no USDA symbol.  Returns Err if it fails.
+    async fn select_plants_missing_details_after_id(
+        &self,
+        after_id: usize,
+        limit: usize,
+    ) -> anyhow::Result<Vec<Plant>>;
+
+    /// Inserts other names for a plant, ignoring ones it already has.
+    /// Returns Err if it fails.
+    async fn insert_plant_synonyms(
+        &self,
+        plant_id: usize,
+        synonyms: &[PlantSynonym],
+    ) -> anyhow::Result<()>;
+
+    /// Selects the other names of each plant, as (plant_id, synonym).
+    /// Returns Err if it fails.
+    async fn select_plant_synonyms(
+        &self,
+        plant_ids: Vec<usize>,
+    ) -> anyhow::Result<Vec<(usize, PlantSynonym)>>;
+
+    /// Selects the regions each plant is native to, as (plant_id, region).
+    /// Returns Err if it fails.
+    async fn select_plant_regions(
+        &self,
+        plant_ids: Vec<usize>,
+    ) -> anyhow::Result<Vec<(usize, PlantRegion)>>;
+
+    /// Inserts one image, returning its id.
+    /// Returns Err if it fails.
+    async fn insert_image(&self, image: &Image) -> anyhow::Result<usize>;
+
+    /// Inserts one entry in the plant audit log.
+    /// Returns Err if it fails.
+    async fn insert_plant_change(&self, change: &PlantChange) -> anyhow::Result<()>;
+
+    /// Selects all nurseries which match the given zipcode.
+    /// Returns Err if it fails, Ok(empty vec) if none are found.
+    async fn select_nurseries_by_zip(&self, zip: &str) -> anyhow::Result<Vec<Nursery>>;
+
+    /// Selects a region's name for the given zipcode.
+    /// Returns Err if it fails, Ok(None) if none are found.
+    async fn select_region_name_by_zip(&self, zip: &str) -> anyhow::Result<Option<String>>;
+
+    /// Selects every region, by name.
+    /// Returns Err if it fails.
+    async fn select_regions(&self) -> anyhow::Result<Vec<Region>>;
+
+    /// Selects one region by id.
+    /// Returns Err if it fails, Ok(None) if not found.
+    async fn select_region(&self, id: usize) -> anyhow::Result<Option<Region>>;
+
+    /// Selects the zipcodes in a region, in order.
+    /// Returns Err if it fails.
+    async fn select_region_zipcodes(&self, id: usize) -> anyhow::Result<Vec<String>>;
+
+    /// Counts the plants native to a region by shade and moisture.
+    /// Returns Err if it fails.
+    async fn count_region_plants(&self, id: usize) -> anyhow::Result<RegionPlantCounts>;
+
+    /// Selects one page of the plants native to a region.
+    /// Returns Err if it fails.
+    async fn select_plants_by_region(
+        &self,
+        id: usize,
+        page: &PlantPage,
+    ) -> anyhow::Result<Vec<Plant>>;
+
+    /// Selects the zipcodes in a region whose centroids are within a box
+    /// extending this many degrees from the location in each direction.
+    /// Returns Err if it fails.
+    async fn select_zipcodes_near(
+        &self,
+        location: Coordinates,
+        degrees: f64,
+    ) -> anyhow::Result<Vec<(String, Coordinates)>>;
+
+    /// Selects a Garden with its zones (but not the plants!) by read_id or
+    /// write_id.  A Garden without zones has one, from its own conditions.
+    /// Returns Err if it fails, Ok(None) if not found.
+    async fn select_garden_by_id(
+        &self,
+        id: &str,
+        read_only: bool,
+    ) -> anyhow::Result<Option<Garden>>;
+
+    /// Selects all Gardens (but not their zones or plants!).
+    async fn select_gardens(&self, require_precise_location: bool) -> anyhow::Result<Vec<Garden>>;
+
+    /// Selects the plants in a Garden by read_id or write_id, in order.
+    async fn select_plants_by_garden_id(
+        &self,
+        garden_id: &str,
+        read_only: bool,
+    ) -> anyhow::Result<Vec<GardenPlant>>;
+
+    /// Inserts a Garden (but not the plants!), returning its id.
+    async fn insert_garden(
+        &self,
+        garden: &Garden,
+        read_id: &str,
+        write_id: &str,
+    ) -> anyhow::Result<usize>;
+
+    /// Updates an existing Garden (but not the plants!), keeping its shade
+    /// and moisture when conditions is None.
+    async fn update_garden(
+        &self,
+        write_id: &str,
+        name: &str,
+        conditions: Option<(Shade, Moisture)>,
+    ) -> anyhow::Result<()>;
+
+    /// Replaces all zones in a Garden, numbering them in order.
+    async fn replace_garden_zones(
+        &self,
+        write_id: &str,
+        zones: &[GardenZone],
+    ) -> anyhow::Result<()>;
+
+    /// Replaces all plants in a Garden with the given (plant_id, details), in order.
+    /// The same plant is only kept once in each zone.
+    async fn replace_garden_plants(
+        &self,
+        write_id: &str,
+        plants: Vec<(usize, GardenPlantDetails)>,
+    ) -> anyhow::Result<()>;
+
+    /// Finds up to 10 plants where every word in word_prefix begins a word
+    /// in the plant's scientific or common name.
+    async fn find_plants_by_word_prefix(&self, word_prefix: &str) -> anyhow::Result<Vec<Plant>>;
+
+    /// Increments today's request count for the uri, returning the new count.
+    async fn upsert_request_count(&self, uri: &str) -> anyhow::Result<usize>;
+
+    /// Selects the number of requests for the uri in the current month.
+    async fn select_monthly_request_count(&self, uri: &str) -> anyhow::Result<usize>;
+
+    /// All changesets which define this backend's schema, in order.
+    fn changesets(&self) -> Vec<Changeset>;
+
+    /// Selects the (file, id) of every changeset which has been applied,
+    /// creating the table which tracks them if needed.
+    async fn select_applied_changesets(&self) -> anyhow::Result<HashSet<(String, String)>>;
+
+    /// Executes one changeset and records it as applied.
+    async fn apply_changeset(&self, changeset: &Changeset) -> anyhow::Result<()>;
+}
@@ -37,6 +37,10 @@ pub struct Plant {
     #[serde(skip_serializing)]
     pub deer_resistance_rating: Option<u8>,
 
+    /// The plant's symbol in the USDA PLANTS database, ex: "ASTU"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usda_symbol: Option<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usda_source: Option<String>,
 
@@ -49,6 +53,19 @@ pub struct Plant {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub spread: Option<String>,
 
+    /// Structured versions of bloom, height and spread, parsed from their text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bloom_period: Option<BloomPeriod>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bloom_colors: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height_range: Option<SizeRange>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spread_range: Option<SizeRange>,
+
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub highlights: Vec<Highlight>,
 
@@ -66,11 +83,16 @@ impl Plant {
             bloom: None,
             height: None,
             spread: None,
+            bloom_period: None,
+            bloom_colors: vec![],
+            height_range: None,
+            spread_range: None,
             pollinator_rating: None,
             bird_rating: None,
             spread_rating: None,
             deer_resistance_rating: None,
             image: None,
+            usda_symbol: None,
             usda_source: None,
             wiki_source: None,
             highlights: vec![],
@@ -92,16 +114,416 @@ impl Plant {
             bird_rating: other.bird_rating.or(self.bird_rating),
             spread_rating: other.spread_rating.or(self.spread_rating),
             deer_resistance_rating: other.deer_resistance_rating.or(self.deer_resistance_rating),
+            usda_symbol: other.usda_symbol.clone().or(self.usda_symbol.clone()),
             usda_source: other.usda_source.clone().or(self.usda_source.clone()),
             wiki_source: other.wiki_source.clone().or(self.wiki_source.clone()),
             height: other.height.clone().or(self.height.clone()),
             spread: other.spread.clone().or(self.spread.clone()),
+            bloom_period: other.bloom_period.or(self.bloom_period),
+            bloom_colors: if other.bloom_colors.is_empty() {
+                self.bloom_colors.clone()
+            } else {
+                other.bloom_colors.clone()
+            },
+            height_range: other.height_range.or(self.height_range),
+            spread_range: other.spread_range.or(self.spread_range),
             highlights: other.highlights.clone(),
             done_loading: true,
         }
     }
 }
 
+/// The months a plant blooms, from 1 (January) to 12 (December).  May wrap
+/// around the new year, ex: November to February.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BloomPeriod {
+    pub start_month: u8,
+    pub end_month: u8,
+}
+
+impl BloomPeriod {
+    /// Each month in bloom, in order from start_month.
+    pub fn months(&self) -> Vec<u8> {
+        let mut months = vec![self.start_month];
+        let mut month = self.start_month;
+        while month != self.end_month && months.len() < 12 {
+            month = month % 12 + 1;
+            months.push(month);
+        }
+
+        months
+    }
+}
+
+/// A plant's height or spread, in inches.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeRange {
+    pub min_inches: u32,
+    pub max_inches: u32,
+}
+
+/// Optional filters narrowing a plant search.  Each one only matches plants
+/// with a known value, ex: a plant without a parsed height never matches
+/// max_height_inches.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlantFilters {
+    /// Matches plants blooming during any part of this period
+    pub bloom: Option<BloomPeriod>,
+    pub min_height_inches: Option<u32>,
+    pub max_height_inches: Option<u32>,
+    pub min_pollinator_rating: Option<u8>,
+    pub min_bird_rating: Option<u8>,
+    pub min_deer_resistance_rating: Option<u8>,
+}
+
+/// How plant search results are ordered.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlantSort {
+    /// Best for pollinators and birds first
+    #[default]
+    Wildlife,
+    /// Most deer resistant first
+    DeerResistance,
+    /// By common name
+    Name,
+    /// Earliest bloom first
+    Bloom,
+}
+
+/// Which page of plant search results to select.  Without a limit, every
+/// result after offset is selected.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PlantPage {
+    pub sort: PlantSort,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+impl PlantPage {
+    /// The most plants in one page of results
+    pub const MAX_LIMIT: usize = 200;
+
+    /// Builds a page from optional request params, keeping the limit sane.
+    pub fn new(sort: Option<PlantSort>, offset: Option<usize>, limit: Option<usize>) -> Self {
+        Self {
+            sort: sort.unwrap_or_default(),
+            offset: offset.unwrap_or_default(),
+            limit: limit.map(|limit| limit.clamp(1, Self::MAX_LIMIT)),
+        }
+    }
+}
+
+/// A point on the globe, in degrees.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Coordinates {
+    /// Returns Err if the latitude or longitude is out of range.
+    pub fn new(latitude: f64, longitude: f64) -> anyhow::Result<Self> {
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return Err(anyhow!("invalid location: {latitude}, {longitude}"));
+        }
+
+        Ok(Self {
+            latitude,
+            longitude,
+        })
+    }
+
+    /// Builds Coordinates from optional request parameters, which must be
+    /// given together.  Returns Ok(None) when neither is given.
+    pub fn from_optional(
+        latitude: Option<f64>,
+        longitude: Option<f64>,
+    ) -> anyhow::Result<Option<Self>> {
+        match (latitude, longitude) {
+            (Some(latitude), Some(longitude)) => Self::new(latitude, longitude).map(Some),
+            (None, None) => Ok(None),
+            _ => Err(anyhow!("latitude and longitude are required together")),
+        }
+    }
+
+    /// The distance to another point along the earth's surface, in miles.
+    pub fn distance_miles(&self, other: &Coordinates) -> f64 {
+        const EARTH_RADIUS_MILES: f64 = 3958.8;
+
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let dlat = lat2 - lat1;
+        let dlng = (other.longitude - self.longitude).to_radians();
+
+        // Haversine formula
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlng / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_MILES * a.sqrt().asin()
+    }
+}
+
+/// A zipcode in the database which a search ran in, in place of the zipcode
+/// or location requested.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedZip {
+    pub zip: String,
+
+    /// How far this zipcode's center is from what was requested, or zero
+    /// when the requested zipcode was found.
+    pub distance_miles: f64,
+}
+
+/// An area plants are native to, covering every zipcode within its radius.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Region {
+    pub id: usize,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub radius_miles: usize,
+}
+
+/// How many of a region's plants grow in each shade and moisture.  Plants
+/// with unknown conditions are counted in all of them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionPlantCounts {
+    pub total: usize,
+    pub shades: Vec<ConditionCount<Shade>>,
+    pub moistures: Vec<ConditionCount<Moisture>>,
+}
+
+impl RegionPlantCounts {
+    /// Pairs counts up with conditions, which are ordered None, Some, Lots.
+    pub fn new(total: usize, shades: [usize; 3], moistures: [usize; 3]) -> Self {
+        let shades = [Shade::None, Shade::Some, Shade::Lots]
+            .into_iter()
+            .zip(shades)
+            .map(|(condition, plants)| ConditionCount { condition, plants })
+            .collect();
+        let moistures = [Moisture::None, Moisture::Some, Moisture::Lots]
+            .into_iter()
+            .zip(moistures)
+            .map(|(condition, plants)| ConditionCount { condition, plants })
+            .collect();
+
+        Self {
+            total,
+            shades,
+            moistures,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConditionCount<T> {
+    pub condition: T,
+    pub plants: usize,
+}
+
+/// A region a plant is native to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlantRegion {
+    pub region_id: usize,
+    pub name: String,
+
+    /// Any one zipcode in the region, which is enough to identify it
+    pub zipcode: String,
+
+    /// Why the plant is native here
+    #[serde(flatten)]
+    pub provenance: NativeProvenance,
+}
+
+/// Why a plant is considered native to a region.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeProvenance {
+    pub source: NativeSource,
+    pub confidence: NativeConfidence,
+
+    /// When the plant was added to the region, ex: "2024-03-01".  Older
+    /// links weren't dated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_added: Option<String>,
+}
+
+impl NativeProvenance {
+    /// An undated provenance, as confident as the source usually is.
+    pub fn new(source: NativeSource) -> Self {
+        Self {
+            source,
+            confidence: source.default_confidence(),
+            date_added: None,
+        }
+    }
+}
+
+/// Where the knowledge that a plant is native to a region came from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NativeSource {
+    /// The USDA PLANTS database
+    Usda,
+    /// A state's list of native plants
+    StateList,
+    /// Someone who maintains the plant catalog
+    Curator,
+    /// Generated while answering a plant search
+    Generated,
+}
+
+impl NativeSource {
+    pub fn default_confidence(&self) -> NativeConfidence {
+        match self {
+            NativeSource::Usda | NativeSource::Curator => NativeConfidence::High,
+            NativeSource::StateList => NativeConfidence::Medium,
+            NativeSource::Generated => NativeConfidence::Low,
+        }
+    }
+}
+
+impl Display for NativeSource {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for NativeSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "Usda" => Ok(NativeSource::Usda),
+            "StateList" => Ok(NativeSource::StateList),
+            "Curator" => Ok(NativeSource::Curator),
+            "Generated" => Ok(NativeSource::Generated),
+            _ => Err(anyhow!("can't create NativeSource from {s}")),
+        }
+    }
+}
+
+/// How sure the source is that a plant is native to a region.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NativeConfidence {
+    Low,
+    Medium,
+    High,
+}
+
+impl Display for NativeConfidence {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for NativeConfidence {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "Low" => Ok(NativeConfidence::Low),
+            "Medium" => Ok(NativeConfidence::Medium),
+            "High" => Ok(NativeConfidence::High),
+            _ => Err(anyhow!("can't create NativeConfidence from {s}")),
+        }
+    }
+}
+
+/// Another name a plant is known by.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlantSynonym {
+    pub name: String,
+    pub kind: SynonymKind,
+}
+
+impl PlantSynonym {
+    pub fn new(name: &str, kind: SynonymKind) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SynonymKind {
+    /// An old or alternate scientific name, ex: "Aster novae-angliae"
+    Scientific,
+    /// A common name besides the plant's main one
+    Common,
+}
+
+impl Display for SynonymKind {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for SynonymKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "Scientific" => Ok(SynonymKind::Scientific),
+            "Common" => Ok(SynonymKind::Common),
+            _ => Err(anyhow!("can't create SynonymKind from {s}")),
+        }
+    }
+}
+
+/// One change made to a plant through the admin API, kept as an audit log.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlantChange {
+    pub plant_id: usize,
+
+    /// The name belonging to the API token which made the change
+    pub changed_by: String,
+
+    pub action: PlantChangeAction,
+
+    /// Each field which changed, ex: {"birdRating": {"from": 4, "to": 5}}
+    pub changes: serde_json::Value,
+}
+
+/// Stored in plant_changes the same way the api writes it, ex: "update"
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlantChangeAction {
+    Create,
+    Update,
+}
+
+impl Display for PlantChangeAction {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            PlantChangeAction::Create => write!(f, "create"),
+            PlantChangeAction::Update => write!(f, "update"),
+        }
+    }
+}
+
+impl FromStr for PlantChangeAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "create" => Ok(PlantChangeAction::Create),
+            "update" => Ok(PlantChangeAction::Update),
+            _ => Err(anyhow!("can't create PlantChangeAction from {s}")),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Image {
@@ -134,7 +556,8 @@ impl Image {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+/// Ordered from sunniest to shadiest
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub enum Shade {
     #[serde(rename = "Full Sun")]
     None,
@@ -173,7 +596,8 @@ impl FromStr for Shade {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+/// Ordered from driest to wettest
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub enum Moisture {
     #[serde(rename = "Low")]
     None,
@@ -242,8 +666,8 @@ pub enum HighlightCategory {
 /// it will thrive in.
 #[derive(Serialize)]
 pub struct Garden {
-    /// The plants in this garden
-    pub plants: Vec<Plant>,
+    /// The plants in this garden, with how each is planted
+    pub plants: Vec<GardenPlant>,
 
     /// A short name for this garden, defaulted to something reasonable but changable
     pub name: String,
@@ -254,12 +678,21 @@ pub struct Garden {
     /// The name of the region this garden was created in
     pub region_name: Option<String>,
 
-    /// Shade condition this Garden will thrive in
+    /// The id of the region this garden was created in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region_id: Option<usize>,
+
+    /// Shade condition this Garden will thrive in, or its first zone's
     pub shade: Shade,
 
-    /// Moisture condition this Garden will thrive in
+    /// Moisture condition this Garden will thrive in, or its first zone's
     pub moisture: Moisture,
 
+    /// Areas of this Garden with their own conditions, which plants are
+    /// placed in by index.  Not loaded when listing gardens.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub zones: Vec<GardenZone>,
+
     /// An identifier which allows read-only access to this Garden
     pub read_id: Option<String>,
 
@@ -274,7 +707,7 @@ pub struct Garden {
 }
 
 impl Garden {
-    /// Creates a Garden without plants or region_name
+    /// Creates a Garden without plants or region
     pub fn empty(name: String, zipcode: String, shade: Shade, moisture: Moisture) -> Self {
         Self {
             name,
@@ -282,13 +715,147 @@ impl Garden {
             shade,
             moisture,
             plants: vec![],
+            zones: vec![],
             region_name: None,
+            region_id: None,
             read_id: None,
             write_id: None,
             latitude: None,
             longitude: None,
         }
     }
+
+    /// Uses these zones, or when there are none (like gardens from before
+    /// zones) one zone with the garden's own conditions.
+    pub fn with_zones(self, zones: Vec<GardenZone>) -> Self {
+        let zones = if zones.is_empty() {
+            vec![GardenZone::new(
+                DEFAULT_ZONE_NAME,
+                self.shade,
+                self.moisture,
+            )]
+        } else {
+            zones
+        };
+
+        Self { zones, ..self }
+    }
+
+    /// The zone a plant is in, falling back to the garden's own conditions
+    /// when its zone isn't loaded.
+    pub fn zone_conditions(&self, zone: usize) -> (Shade, Moisture) {
+        match self.zones.get(zone) {
+            Some(zone) => (zone.shade, zone.moisture),
+            None => (self.shade, self.moisture),
+        }
+    }
+}
+
+/// The name of the one zone in a garden which was created without any
+pub const DEFAULT_ZONE_NAME: &str = "Garden";
+
+/// An area of a garden with its own conditions, ex: a shady bed by the house.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GardenZone {
+    pub name: String,
+    pub shade: Shade,
+    pub moisture: Moisture,
+}
+
+impl GardenZone {
+    pub fn new(name: &str, shade: Shade, moisture: Moisture) -> Self {
+        Self {
+            name: name.to_string(),
+            shade,
+            moisture,
+        }
+    }
+}
+
+/// A plant in a garden, with how it is planted there.
+#[derive(Serialize, Debug, Clone)]
+pub struct GardenPlant {
+    #[serde(flatten)]
+    pub plant: Plant,
+
+    #[serde(flatten)]
+    pub details: GardenPlantDetails,
+
+    /// Why it may not do well there, filled in when the garden is read
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<GardenPlantWarning>,
+}
+
+/// Why a plant may not do well where it is in a garden.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GardenPlantWarning {
+    /// It doesn't grow in its zone's shade
+    ShadeMismatch,
+
+    /// It doesn't grow in its zone's moisture
+    MoistureMismatch,
+
+    /// It isn't known to be native to the garden's region
+    NotNative,
+}
+
+/// How a plant is planted in a garden.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GardenPlantDetails {
+    pub quantity: u32,
+    pub notes: Option<String>,
+
+    /// When it was planted, as YYYY-MM-DD
+    pub planted_date: Option<String>,
+    pub status: GardenPlantStatus,
+
+    /// Which of the garden's zones it is in
+    pub zone: usize,
+}
+
+impl Default for GardenPlantDetails {
+    /// One plant, not planted yet, in the first zone
+    fn default() -> Self {
+        Self {
+            quantity: 1,
+            notes: None,
+            planted_date: None,
+            status: GardenPlantStatus::Planned,
+            zone: 0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GardenPlantStatus {
+    #[default]
+    Planned,
+    Planted,
+    Established,
+    Lost,
+}
+
+impl Display for GardenPlantStatus {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for GardenPlantStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "Planned" => Ok(GardenPlantStatus::Planned),
+            "Planted" => Ok(GardenPlantStatus::Planted),
+            "Established" => Ok(GardenPlantStatus::Established),
+            "Lost" => Ok(GardenPlantStatus::Lost),
+            _ => Err(anyhow!("can't create GardenPlantStatus from {s}")),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
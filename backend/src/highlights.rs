@@ -1,61 +1,19 @@
-use std::cmp::Ordering;
-
-use lazy_static::lazy_static;
-
-use crate::domain::{Highlight, HighlightCategory, Moisture, Plant, Shade};
-
-lazy_static! {
-    static ref POLLINATOR_GREAT: Highlight = Highlight {
-        label: "Great for pollinators".to_string(),
-        priority: 1003,
-        category: HighlightCategory::Great
-    };
-    static ref POLLINATOR_GOOD: Highlight = Highlight {
-        label: "Good for pollinators".to_string(),
-        priority: 503,
-        category: HighlightCategory::Good
-    };
-    static ref BIRD_GREAT: Highlight = Highlight {
-        label: "Great for birds".to_string(),
-        priority: 1002,
-        category: HighlightCategory::Great
-    };
-    static ref BIRD_GOOD: Highlight = Highlight {
-        label: "Good for birds".to_string(),
-        priority: 502,
-        category: HighlightCategory::Good
-    };
-    static ref VERY_DEER_RESISTANT: Highlight = Highlight {
-        label: "Deer resistant".to_string(),
-        priority: 2000,
-        category: HighlightCategory::Great
-    };
-    static ref AGGRESSIVE_SPREAD: Highlight = Highlight {
-        label: "Spreads aggressively".to_string(),
-        priority: 3000,
-        category: HighlightCategory::Bad
-    };
-    static ref VERY_AGGRESSIVE_SPREAD: Highlight = Highlight {
-        label: "Spreads aggressively".to_string(),
-        priority: 3000,
-        category: HighlightCategory::Worse
-    };
-    static ref GROWS_IN_SHADE: Highlight = Highlight {
-        label: "Grows in shade".to_string(),
-        priority: 3,
-        category: HighlightCategory::Good
-    };
-    static ref GROWS_IN_PART_SHADE: Highlight = Highlight {
-        label: "Grows in part shade".to_string(),
-        priority: 2,
-        category: HighlightCategory::Good
-    };
-    static ref GROWS_IN_DRY_SOIL: Highlight = Highlight {
-        label: "Grows in dry soil".to_string(),
-        priority: 1,
-        category: HighlightCategory::Good
-    };
-}
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::HashSet,
+    fs,
+};
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{Highlight, HighlightCategory, Moisture, Plant, Shade},
+    plant_details::{parse_bloom, parse_size},
+};
+
+/// The rules used when no config file is given, built into the binary.
+const DEFAULT_RULES: &str = include_str!("../config/highlights.toml");
 
 impl Ord for HighlightCategory {
     fn cmp(&self, other: &Self) -> Ordering {
@@ -80,22 +38,370 @@ impl HighlightCategory {
     }
 }
 
-pub struct Highlights {}
+/// The rules which turn a plant into highlights, see config/highlights.toml.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct HighlightRules {
+    /// How many highlights each plant shows
+    pub max_highlights: usize,
+
+    #[serde(default)]
+    pub ratings: Vec<RatingRule>,
+
+    #[serde(default)]
+    pub blooms: Vec<BloomRule>,
+
+    #[serde(default)]
+    pub sizes: Vec<SizeRule>,
+
+    /// Used only when no rating highlights apply
+    #[serde(default)]
+    pub fillers: Vec<FillerRule>,
+
+    /// Used instead of fillers when the searched conditions are known
+    #[serde(default)]
+    pub conditions: Vec<ConditionRule>,
+}
+
+/// What a rule shows on a plant's card when it applies, flattened into each
+/// rule.  Serde can't deny unknown fields alongside flatten, so a misspelled
+/// field in a rule is ignored rather than rejected.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HighlightLabel {
+    #[serde(rename = "label")]
+    pub text: String,
+    pub category: HighlightCategory,
+    pub priority: usize,
+}
+
+impl HighlightLabel {
+    fn highlight(&self) -> Highlight {
+        Highlight {
+            label: self.text.clone(),
+            category: self.category.clone(),
+            priority: self.priority,
+        }
+    }
+}
+
+/// Highlights a plant when one of its ratings is at least min_rating.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RatingRule {
+    pub rating: RatingField,
+    pub min_rating: u8,
+    #[serde(flatten)]
+    pub label: HighlightLabel,
+}
+
+/// Highlights a plant which grows in one shade or moisture condition.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FillerRule {
+    pub shade: Option<Shade>,
+    pub moisture: Option<Moisture>,
+    #[serde(flatten)]
+    pub label: HighlightLabel,
+}
+
+/// Highlights a plant which blooms in any of these months (1 to 12).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BloomRule {
+    pub months: Vec<u8>,
+    #[serde(flatten)]
+    pub label: HighlightLabel,
+}
+
+/// Highlights a plant whose whole height or spread range is within
+/// min_inches and max_inches.  Either may be left out.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SizeRule {
+    pub dimension: Dimension,
+    pub min_inches: Option<u32>,
+    pub max_inches: Option<u32>,
+    #[serde(flatten)]
+    pub label: HighlightLabel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Dimension {
+    Height,
+    Spread,
+}
+
+/// Highlights a plant which grows in conditions other than the ones searched
+/// for, either one specific shade/moisture or any in a direction.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConditionRule {
+    pub shade: Option<Shade>,
+    pub moisture: Option<Moisture>,
+    pub tolerates: Option<Tolerance>,
+    #[serde(flatten)]
+    pub label: HighlightLabel,
+}
+
+/// A direction away from the searched conditions.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Tolerance {
+    Sunnier,
+    Shadier,
+    Drier,
+    Wetter,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RatingField {
+    PollinatorRating,
+    BirdRating,
+    SpreadRating,
+    DeerResistanceRating,
+}
+
+impl RatingField {
+    pub fn value(&self, plant: &Plant) -> Option<u8> {
+        match self {
+            RatingField::PollinatorRating => plant.pollinator_rating,
+            RatingField::BirdRating => plant.bird_rating,
+            RatingField::SpreadRating => plant.spread_rating,
+            RatingField::DeerResistanceRating => plant.deer_resistance_rating,
+        }
+    }
+}
+
+impl SizeRule {
+    fn matches(&self, plant: &Plant) -> bool {
+        // Plants saved before their size was parsed only have the text
+        let (range, text) = match self.dimension {
+            Dimension::Height => (plant.height_range, &plant.height),
+            Dimension::Spread => (plant.spread_range, &plant.spread),
+        };
+        let Some(size) = range.or_else(|| text.as_deref().and_then(parse_size)) else {
+            return false;
+        };
+
+        self.min_inches.is_none_or(|min| size.min_inches >= min)
+            && self.max_inches.is_none_or(|max| size.max_inches <= max)
+    }
+}
+
+impl ConditionRule {
+    /// Matches conditions beyond all of the searched ones, ex: sunnier than
+    /// the sunniest shade searched for.
+    fn matches(&self, plant: &Plant, shades: &[Shade], moistures: &[Moisture]) -> bool {
+        if let Some(rule_shade) = self.shade {
+            return !shades.contains(&rule_shade) && plant.shades.contains(&rule_shade);
+        }
+        if let Some(rule_moisture) = self.moisture {
+            return !moistures.contains(&rule_moisture) && plant.moistures.contains(&rule_moisture);
+        }
+
+        match self.tolerates {
+            Some(Tolerance::Sunnier) => plant.shades.iter().any(|p| shades.iter().all(|s| p < s)),
+            Some(Tolerance::Shadier) => plant.shades.iter().any(|p| shades.iter().all(|s| p > s)),
+            Some(Tolerance::Drier) => plant
+                .moistures
+                .iter()
+                .any(|p| moistures.iter().all(|m| p < m)),
+            Some(Tolerance::Wetter) => plant
+                .moistures
+                .iter()
+                .any(|p| moistures.iter().all(|m| p > m)),
+            None => false,
+        }
+    }
+}
+
+impl HighlightRules {
+    /// Reads rules from a TOML file, or JSON if the file ends in ".json".
+    pub fn from_file(path: &str) -> anyhow::Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|e| anyhow!("can't read {path}: {e}"))?;
+
+        if path.ends_with(".json") {
+            Self::from_json(&contents)
+        } else {
+            Self::from_toml(&contents)
+        }
+    }
+
+    pub fn from_toml(contents: &str) -> anyhow::Result<Self> {
+        let rules: Self = toml::from_str(contents)?;
+        rules.validate()?;
+
+        Ok(rules)
+    }
+
+    pub fn from_json(contents: &str) -> anyhow::Result<Self> {
+        let rules: Self = serde_json::from_str(contents)?;
+        rules.validate()?;
+
+        Ok(rules)
+    }
+
+    /// The lowest rating which earns a highlight in any of the categories,
+    /// ex: 8 for great pollinator plants.  None if no rule gives one.
+    pub fn min_rating(&self, rating: RatingField, categories: &[HighlightCategory]) -> Option<u8> {
+        self.ratings
+            .iter()
+            .filter(|rule| rule.rating == rating && categories.contains(&rule.label.category))
+            .map(|rule| rule.min_rating)
+            .min()
+    }
+
+    /// Checks for rules which could never work as intended, reporting all
+    /// of them at once.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut errors = vec![];
+
+        if self.max_highlights == 0 {
+            errors.push("max_highlights must be at least 1".to_string());
+        }
+
+        let mut thresholds = HashSet::new();
+        for rule in &self.ratings {
+            if rule.label.text.trim().is_empty() {
+                errors.push(format!("{:?} rule has no label", rule.rating));
+            }
+            if !(1..=10).contains(&rule.min_rating) {
+                errors.push(format!(
+                    "{}: min_rating must be between 1 and 10, was {}",
+                    rule.label.text, rule.min_rating
+                ));
+            }
+            if !thresholds.insert((rule.rating, rule.min_rating)) {
+                errors.push(format!(
+                    "{}: another {:?} rule has min_rating {}",
+                    rule.label.text, rule.rating, rule.min_rating
+                ));
+            }
+        }
+
+        for rule in &self.fillers {
+            if rule.label.text.trim().is_empty() {
+                errors.push("filler has no label".to_string());
+            }
+            if rule.shade.is_some() == rule.moisture.is_some() {
+                errors.push(format!(
+                    "{}: filler needs exactly one of shade or moisture",
+                    rule.label.text
+                ));
+            }
+        }
+
+        for rule in &self.blooms {
+            if rule.label.text.trim().is_empty() {
+                errors.push("bloom rule has no label".to_string());
+            }
+            if rule.months.is_empty() || rule.months.iter().any(|m| !(1..=12).contains(m)) {
+                errors.push(format!(
+                    "{}: months must be between 1 and 12, were {:?}",
+                    rule.label.text, rule.months
+                ));
+            }
+        }
+
+        for rule in &self.sizes {
+            if rule.label.text.trim().is_empty() {
+                errors.push("size rule has no label".to_string());
+            }
+            match (rule.min_inches, rule.max_inches) {
+                (None, None) => errors.push(format!(
+                    "{}: size rule needs min_inches or max_inches",
+                    rule.label.text
+                )),
+                (Some(min), Some(max)) if min > max => errors.push(format!(
+                    "{}: min_inches {min} is more than max_inches {max}",
+                    rule.label.text
+                )),
+                _ => (),
+            }
+        }
+
+        for rule in &self.conditions {
+            if rule.label.text.trim().is_empty() {
+                errors.push("condition has no label".to_string());
+            }
+
+            let kinds = [
+                rule.shade.is_some(),
+                rule.moisture.is_some(),
+                rule.tolerates.is_some(),
+            ];
+            if kinds.iter().filter(|is_set| **is_set).count() != 1 {
+                errors.push(format!(
+                    "{}: condition needs exactly one of shade, moisture or tolerates",
+                    rule.label.text
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("invalid highlight rules: {}", errors.join(", ")))
+        }
+    }
+}
+
+impl Default for HighlightRules {
+    fn default() -> Self {
+        Self::from_toml(DEFAULT_RULES).expect("config/highlights.toml is valid")
+    }
+}
+
+#[derive(Default)]
+pub struct Highlights {
+    rules: HighlightRules,
+}
 
 impl Highlights {
+    pub fn new(rules: HighlightRules) -> Self {
+        Self { rules }
+    }
+
+    pub fn rules(&self) -> &HighlightRules {
+        &self.rules
+    }
+
     pub fn generate(&self, plant: &Plant) -> Vec<Highlight> {
-        let mut highlights = list_highlights(plant);
+        let mut highlights = self.list_highlights(plant);
 
         // If there are no main highlights, try to generate some fillers.
         if highlights.is_empty() {
-            highlights.extend(list_fillers(plant));
+            highlights.extend(self.list_fillers(plant));
         }
 
+        self.keep_top(highlights)
+    }
+
+    /// Like generate, but for a plant found by searching for these conditions.
+    /// Every result grows in at least one of them, so rather than fillers
+    /// which say so, this points out what else the plant tolerates.
+    pub fn generate_for_conditions(
+        &self,
+        plant: &Plant,
+        shades: &[Shade],
+        moistures: &[Moisture],
+    ) -> Vec<Highlight> {
+        let mut highlights = self.list_highlights(plant);
+
+        highlights.extend(
+            self.rules
+                .conditions
+                .iter()
+                .filter(|rule| rule.matches(plant, shades, moistures))
+                .map(|rule| rule.label.highlight()),
+        );
+
+        self.keep_top(highlights)
+    }
+
+    fn keep_top(&self, mut highlights: Vec<Highlight>) -> Vec<Highlight> {
         // Sort the list by priority, with highest priority first.
-        highlights.sort_by(|lhs, rhs| rhs.priority.cmp(&lhs.priority));
+        highlights.sort_by_key(|h| Reverse(h.priority));
 
-        // Keep the top three.
-        highlights.truncate(3);
+        // Keep the top few.
+        highlights.truncate(self.rules.max_highlights);
 
         // Sort by category (good/bad) then priority to move bad items to the end
         highlights.sort_by(|lhs, rhs| {
@@ -109,46 +415,370 @@ impl Highlights {
 
         highlights
     }
+
+    /// Finds the matching rule with the highest min_rating for each rating,
+    /// then any matching bloom and size rules.
+    fn list_highlights(&self, plant: &Plant) -> Vec<Highlight> {
+        let mut best: Vec<&RatingRule> = vec![];
+
+        for rule in &self.rules.ratings {
+            match rule.rating.value(plant) {
+                Some(rating) if rating >= rule.min_rating => (),
+                _ => continue,
+            }
+
+            match best.iter_mut().find(|b| b.rating == rule.rating) {
+                Some(b) if b.min_rating < rule.min_rating => *b = rule,
+                Some(_) => (),
+                None => best.push(rule),
+            }
+        }
+
+        let mut highlights: Vec<Highlight> =
+            best.iter().map(|rule| rule.label.highlight()).collect();
+
+        let bloom = plant
+            .bloom_period
+            .or_else(|| plant.bloom.as_deref().and_then(parse_bloom));
+        if let Some(bloom) = bloom {
+            let months = bloom.months();
+            highlights.extend(
+                self.rules
+                    .blooms
+                    .iter()
+                    .filter(|rule| rule.months.iter().any(|m| months.contains(m)))
+                    .map(|rule| rule.label.highlight()),
+            );
+        }
+
+        highlights.extend(
+            self.rules
+                .sizes
+                .iter()
+                .filter(|rule| rule.matches(plant))
+                .map(|rule| rule.label.highlight()),
+        );
+
+        highlights
+    }
+
+    /// Finds the first matching shade filler and moisture filler.
+    fn list_fillers(&self, plant: &Plant) -> Vec<Highlight> {
+        let shade = self
+            .rules
+            .fillers
+            .iter()
+            .find(|rule| rule.shade.is_some_and(|s| plant.shades.contains(&s)));
+        let moisture = self
+            .rules
+            .fillers
+            .iter()
+            .find(|rule| rule.moisture.is_some_and(|m| plant.moistures.contains(&m)));
+
+        shade
+            .into_iter()
+            .chain(moisture)
+            .map(|rule| rule.label.highlight())
+            .collect()
+    }
 }
 
-fn list_highlights(plant: &Plant) -> Vec<Highlight> {
-    let mut highlights = vec![];
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    match plant.pollinator_rating {
-        Some(rating) if rating >= 8 => highlights.push(POLLINATOR_GREAT.clone()),
-        Some(rating) if rating >= 6 => highlights.push(POLLINATOR_GOOD.clone()),
-        _ => (),
+    fn labels(highlights: Vec<Highlight>) -> Vec<(String, HighlightCategory)> {
+        highlights
+            .into_iter()
+            .map(|h| (h.label, h.category))
+            .collect()
     }
-    match plant.bird_rating {
-        Some(rating) if rating >= 8 => highlights.push(BIRD_GREAT.clone()),
-        Some(rating) if rating >= 6 => highlights.push(BIRD_GOOD.clone()),
-        _ => (),
+
+    #[test]
+    fn test_default_rules() {
+        let highlights = Highlights::default();
+
+        let plant = Plant {
+            pollinator_rating: Some(7),
+            bird_rating: Some(9),
+            spread_rating: Some(8),
+            deer_resistance_rating: Some(3),
+            ..Plant::new("Scientific", "Common")
+        };
+        assert_eq!(
+            labels(highlights.generate(&plant)),
+            vec![
+                ("Great for birds".to_string(), HighlightCategory::Great),
+                ("Good for pollinators".to_string(), HighlightCategory::Good),
+                ("Spreads aggressively".to_string(), HighlightCategory::Worse),
+            ]
+        );
+
+        let plant = Plant {
+            shades: vec![Shade::Some, Shade::Lots],
+            moistures: vec![Moisture::None],
+            ..Plant::new("Scientific", "Common")
+        };
+        assert_eq!(
+            labels(highlights.generate(&plant)),
+            vec![
+                ("Grows in shade".to_string(), HighlightCategory::Good),
+                ("Grows in dry soil".to_string(), HighlightCategory::Good),
+            ]
+        );
     }
-    match plant.spread_rating {
-        Some(rating) if rating >= 8 => highlights.push(VERY_AGGRESSIVE_SPREAD.clone()),
-        Some(rating) if rating >= 6 => highlights.push(AGGRESSIVE_SPREAD.clone()),
-        _ => (),
+
+    #[test]
+    fn test_min_rating() {
+        let rules = HighlightRules::default();
+
+        assert_eq!(
+            rules.min_rating(RatingField::PollinatorRating, &[HighlightCategory::Great]),
+            Some(8)
+        );
+        assert_eq!(
+            rules.min_rating(
+                RatingField::SpreadRating,
+                &[HighlightCategory::Bad, HighlightCategory::Worse]
+            ),
+            Some(6)
+        );
+        assert_eq!(
+            rules.min_rating(RatingField::SpreadRating, &[HighlightCategory::Great]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_json_rules() {
+        let rules = HighlightRules::from_json(
+            r#"{
+                "max_highlights": 1,
+                "ratings": [
+                    {"rating": "bird_rating", "min_rating": 3, "label": "Birds", "category": "good", "priority": 1},
+                    {"rating": "deer_resistance_rating", "min_rating": 5, "label": "Deer", "category": "great", "priority": 2}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let highlights = Highlights::new(rules);
+
+        let plant = Plant {
+            bird_rating: Some(3),
+            deer_resistance_rating: Some(5),
+            ..Plant::new("Scientific", "Common")
+        };
+        assert_eq!(
+            labels(highlights.generate(&plant)),
+            vec![("Deer".to_string(), HighlightCategory::Great)]
+        );
     }
-    match plant.deer_resistance_rating {
-        Some(rating) if rating >= 8 => highlights.push(VERY_DEER_RESISTANT.clone()),
-        _ => (),
+
+    #[test]
+    fn test_invalid_rules() {
+        let error = HighlightRules::from_toml(
+            r#"
+max_highlights = 0
+
+[[ratings]]
+rating = "bird_rating"
+min_rating = 11
+label = "Birds"
+category = "good"
+priority = 1
+
+[[ratings]]
+rating = "bird_rating"
+min_rating = 11
+label = "More birds"
+category = "great"
+priority = 2
+
+[[fillers]]
+shade = "Full Sun"
+moisture = "Low"
+label = "Sunny and dry"
+category = "good"
+priority = 1
+"#,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "invalid highlight rules: max_highlights must be at least 1, \
+             Birds: min_rating must be between 1 and 10, was 11, \
+             More birds: min_rating must be between 1 and 10, was 11, \
+             More birds: another BirdRating rule has min_rating 11, \
+             Sunny and dry: filler needs exactly one of shade or moisture"
+        );
+
+        let error = HighlightRules::from_toml(
+            r#"
+max_highlights = 3
+
+[[ratings]]
+rating = "beauty_rating"
+min_rating = 5
+label = "Pretty"
+category = "good"
+priority = 1
+"#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("beauty_rating"));
     }
 
-    highlights
-}
+    #[test]
+    fn test_generate_for_conditions() {
+        let highlights = Highlights::default();
 
-fn list_fillers(plant: &Plant) -> Vec<Highlight> {
-    let mut fillers = vec![];
+        // Searched for what it needs, so it only tolerates wetter soil
+        let plant = Plant {
+            shades: vec![Shade::Lots],
+            moistures: vec![Moisture::Some, Moisture::Lots],
+            ..Plant::new("Scientific", "Common")
+        };
+        assert_eq!(
+            labels(highlights.generate_for_conditions(&plant, &[Shade::Lots], &[Moisture::Some])),
+            vec![(
+                "Tolerates wetter soil than you need".to_string(),
+                HighlightCategory::Good
+            )]
+        );
 
-    if plant.shades.contains(&Shade::Lots) {
-        fillers.push(GROWS_IN_SHADE.clone())
-    } else if plant.shades.contains(&Shade::Some) {
-        fillers.push(GROWS_IN_PART_SHADE.clone())
+        let plant = Plant {
+            pollinator_rating: Some(9),
+            shades: vec![Shade::None, Shade::Some, Shade::Lots],
+            moistures: vec![Moisture::None, Moisture::Some],
+            ..Plant::new("Scientific", "Common")
+        };
+        assert_eq!(
+            labels(highlights.generate_for_conditions(&plant, &[Shade::Some], &[Moisture::Some])),
+            vec![
+                (
+                    "Great for pollinators".to_string(),
+                    HighlightCategory::Great
+                ),
+                ("Also handles full sun".to_string(), HighlightCategory::Good),
+                (
+                    "Also handles full shade".to_string(),
+                    HighlightCategory::Good
+                ),
+            ]
+        );
+
+        // Full sun was searched for too, so it isn't a highlight
+        assert_eq!(
+            labels(highlights.generate_for_conditions(
+                &plant,
+                &[Shade::None, Shade::Some],
+                &[Moisture::None, Moisture::Some]
+            )),
+            vec![
+                (
+                    "Great for pollinators".to_string(),
+                    HighlightCategory::Great
+                ),
+                (
+                    "Also handles full shade".to_string(),
+                    HighlightCategory::Good
+                ),
+            ]
+        );
     }
 
-    if plant.moistures.contains(&Moisture::None) {
-        fillers.push(GROWS_IN_DRY_SOIL.clone())
+    #[test]
+    fn test_condition_rule_relative_to_search() {
+        let rules = HighlightRules::from_toml(
+            r#"
+max_highlights = 3
+
+[[conditions]]
+tolerates = "sunnier"
+label = "Handles more sun"
+category = "good"
+priority = 1
+
+[[conditions]]
+tolerates = "drier"
+shade = "Full Sun"
+label = "Ambiguous"
+category = "good"
+priority = 1
+"#,
+        );
+        assert_eq!(
+            rules.unwrap_err().to_string(),
+            "invalid highlight rules: Ambiguous: condition needs exactly one of shade, moisture or tolerates"
+        );
+
+        let rules = HighlightRules {
+            conditions: vec![ConditionRule {
+                shade: None,
+                moisture: None,
+                tolerates: Some(Tolerance::Sunnier),
+                label: HighlightLabel {
+                    text: "Handles more sun".to_string(),
+                    category: HighlightCategory::Good,
+                    priority: 1,
+                },
+            }],
+            ..HighlightRules::default()
+        };
+        let highlights = Highlights::new(rules);
+
+        let plant = Plant {
+            shades: vec![Shade::Some, Shade::Lots],
+            ..Plant::new("Scientific", "Common")
+        };
+        assert_eq!(
+            highlights
+                .generate_for_conditions(&plant, &[Shade::Lots], &[Moisture::Some])
+                .len(),
+            1
+        );
+        assert!(highlights
+            .generate_for_conditions(&plant, &[Shade::Some], &[Moisture::Some])
+            .is_empty());
     }
 
-    fillers
+    #[test]
+    fn test_bloom_and_size_highlights() {
+        let highlights = Highlights::default();
+
+        let plant = Plant {
+            bloom: Some("Late summer to fall".to_string()),
+            height: Some("4-6 inches".to_string()),
+            shades: vec![Shade::Lots],
+            ..Plant::new("Scientific", "Common")
+        };
+        assert_eq!(
+            labels(highlights.generate(&plant)),
+            vec![
+                ("Blooms in fall".to_string(), HighlightCategory::Good),
+                (
+                    "Ground cover under 1 ft".to_string(),
+                    HighlightCategory::Good
+                ),
+            ]
+        );
+
+        let plant = Plant {
+            bloom: Some("July".to_string()),
+            height: Some("5-8 feet".to_string()),
+            ..Plant::new("Scientific", "Common")
+        };
+        assert_eq!(
+            labels(highlights.generate(&plant)),
+            vec![("Tall backdrop plant".to_string(), HighlightCategory::Good)]
+        );
+
+        // Only some of the range is tall, so it isn't a backdrop
+        let plant = Plant {
+            height: Some("3-6 feet".to_string()),
+            ..Plant::new("Scientific", "Common")
+        };
+        assert!(highlights.generate(&plant).is_empty());
+    }
 }
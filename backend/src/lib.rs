@@ -1,5 +1,12 @@
 pub mod app;
+pub mod auth;
+pub mod calendar;
+pub mod catalog;
 pub mod controllers;
 pub mod database;
 pub mod domain;
 pub mod highlights;
+pub mod plant_details;
+pub mod search;
+pub mod summary;
+pub mod usda;
@@ -0,0 +1,361 @@
+//! Parses the free text bloom, height and spread fields of a Plant into
+//! structured values.  The text comes from many sources, so these are
+//! lenient and return None rather than guessing when nothing is recognized.
+
+use crate::domain::{BloomPeriod, Plant, SizeRange};
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// Words joining the ends of a range, ex: "June to August" or "2 or 3 feet"
+const RANGE_WORDS: [&str; 6] = ["to", "through", "thru", "until", "and", "or"];
+
+/// Colors a bloom may be described with, and any other words for them.
+const COLORS: [(&str, &[&str]); 12] = [
+    ("white", &[]),
+    ("cream", &["creamy"]),
+    ("yellow", &["gold", "golden"]),
+    ("orange", &[]),
+    ("red", &["crimson", "scarlet", "maroon", "burgundy"]),
+    ("pink", &["rose", "rosy"]),
+    ("magenta", &["fuchsia"]),
+    ("purple", &["violet"]),
+    ("lavender", &["lilac"]),
+    ("blue", &[]),
+    ("green", &["greenish"]),
+    ("brown", &[]),
+];
+
+/// Fills in the structured bloom and size fields from the plant's text.
+/// Fields whose text doesn't parse keep whatever value they had.
+pub fn parse_details(plant: &Plant) -> Plant {
+    let bloom = plant.bloom.as_deref().unwrap_or_default();
+    let bloom_colors = parse_bloom_colors(bloom);
+
+    Plant {
+        bloom_period: parse_bloom(bloom).or(plant.bloom_period),
+        bloom_colors: if bloom_colors.is_empty() {
+            plant.bloom_colors.clone()
+        } else {
+            bloom_colors
+        },
+        height_range: plant
+            .height
+            .as_deref()
+            .and_then(parse_size)
+            .or(plant.height_range),
+        spread_range: plant
+            .spread
+            .as_deref()
+            .and_then(parse_size)
+            .or(plant.spread_range),
+        ..plant.clone()
+    }
+}
+
+/// Parses the colors of a bloom, ex: "Violet to white flowers in May".
+/// Returns each color once, in the order they are first mentioned.
+pub fn parse_bloom_colors(text: &str) -> Vec<String> {
+    let mut colors: Vec<String> = vec![];
+    for word in text
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphabetic())
+    {
+        let color = COLORS
+            .iter()
+            .find(|(name, others)| *name == word || others.contains(&word));
+
+        if let Some((name, _)) = color {
+            if !colors.iter().any(|c| c == name) {
+                colors.push(name.to_string());
+            }
+        }
+    }
+
+    colors
+}
+
+/// Parses when a plant blooms, ex: "June to August", "late spring", "Summer-Fall".
+/// Spans from the first month or season mentioned to the last.
+pub fn parse_bloom(text: &str) -> Option<BloomPeriod> {
+    let text = text.to_lowercase();
+    let mut spans: Vec<(u8, u8)> = vec![];
+
+    let words: Vec<&str> = text
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    // early/mid/late narrow a season down to one of its months
+    let mut modifier: Option<usize> = None;
+    for (index, word) in words.iter().enumerate() {
+        let span = match *word {
+            "early" => {
+                modifier = Some(0);
+                continue;
+            }
+            "mid" | "middle" => {
+                modifier = Some(1);
+                continue;
+            }
+            "late" => {
+                modifier = Some(2);
+                continue;
+            }
+            "spring" => season_span(3, modifier),
+            "summer" => season_span(6, modifier),
+            "fall" | "autumn" => season_span(9, modifier),
+            "winter" => season_span(12, modifier),
+            "may" if !is_may_the_month(&words, index) => continue,
+            _ => match parse_month(word) {
+                Some(month) => (month, month),
+                None => continue,
+            },
+        };
+
+        modifier = None;
+        spans.push(span);
+    }
+
+    let (start_month, _) = *spans.first()?;
+    let (_, end_month) = *spans.last()?;
+    Some(BloomPeriod {
+        start_month,
+        end_month,
+    })
+}
+
+/// Parses a height or spread, ex: "2-3 feet", "12 to 18 inches", "3'".
+/// Numbers without a unit take the next one, as in "2-3 feet".
+pub fn parse_size(text: &str) -> Option<SizeRange> {
+    let mut values: Vec<f64> = vec![];
+    let mut pending: Vec<f64> = vec![];
+
+    let text = text.to_lowercase();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() || c == '.' {
+            let mut number = c.to_string();
+            while let Some(next) = chars.next_if(|n| n.is_ascii_digit() || *n == '.') {
+                number.push(next);
+            }
+
+            // Fractions, ex: "1/2 inch"
+            if chars.next_if_eq(&'/').is_some() {
+                let mut denominator = String::new();
+                while let Some(next) = chars.next_if(|n| n.is_ascii_digit()) {
+                    denominator.push(next);
+                }
+                if let (Ok(n), Ok(d)) = (number.parse::<f64>(), denominator.parse::<f64>()) {
+                    if d > 0.0 {
+                        pending.push(n / d);
+                    }
+                }
+                continue;
+            }
+
+            if let Ok(number) = number.parse() {
+                pending.push(number);
+            }
+        } else if c.is_ascii_alphabetic() || c == '\'' || c == '"' {
+            let mut word = c.to_string();
+            if c.is_ascii_alphabetic() {
+                while let Some(next) = chars.next_if(|n| n.is_ascii_alphabetic()) {
+                    word.push(next);
+                }
+            }
+
+            // Units only count right after a number, so any other word in
+            // between (ex: "3 stems in") means the numbers weren't sizes
+            if let Some(inches_per_unit) = unit_in_inches(&word) {
+                values.extend(pending.drain(..).map(|v| v * inches_per_unit));
+            } else if !RANGE_WORDS.contains(&word.as_str()) {
+                pending.clear();
+            }
+        }
+    }
+
+    let min = values.iter().copied().reduce(f64::min)?;
+    let max = values.iter().copied().reduce(f64::max)?;
+    Some(SizeRange {
+        min_inches: min.round() as u32,
+        max_inches: max.round() as u32,
+    })
+}
+
+/// Matches full or abbreviated month names, ex: "September" or "Sept"
+fn parse_month(word: &str) -> Option<u8> {
+    if word.len() < 3 {
+        return None;
+    }
+
+    MONTHS
+        .iter()
+        .position(|month| word.starts_with(month) && is_month_name(word))
+        .map(|index| index as u8 + 1)
+}
+
+/// Avoids words which only start like a month, ex: "junk" or "mayflower"
+fn is_month_name(word: &str) -> bool {
+    const NAMES: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+
+    NAMES
+        .iter()
+        .any(|name| name.starts_with(word) || (word == "sept" && *name == "september"))
+}
+
+/// Whether "may" at words[index] is the month rather than the verb, ex: "in
+/// May" or "May to June" but not "may bloom again".  It has to be next to a
+/// word about when something blooms.
+fn is_may_the_month(words: &[&str], index: usize) -> bool {
+    const BEFORE: [&str; 8] = ["in", "from", "by", "of", "into", "early", "mid", "late"];
+
+    let is_bloom_word = |word: &str| {
+        RANGE_WORDS.contains(&word)
+            || parse_month(word).is_some()
+            || matches!(word, "spring" | "summer" | "fall" | "autumn" | "winter")
+    };
+
+    let before = index.checked_sub(1).map(|i| words[i]);
+    let after = words.get(index + 1);
+
+    before.is_some_and(|word| BEFORE.contains(&word) || is_bloom_word(word))
+        || after.is_none_or(|word| is_bloom_word(word))
+}
+
+/// The months of a season which starts in first_month, or just one of them
+/// when narrowed by early (0), mid (1) or late (2).
+fn season_span(first_month: u8, modifier: Option<usize>) -> (u8, u8) {
+    let month = |offset: usize| (first_month as usize - 1 + offset) as u8 % 12 + 1;
+
+    match modifier {
+        Some(offset) => (month(offset), month(offset)),
+        None => (month(0), month(2)),
+    }
+}
+
+fn unit_in_inches(word: &str) -> Option<f64> {
+    match word {
+        "'" | "ft" | "foot" | "feet" => Some(12.0),
+        "\"" | "in" | "inch" | "inches" => Some(1.0),
+        "cm" => Some(1.0 / 2.54),
+        "m" | "meter" | "meters" | "metre" | "metres" => Some(100.0 / 2.54),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bloom(text: &str) -> Option<(u8, u8)> {
+        parse_bloom(text).map(|b| (b.start_month, b.end_month))
+    }
+
+    fn size(text: &str) -> Option<(u32, u32)> {
+        parse_size(text).map(|s| (s.min_inches, s.max_inches))
+    }
+
+    #[test]
+    fn test_parse_bloom() {
+        assert_eq!(bloom("June to August"), Some((6, 8)));
+        assert_eq!(bloom("Jun-Aug"), Some((6, 8)));
+        assert_eq!(bloom("Sept - Oct"), Some((9, 10)));
+        assert_eq!(bloom("summer"), Some((6, 8)));
+        assert_eq!(bloom("Late spring to early summer"), Some((5, 6)));
+        assert_eq!(bloom("Summer, Fall"), Some((6, 11)));
+        assert_eq!(bloom("mid-winter"), Some((1, 1)));
+        assert_eq!(bloom("November through February"), Some((11, 2)));
+        assert_eq!(bloom("May"), Some((5, 5)));
+        assert_eq!(bloom("Mayflower junk"), None);
+        assert_eq!(bloom("Blooms in May"), Some((5, 5)));
+        assert_eq!(bloom("early May - June"), Some((5, 6)));
+
+        // "may" the verb isn't a month
+        assert_eq!(bloom("may bloom again"), None);
+        assert_eq!(bloom("May rebloom in fall"), Some((9, 11)));
+        assert_eq!(bloom("It may flower in June"), Some((6, 6)));
+        assert_eq!(bloom("Does not flower"), None);
+        assert_eq!(bloom(""), None);
+    }
+
+    #[test]
+    fn test_parse_bloom_colors() {
+        assert_eq!(parse_bloom_colors("White"), vec!["white"]);
+        assert_eq!(
+            parse_bloom_colors("Violet to white, sometimes lilac"),
+            vec!["purple", "white", "lavender"]
+        );
+        assert_eq!(parse_bloom_colors("Scarlet/crimson flowers"), vec!["red"]);
+        assert_eq!(parse_bloom_colors("Redbud"), Vec::<String>::new());
+        assert_eq!(parse_bloom_colors("June to August"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_parse_details() {
+        let mut plant = Plant::new("Asclepias tuberosa", "Butterfly Weed");
+        plant.bloom = Some("Orange, June to August".to_string());
+        plant.height = Some("1-2 feet".to_string());
+        plant.spread = Some("varies".to_string());
+        plant.spread_range = Some(SizeRange {
+            min_inches: 12,
+            max_inches: 18,
+        });
+
+        let parsed = parse_details(&plant);
+        assert_eq!(
+            parsed.bloom_period,
+            Some(BloomPeriod {
+                start_month: 6,
+                end_month: 8
+            })
+        );
+        assert_eq!(parsed.bloom_colors, vec!["orange"]);
+        assert_eq!(
+            parsed.height_range,
+            Some(SizeRange {
+                min_inches: 12,
+                max_inches: 24
+            })
+        );
+
+        // spread doesn't parse, so it keeps its existing range
+        assert_eq!(parsed.spread_range, plant.spread_range);
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(size("2-3 feet"), Some((24, 36)));
+        assert_eq!(size("12 to 18 inches"), Some((12, 18)));
+        assert_eq!(size("3 ft"), Some((36, 36)));
+        assert_eq!(size("1-2'"), Some((12, 24)));
+        assert_eq!(size("6\" - 1 foot"), Some((6, 12)));
+        assert_eq!(size("1.5 - 2 feet"), Some((18, 24)));
+        assert_eq!(size("1/2 inch"), Some((1, 1)));
+        assert_eq!(size("30-60 cm"), Some((12, 24)));
+        assert_eq!(size("Up to 40 feet tall"), Some((480, 480)));
+        assert_eq!(size("varies"), None);
+        assert_eq!(size("2-3"), None);
+        assert_eq!(size("2 or 3 m"), Some((79, 118)));
+
+        // Units have to come right after a number
+        assert_eq!(size("3 stems in each clump"), None);
+        assert_eq!(size("Spreads in 2 years"), None);
+        assert_eq!(size("5 mm a year"), None);
+        assert_eq!(size("Clumps 2 to 3 feet wide in time"), Some((24, 36)));
+    }
+}
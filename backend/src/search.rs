@@ -0,0 +1,418 @@
+//! Finds plants by name while typing, tolerating typos like "milkweeed".
+//! Every word of the query must begin a word of one of the plant's names,
+//! and the names are ranked so common names come before scientific ones.
+
+use std::{cmp::Ordering, sync::RwLock};
+
+use serde::Serialize;
+
+use crate::domain::{Plant, PlantSynonym, SynonymKind};
+
+/// Which of a plant's names matched a search, from most to least preferred.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum NameField {
+    Common,
+    CommonSynonym,
+    Scientific,
+    ScientificSynonym,
+}
+
+impl From<SynonymKind> for NameField {
+    fn from(kind: SynonymKind) -> Self {
+        match kind {
+            SynonymKind::Common => NameField::CommonSynonym,
+            SynonymKind::Scientific => NameField::ScientificSynonym,
+        }
+    }
+}
+
+/// The name a search matched, and which parts of it matched.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NameMatch {
+    pub field: NameField,
+    pub name: String,
+
+    /// Character ranges of name which matched, as [start, end)
+    pub ranges: Vec<[usize; 2]>,
+
+    /// How many letters had to be changed for it to match
+    #[serde(skip)]
+    pub typos: usize,
+}
+
+impl NameMatch {
+    /// Fewer typos first, then common names, then shorter (closer) names.
+    fn rank(&self, other: &NameMatch) -> Ordering {
+        self.typos
+            .cmp(&other.typos)
+            .then(self.field.cmp(&other.field))
+            .then(self.name.chars().count().cmp(&other.name.chars().count()))
+            .then_with(|| self.name.cmp(&other.name))
+    }
+}
+
+/// A plant found by name, with why it was found.
+#[derive(Serialize, Debug, Clone)]
+pub struct PlantNameMatch {
+    #[serde(flatten)]
+    pub plant: Plant,
+    pub matched: NameMatch,
+}
+
+struct IndexedPlant {
+    plant: Plant,
+    names: Vec<(NameField, String)>,
+}
+
+impl IndexedPlant {
+    fn new(plant: Plant, synonyms: &[PlantSynonym]) -> Self {
+        let mut indexed = Self {
+            names: vec![
+                (NameField::Common, plant.common.clone()),
+                (NameField::Scientific, plant.scientific.clone()),
+            ],
+            plant,
+        };
+        indexed.add_synonyms(synonyms);
+        indexed
+    }
+
+    fn add_synonyms(&mut self, synonyms: &[PlantSynonym]) {
+        for synonym in synonyms {
+            let name = (synonym.kind.into(), synonym.name.clone());
+            if !self.names.contains(&name) {
+                self.names.push(name);
+            }
+        }
+    }
+
+    /// The best matching name, if any of them match.
+    fn best_match(&self, query: &[Vec<char>]) -> Option<NameMatch> {
+        self.names
+            .iter()
+            .filter_map(|(field, name)| match_name(query, *field, name))
+            .min_by(NameMatch::rank)
+    }
+}
+
+/// Every plant's names, held in memory so searches don't need the database.
+/// It is empty until loaded, and then kept current as plants are saved.
+/// Plants saved by other processes show up once it is loaded again.
+#[derive(Default)]
+pub struct PlantSearchIndex {
+    plants: RwLock<Option<Vec<IndexedPlant>>>,
+}
+
+impl PlantSearchIndex {
+    /// Builds a loaded index from plants and their synonyms.
+    pub fn from_plants(plants: Vec<(Plant, Vec<PlantSynonym>)>) -> Self {
+        let index = Self::default();
+        index.replace(plants);
+        index
+    }
+
+    /// Replaces everything in the index, which is loaded afterwards.
+    pub fn replace(&self, plants: Vec<(Plant, Vec<PlantSynonym>)>) {
+        let plants = plants
+            .into_iter()
+            .map(|(plant, synonyms)| IndexedPlant::new(plant, &synonyms))
+            .collect();
+        *self.plants.write().expect("search index lock poisoned") = Some(plants);
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.plants
+            .read()
+            .expect("search index lock poisoned")
+            .is_some()
+    }
+
+    /// Adds or updates a saved plant, keeping the synonyms it had.  Does
+    /// nothing until the index is loaded, as loading will include it.
+    pub fn upsert(&self, plant: &Plant) {
+        let mut guard = self.plants.write().expect("search index lock poisoned");
+        let (Some(plants), Some(id)) = (guard.as_mut(), plant.id) else {
+            return;
+        };
+
+        match plants.iter_mut().find(|p| p.plant.id == Some(id)) {
+            Some(existing) => {
+                let synonyms = existing.names.split_off(2);
+                *existing = IndexedPlant {
+                    names: vec![
+                        (NameField::Common, plant.common.clone()),
+                        (NameField::Scientific, plant.scientific.clone()),
+                    ],
+                    plant: plant.clone(),
+                };
+                existing.names.extend(synonyms);
+            }
+            None => plants.push(IndexedPlant::new(plant.clone(), &[])),
+        }
+    }
+
+    /// Adds more names to an indexed plant.
+    pub fn add_synonyms(&self, plant_id: usize, synonyms: &[PlantSynonym]) {
+        let mut guard = self.plants.write().expect("search index lock poisoned");
+        let Some(plants) = guard.as_mut() else {
+            return;
+        };
+
+        if let Some(existing) = plants.iter_mut().find(|p| p.plant.id == Some(plant_id)) {
+            existing.add_synonyms(synonyms);
+        }
+    }
+
+    /// Finds up to limit plants where every word in the query begins a word
+    /// in one of their names, allowing a few typos in longer words.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<PlantNameMatch> {
+        let query: Vec<Vec<char>> = words(query)
+            .into_iter()
+            .map(|(_, word)| word.chars().collect())
+            .collect();
+        if query.is_empty() {
+            return vec![];
+        }
+
+        let guard = self.plants.read().expect("search index lock poisoned");
+        let Some(plants) = guard.as_ref() else {
+            return vec![];
+        };
+
+        let mut matches: Vec<PlantNameMatch> = plants
+            .iter()
+            .filter_map(|indexed| {
+                Some(PlantNameMatch {
+                    matched: indexed.best_match(&query)?,
+                    plant: indexed.plant.clone(),
+                })
+            })
+            .collect();
+
+        matches.sort_by(|a, b| a.matched.rank(&b.matched));
+        matches.truncate(limit);
+        matches
+    }
+}
+
+/// Matches every query word to the start of a word in the name, returning
+/// None if any of them can't be matched within its typo allowance.
+fn match_name(query: &[Vec<char>], field: NameField, name: &str) -> Option<NameMatch> {
+    let name_words: Vec<(usize, Vec<char>)> = words(name)
+        .into_iter()
+        .map(|(start, word)| (start, word.chars().collect()))
+        .collect();
+
+    let mut typos = 0;
+    let mut ranges: Vec<[usize; 2]> = vec![];
+    for query_word in query {
+        let (distance, start, length) = name_words
+            .iter()
+            .filter_map(|(start, word)| {
+                let (distance, length) = prefix_distance(query_word, word)?;
+                Some((distance, *start, length))
+            })
+            .min_by_key(|(distance, start, _)| (*distance, *start))?;
+
+        if distance > allowed_typos(query_word.len()) {
+            return None;
+        }
+
+        typos += distance;
+        ranges.push([start, start + length]);
+    }
+
+    Some(NameMatch {
+        field,
+        name: name.to_string(),
+        ranges: merge_ranges(ranges),
+        typos,
+    })
+}
+
+/// Short words must be typed exactly, longer words can have a typo or two.
+fn allowed_typos(length: usize) -> usize {
+    match length {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// The fewest edits (insert, delete, substitute or swap neighbors) to turn
+/// query into the start of word, and how long that start is.  The first
+/// letter must match, as it is rarely the one mistyped.
+fn prefix_distance(query: &[char], word: &[char]) -> Option<(usize, usize)> {
+    if query.first() != word.first() {
+        return None;
+    }
+
+    // distances[i][j] is the edits between query[..i] and word[..j]
+    let mut distances = vec![vec![0; word.len() + 1]; query.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=query.len() {
+        for j in 1..=word.len() {
+            let substitution = usize::from(query[i - 1] != word[j - 1]);
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + substitution);
+
+            if i > 1 && j > 1 && query[i - 1] == word[j - 2] && query[i - 2] == word[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+
+            distances[i][j] = distance;
+        }
+    }
+
+    // The shortest start of the word with the fewest edits
+    distances[query.len()]
+        .iter()
+        .enumerate()
+        .skip(1)
+        .min_by_key(|(length, distance)| (**distance, *length))
+        .map(|(length, distance)| (*distance, length))
+}
+
+/// Joins ranges which overlap or touch, in order.
+fn merge_ranges(mut ranges: Vec<[usize; 2]>) -> Vec<[usize; 2]> {
+    ranges.sort();
+
+    let mut merged: Vec<[usize; 2]> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range[0] <= last[1] => last[1] = last[1].max(range[1]),
+            _ => merged.push(range),
+        }
+    }
+
+    merged
+}
+
+/// The lowercase words of a name with the character each starts at.
+/// Hyphens and symbols separate words, ex: "novae-angliae" or "×collina".
+fn words(name: &str) -> Vec<(usize, String)> {
+    let mut words: Vec<(usize, String)> = vec![];
+    let mut in_word = false;
+    for (index, c) in name.chars().enumerate() {
+        if !c.is_alphanumeric() {
+            in_word = false;
+            continue;
+        }
+
+        if !in_word {
+            words.push((index, String::new()));
+            in_word = true;
+        }
+        if let Some((_, word)) = words.last_mut() {
+            word.extend(c.to_lowercase());
+        }
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plant(id: usize, scientific: &str, common: &str) -> Plant {
+        Plant {
+            id: Some(id),
+            ..Plant::new(scientific, common)
+        }
+    }
+
+    fn index() -> PlantSearchIndex {
+        PlantSearchIndex::from_plants(vec![
+            (plant(1, "Asclepias syriaca", "Common Milkweed"), vec![]),
+            (plant(2, "Asclepias incarnata", "Swamp Milkweed"), vec![]),
+            (
+                plant(3, "Symphyotrichum novae-angliae", "New England Aster"),
+                vec![PlantSynonym::new(
+                    "Aster novae-angliae",
+                    SynonymKind::Scientific,
+                )],
+            ),
+            (plant(4, "Milium effusum", "Wood Millet"), vec![]),
+        ])
+    }
+
+    fn found(matches: &[PlantNameMatch]) -> Vec<usize> {
+        matches.iter().filter_map(|m| m.plant.id).collect()
+    }
+
+    #[test]
+    fn test_search_tolerates_typos() {
+        let index = index();
+
+        assert_eq!(found(&index.search("milkweeed", 10)), vec![2, 1]);
+        assert_eq!(found(&index.search("swmap milk", 10)), vec![2]);
+        assert_eq!(found(&index.search("mi", 10)), vec![4, 2, 1]);
+        assert_eq!(found(&index.search("aslcepias", 10)), vec![1, 2]);
+        assert_eq!(found(&index.search("mi", 2)), vec![4, 2]);
+
+        // Short words and first letters must be exact
+        assert_eq!(found(&index.search("mx", 10)), Vec::<usize>::new());
+        assert_eq!(found(&index.search("nilkweed", 10)), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_search_ranks_common_names_first() {
+        let index = index();
+
+        // "Aster" is the common name of one, and a synonym of the other
+        let matches = index.search("aster", 10);
+        assert_eq!(found(&matches), vec![3]);
+        assert_eq!(matches[0].matched.field, NameField::Common);
+
+        let matches = index.search("aster nov", 10);
+        assert_eq!(matches[0].matched.field, NameField::ScientificSynonym);
+        assert_eq!(matches[0].matched.name, "Aster novae-angliae");
+
+        let matches = index.search("milium", 10);
+        assert_eq!(found(&matches), vec![4]);
+        assert_eq!(matches[0].matched.field, NameField::Scientific);
+
+        // Every word must match within the same name
+        let matches = index.search("asclepias milkweed", 10);
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn test_search_highlights_matches() {
+        let index = index();
+
+        let matches = index.search("milkweeed com", 10);
+        assert_eq!(matches[0].matched.name, "Common Milkweed");
+        assert_eq!(matches[0].matched.ranges, vec![[0, 3], [7, 15]]);
+
+        let matches = index.search("angl", 1);
+        assert_eq!(matches[0].matched.name, "Symphyotrichum novae-angliae");
+        assert_eq!(matches[0].matched.ranges, vec![[21, 25]]);
+    }
+
+    #[test]
+    fn test_upsert_and_add_synonyms() {
+        let index = PlantSearchIndex::default();
+        index.upsert(&plant(1, "Asclepias syriaca", "Common Milkweed"));
+        assert!(!index.is_loaded());
+        assert!(index.search("milkweed", 10).is_empty());
+
+        let index = PlantSearchIndex::from_plants(vec![]);
+        index.upsert(&plant(1, "Asclepias syriaca", "Common Milkweed"));
+        index.add_synonyms(1, &[PlantSynonym::new("Silkweed", SynonymKind::Common)]);
+        index.upsert(&plant(1, "Asclepias syriaca", "Milkweed"));
+
+        assert_eq!(index.search("milk", 10)[0].plant.common, "Milkweed");
+        assert_eq!(found(&index.search("silk", 10)), vec![1]);
+    }
+}
@@ -0,0 +1,334 @@
+//! Sums up what a garden as a whole offers wildlife, from the ratings of the
+//! plants in it.  Each plant's highlights say the same things one at a time,
+//! so the same rules decide what counts as great.
+
+use std::{cmp::Reverse, collections::HashSet};
+
+use serde::Serialize;
+
+use crate::{
+    calendar::{bloom_calendar, is_same_plant},
+    domain::{Garden, GardenPlantStatus, HighlightCategory, Plant},
+    highlights::{HighlightRules, RatingField},
+};
+
+/// What a garden offers wildlife, and what to watch out for.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GardenSummary {
+    /// How many kinds of plants are in the garden, not counting lost ones
+    pub plant_count: usize,
+
+    /// How many plants there are, adding up each one's quantity
+    pub total_quantity: u64,
+
+    pub pollinator_rating: RatingSummary,
+    pub bird_rating: RatingSummary,
+    pub deer_resistance_rating: RatingSummary,
+    pub spread_rating: RatingSummary,
+
+    /// How many kinds of plants have a great pollinator highlight
+    pub great_for_pollinators: usize,
+
+    /// How many kinds of plants have a great bird highlight
+    pub great_for_birds: usize,
+
+    /// How many kinds of plants have a great deer resistance highlight
+    pub deer_resistant: usize,
+
+    /// Plants with a bad or worse spread highlight, most aggressive first
+    pub aggressive_spreaders: Vec<SpreaderPlant>,
+
+    pub diversity: Diversity,
+}
+
+/// One rating across the plants in a garden.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RatingSummary {
+    /// The average of the plants with this rating, from 1 to 10
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average: Option<f64>,
+
+    /// How many plants have this rating, the rest are unknown
+    pub rated: usize,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SpreaderPlant {
+    pub id: Option<usize>,
+    pub common: String,
+    pub scientific: String,
+    pub spread_rating: u8,
+}
+
+/// How varied the plants in a garden are.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Diversity {
+    /// How many genera the plants belong to, ex: two milkweeds are one genus
+    pub genera: usize,
+
+    /// How many different colors the plants bloom in
+    pub bloom_colors: usize,
+
+    /// How many months of the year something is blooming
+    pub bloom_months: usize,
+
+    /// The Shannon index of the plants' quantities, which is 0 for a garden
+    /// of one kind of plant and grows with more kinds in even numbers
+    pub shannon_index: f64,
+}
+
+/// Summarizes a garden's plants, judging their ratings by the highlight
+/// rules.  A plant in several zones counts once, with its quantities added
+/// together, and lost plants are left out.
+pub fn garden_summary(garden: &Garden, rules: &HighlightRules) -> GardenSummary {
+    let mut plants: Vec<(&Plant, u64)> = vec![];
+    for garden_plant in &garden.plants {
+        if garden_plant.details.status == GardenPlantStatus::Lost {
+            continue;
+        }
+
+        let plant = &garden_plant.plant;
+        let quantity = u64::from(garden_plant.details.quantity);
+        match plants.iter_mut().find(|(p, _)| is_same_plant(p, plant)) {
+            Some((_, total)) => *total += quantity,
+            None => plants.push((plant, quantity)),
+        }
+    }
+
+    let ratings = |rating: RatingField| -> Vec<u8> {
+        plants.iter().filter_map(|(p, _)| rating.value(p)).collect()
+    };
+    let count_great = |rating: RatingField| -> usize {
+        let Some(great) = rules.min_rating(rating, &[HighlightCategory::Great]) else {
+            return 0;
+        };
+        ratings(rating).into_iter().filter(|r| *r >= great).count()
+    };
+
+    let aggressive = rules.min_rating(
+        RatingField::SpreadRating,
+        &[HighlightCategory::Bad, HighlightCategory::Worse],
+    );
+    let mut aggressive_spreaders: Vec<SpreaderPlant> = plants
+        .iter()
+        .filter_map(|(p, _)| {
+            let spread_rating = p.spread_rating?;
+            (spread_rating >= aggressive?).then(|| SpreaderPlant {
+                id: p.id,
+                common: p.common.clone(),
+                scientific: p.scientific.clone(),
+                spread_rating,
+            })
+        })
+        .collect();
+    aggressive_spreaders.sort_by_key(|p| Reverse(p.spread_rating));
+
+    GardenSummary {
+        plant_count: plants.len(),
+        total_quantity: plants.iter().map(|(_, quantity)| quantity).sum(),
+        pollinator_rating: summarize(&ratings(RatingField::PollinatorRating)),
+        bird_rating: summarize(&ratings(RatingField::BirdRating)),
+        deer_resistance_rating: summarize(&ratings(RatingField::DeerResistanceRating)),
+        spread_rating: summarize(&ratings(RatingField::SpreadRating)),
+        great_for_pollinators: count_great(RatingField::PollinatorRating),
+        great_for_birds: count_great(RatingField::BirdRating),
+        deer_resistant: count_great(RatingField::DeerResistanceRating),
+        aggressive_spreaders,
+        diversity: diversity(garden, &plants),
+    }
+}
+
+fn summarize(ratings: &[u8]) -> RatingSummary {
+    let total: u32 = ratings.iter().map(|r| *r as u32).sum();
+    RatingSummary {
+        average: (!ratings.is_empty()).then(|| total as f64 / ratings.len() as f64),
+        rated: ratings.len(),
+    }
+}
+
+fn diversity(garden: &Garden, plants: &[(&Plant, u64)]) -> Diversity {
+    let genera: HashSet<String> = plants
+        .iter()
+        .filter_map(|(p, _)| p.scientific.split_whitespace().next())
+        .map(str::to_lowercase)
+        .collect();
+
+    let bloom_colors: HashSet<&String> = plants
+        .iter()
+        .flat_map(|(p, _)| p.bloom_colors.iter())
+        .collect();
+
+    let bloom_months = bloom_calendar(garden)
+        .months
+        .iter()
+        .filter(|month| !month.plants.is_empty())
+        .count();
+
+    let total: u64 = plants.iter().map(|(_, quantity)| quantity).sum();
+    let shannon_index = plants
+        .iter()
+        .filter(|(_, quantity)| *quantity > 0)
+        .map(|(_, quantity)| {
+            let share = *quantity as f64 / total as f64;
+            share * (1.0 / share).ln()
+        })
+        .sum::<f64>();
+
+    Diversity {
+        genera: genera.len(),
+        bloom_colors: bloom_colors.len(),
+        bloom_months,
+        shannon_index,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{GardenPlant, GardenPlantDetails, Moisture, Shade};
+
+    fn garden_plant(id: usize, scientific: &str, quantity: u32) -> GardenPlant {
+        let mut plant = Plant::new(scientific, &format!("Plant {id}"));
+        plant.id = Some(id);
+
+        GardenPlant {
+            plant,
+            details: GardenPlantDetails {
+                quantity,
+                ..GardenPlantDetails::default()
+            },
+            warnings: vec![],
+        }
+    }
+
+    #[test]
+    fn test_garden_summary() {
+        let mut butterfly_weed = garden_plant(1, "Asclepias tuberosa", 3);
+        butterfly_weed.plant.pollinator_rating = Some(9);
+        butterfly_weed.plant.deer_resistance_rating = Some(8);
+        butterfly_weed.plant.bloom = Some("Orange flowers in July".to_string());
+        butterfly_weed.plant.bloom_colors = vec!["orange".to_string()];
+
+        let mut in_another_zone = butterfly_weed.clone();
+        in_another_zone.details.zone = 1;
+
+        let mut swamp_milkweed = garden_plant(2, "Asclepias incarnata", 2);
+        swamp_milkweed.plant.pollinator_rating = Some(6);
+        swamp_milkweed.plant.spread_rating = Some(6);
+
+        let mut mint = garden_plant(3, "Pycnanthemum virginianum", 1);
+        mint.plant.spread_rating = Some(9);
+        mint.plant.bird_rating = Some(8);
+
+        let mut lost = garden_plant(4, "Aster laevis", 10);
+        lost.plant.pollinator_rating = Some(10);
+        lost.details.status = GardenPlantStatus::Lost;
+
+        let mut garden = Garden::empty(
+            "name".to_string(),
+            "43081".to_string(),
+            Shade::None,
+            Moisture::Some,
+        );
+        garden.plants = vec![butterfly_weed, in_another_zone, swamp_milkweed, mint, lost];
+
+        let summary = garden_summary(&garden, &HighlightRules::default());
+
+        assert_eq!(summary.plant_count, 3);
+        assert_eq!(summary.total_quantity, 9);
+        assert_eq!(
+            summary.pollinator_rating,
+            RatingSummary {
+                average: Some(7.5),
+                rated: 2
+            }
+        );
+        assert_eq!(
+            summary.bird_rating,
+            RatingSummary {
+                average: Some(8.0),
+                rated: 1
+            }
+        );
+        assert_eq!(summary.great_for_pollinators, 1);
+        assert_eq!(summary.great_for_birds, 1);
+        assert_eq!(summary.deer_resistant, 1);
+        assert_eq!(
+            summary
+                .aggressive_spreaders
+                .iter()
+                .map(|p| (p.id, p.spread_rating))
+                .collect::<Vec<_>>(),
+            vec![(Some(3), 9), (Some(2), 6)]
+        );
+
+        assert_eq!(summary.diversity.genera, 2);
+        assert_eq!(summary.diversity.bloom_colors, 1);
+        assert_eq!(summary.diversity.bloom_months, 1);
+
+        // Quantities of 6, 2 and 1
+        let expected: f64 = [6.0, 2.0, 1.0]
+            .iter()
+            .map(|quantity: &f64| -(quantity / 9.0) * (quantity / 9.0).ln())
+            .sum();
+        assert!((summary.diversity.shannon_index - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_garden_summary_follows_highlight_rules() {
+        let rules = HighlightRules::from_toml(
+            r#"
+max_highlights = 3
+
+[[ratings]]
+rating = "pollinator_rating"
+min_rating = 5
+label = "Great for pollinators"
+category = "great"
+priority = 1
+"#,
+        )
+        .unwrap();
+
+        let mut aster = garden_plant(1, "Symphyotrichum laeve", u32::MAX);
+        aster.plant.pollinator_rating = Some(5);
+        aster.plant.spread_rating = Some(10);
+        let mut goldenrod = garden_plant(2, "Solidago speciosa", u32::MAX);
+        goldenrod.plant.bird_rating = Some(10);
+
+        let mut garden = Garden::empty(
+            "name".to_string(),
+            "43081".to_string(),
+            Shade::None,
+            Moisture::Some,
+        );
+        garden.plants = vec![aster, goldenrod];
+
+        let summary = garden_summary(&garden, &rules);
+        assert_eq!(summary.total_quantity, 2 * u32::MAX as u64);
+        assert_eq!(summary.great_for_pollinators, 1);
+
+        // Without rules for them, nothing is great for birds or spreads aggressively
+        assert_eq!(summary.great_for_birds, 0);
+        assert!(summary.aggressive_spreaders.is_empty());
+    }
+
+    #[test]
+    fn test_empty_garden_summary() {
+        let garden = Garden::empty(
+            "name".to_string(),
+            "43081".to_string(),
+            Shade::None,
+            Moisture::Some,
+        );
+
+        let summary = garden_summary(&garden, &HighlightRules::default());
+        assert_eq!(summary.plant_count, 0);
+        assert_eq!(summary.pollinator_rating.average, None);
+        assert_eq!(summary.diversity.shannon_index, 0.0);
+    }
+}
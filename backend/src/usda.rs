@@ -0,0 +1,215 @@
+//! Looks up plants in the USDA PLANTS database by scientific name.  The
+//! symbols in resources/usda_symbols.json are keyed by lowercase "genus
+//! species", and synonyms share the accepted name's symbol, ex: both
+//! "aster novae-angliae" and "symphyotrichum novae-angliae" are SYNO2.
+
+use std::{collections::HashMap, sync::OnceLock};
+
+use crate::domain::Plant;
+
+/// The symbols used when no other file is given, built into the binary.
+const DEFAULT_SYMBOLS: &str = include_str!("../resources/usda_symbols.json");
+
+pub struct UsdaSymbols {
+    by_name: HashMap<String, String>,
+
+    /// Every name sharing each symbol, sorted
+    by_symbol: HashMap<String, Vec<String>>,
+}
+
+impl UsdaSymbols {
+    pub fn parse(json: &str) -> anyhow::Result<Self> {
+        let by_name: HashMap<String, String> = serde_json::from_str(json)?;
+
+        let mut by_symbol: HashMap<String, Vec<String>> = HashMap::new();
+        for (name, symbol) in &by_name {
+            by_symbol
+                .entry(symbol.clone())
+                .or_default()
+                .push(name.clone());
+        }
+        for names in by_symbol.values_mut() {
+            names.sort();
+        }
+
+        Ok(Self { by_name, by_symbol })
+    }
+
+    /// The symbols built into the binary, parsed the first time they're used.
+    pub fn builtin() -> &'static UsdaSymbols {
+        static SYMBOLS: OnceLock<UsdaSymbols> = OnceLock::new();
+        SYMBOLS.get_or_init(|| {
+            UsdaSymbols::parse(DEFAULT_SYMBOLS).expect("usda_symbols.json should be valid")
+        })
+    }
+
+    /// Finds the symbol for a scientific name, or one of its synonyms.
+    /// Varieties and subspecies use their species' symbol.
+    pub fn lookup(&self, scientific: &str) -> Option<&str> {
+        self.by_name
+            .get(&species_key(scientific)?)
+            .map(String::as_str)
+    }
+
+    /// The other scientific names sharing the plant's symbol, ex: "Aster
+    /// novae-angliae" for "Symphyotrichum novae-angliae".  Empty when the
+    /// plant has no symbol.
+    pub fn synonyms(&self, plant: &Plant) -> Vec<String> {
+        let Some(names) = plant
+            .usda_symbol
+            .as_ref()
+            .and_then(|symbol| self.by_symbol.get(symbol))
+        else {
+            return vec![];
+        };
+
+        let own_key = species_key(&plant.scientific);
+        names
+            .iter()
+            .filter(|name| Some(*name) != own_key.as_ref())
+            .map(|name| capitalize_genus(name))
+            .collect()
+    }
+
+    /// Fills in the plant's symbol and usda_source when they're missing.
+    /// A usda_source which already names a symbol is trusted over a lookup.
+    pub fn fill_in(&self, plant: &Plant) -> Plant {
+        let usda_symbol = plant
+            .usda_symbol
+            .clone()
+            .or_else(|| plant.usda_source.as_deref().and_then(symbol_from_url))
+            .or_else(|| self.lookup(&plant.scientific).map(str::to_string));
+
+        let usda_source = plant
+            .usda_source
+            .clone()
+            .or_else(|| usda_symbol.as_deref().map(profile_url));
+
+        Plant {
+            usda_symbol,
+            usda_source,
+            ..plant.clone()
+        }
+    }
+}
+
+/// The plant's page on the USDA PLANTS website
+pub fn profile_url(symbol: &str) -> String {
+    format!("https://plants.usda.gov/home/plantProfile?symbol={symbol}")
+}
+
+/// Reads the symbol from a profile url, ex: "...plantProfile?symbol=ASTU"
+fn symbol_from_url(url: &str) -> Option<String> {
+    let (_, query) = url.split_once('?')?;
+    query
+        .split('&')
+        .find_map(|param| param.strip_prefix("symbol="))
+        .filter(|symbol| !symbol.is_empty())
+        .map(str::to_uppercase)
+}
+
+/// Writes a lowercase key as a scientific name, ex: "Aster novae-angliae"
+fn capitalize_genus(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Reduces a scientific name to how the symbols are keyed, ex:
+/// "Rudbeckia hirta var. pulcherrima" is "rudbeckia hirta" and the hybrid
+/// "Carya x collina" is "carya ×collina".
+fn species_key(scientific: &str) -> Option<String> {
+    let lowercase = scientific.to_lowercase();
+    let mut words = lowercase.split_whitespace();
+
+    let genus = words.next()?;
+    let species = match words.next()? {
+        "x" | "×" => format!("×{}", words.next()?),
+        species => species.to_string(),
+    };
+
+    Some(format!("{genus} {species}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbols() -> UsdaSymbols {
+        UsdaSymbols::parse(
+            r#"{
+                "asclepias tuberosa": "ASTU",
+                "aster novae-angliae": "SYNO2",
+                "symphyotrichum novae-angliae": "SYNO2",
+                "carya ×collina": "CACO15"
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_lookup() {
+        let symbols = symbols();
+
+        assert_eq!(symbols.lookup("Asclepias tuberosa"), Some("ASTU"));
+        assert_eq!(symbols.lookup("  asclepias   TUBEROSA "), Some("ASTU"));
+        assert_eq!(symbols.lookup("Aster novae-angliae"), Some("SYNO2"));
+        assert_eq!(
+            symbols.lookup("Asclepias tuberosa ssp. interior"),
+            Some("ASTU")
+        );
+        assert_eq!(symbols.lookup("Carya x collina"), Some("CACO15"));
+        assert_eq!(symbols.lookup("Carya ×collina"), Some("CACO15"));
+        assert_eq!(symbols.lookup("Asclepias"), None);
+        assert_eq!(symbols.lookup("Asclepias incarnata"), None);
+    }
+
+    #[test]
+    fn test_fill_in() {
+        let symbols = symbols();
+
+        let plant = symbols.fill_in(&Plant::new("Aster novae-angliae", "New England Aster"));
+        assert_eq!(plant.usda_symbol, Some("SYNO2".to_string()));
+        assert_eq!(
+            plant.usda_source,
+            Some("https://plants.usda.gov/home/plantProfile?symbol=SYNO2".to_string())
+        );
+
+        // An existing source is kept, and its symbol wins over the lookup
+        let mut plant = Plant::new("Asclepias tuberosa", "Butterfly Weed");
+        plant.usda_source =
+            Some("https://plants.usda.gov/home/plantProfile?symbol=astu2".to_string());
+        let filled = symbols.fill_in(&plant);
+        assert_eq!(filled.usda_symbol, Some("ASTU2".to_string()));
+        assert_eq!(filled.usda_source, plant.usda_source);
+
+        let plant = symbols.fill_in(&Plant::new("Unknown plant", "Unknown"));
+        assert_eq!(plant.usda_symbol, None);
+        assert_eq!(plant.usda_source, None);
+    }
+
+    #[test]
+    fn test_synonyms() {
+        let symbols = symbols();
+
+        let mut plant = Plant::new("Symphyotrichum novae-angliae", "New England Aster");
+        plant.usda_symbol = Some("SYNO2".to_string());
+        assert_eq!(symbols.synonyms(&plant), vec!["Aster novae-angliae"]);
+
+        let mut plant = Plant::new("Asclepias tuberosa", "Butterfly Weed");
+        plant.usda_symbol = Some("ASTU".to_string());
+        assert_eq!(symbols.synonyms(&plant), Vec::<String>::new());
+
+        let plant = Plant::new("Aster novae-angliae", "New England Aster");
+        assert_eq!(symbols.synonyms(&plant), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_builtin_symbols() {
+        let symbols = UsdaSymbols::builtin();
+        assert_eq!(symbols.lookup("Asclepias tuberosa"), Some("ASTU"));
+        assert_eq!(symbols.lookup("Aster novae-angliae"), Some("SYNO2"));
+    }
+}
@@ -2,7 +2,7 @@ export async function getPlants(searchParams, setPlants, setError, selectedPlant
   const { zip, shade, moisture } = searchParams;
   fetch(`${process.env.REACT_APP_URL_PREFIX}/plants?zip=${zip}&shade=${shade}&moisture=${moisture}`)
     .then((response) => response.json())
-    .then((plants) => {
+    .then(({ plants }) => {
       if (selectedPlants !== undefined) {
         for (const plant of plants) {
           plant.selected = selectedPlants.some((sp) => sp.id === plant.id);
@@ -47,5 +47,5 @@ export async function fetchPlantsByName(name) {
       );
     }
     return response.json();
-  });
+  }).then(({ plants }) => plants);
 }